@@ -1,24 +1,171 @@
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 
 #[cfg(test)]
 mod tests;
 
+//Values which can be stored as compact deltas in an undo history
+pub trait Diff {
+    type Delta;
+
+    //Returns the delta which changes this value into the new value
+    fn diff(&self, new: &Self) -> Self::Delta;
+
+    fn apply(&mut self, delta: &Self::Delta);
+    fn revert(&mut self, delta: &Self::Delta);
+}
+
+//Determines how the values of an undo history are stored (An entry is stored for each value)
+pub trait UndoStorage<T>: Sized {
+    type Entry;
+
+    //Returns the storage and the entry of the initial value
+    fn new(initial: T) -> (Self, Self::Entry);
+
+    //The entry is the entry of the current value
+    fn current<'a>(&'a self, entry: &'a Self::Entry) -> &'a T;
+
+    //Returns None if the current value is stored in the entries
+    fn current_mut(&mut self) -> Option<&mut T>;
+
+    //Returns the entry of the value which is committed after the current value
+    fn commit(&mut self, value: T) -> Self::Entry;
+
+    //Changes the value of the entry into the value of the previous entry
+    fn revert(value: &mut T, entry: &Self::Entry, previous_entry: &Self::Entry);
+
+    //Changes the value of the previous entry into the value of the entry
+    fn apply(value: &mut T, entry: &Self::Entry);
+
+    //Returns the entry of the current value if it becomes the initial value
+    fn rebase(&mut self, entry: Self::Entry) -> Self::Entry;
+
+    //Replaces the current value and returns the entry of the new initial value
+    fn reset(&mut self, initial: T) -> Self::Entry;
+}
+
+//Every value is stored as a full copy
+#[derive(Debug)]
+pub struct Snapshots;
+
+impl<T: Clone> UndoStorage<T> for Snapshots {
+    type Entry = T;
+
+    fn new(initial: T) -> (Self, Self::Entry) {
+        (Self, initial)
+    }
+
+    fn current<'a>(&'a self, entry: &'a Self::Entry) -> &'a T {
+        entry
+    }
+
+    fn current_mut(&mut self) -> Option<&mut T> {
+        None
+    }
+
+    fn commit(&mut self, value: T) -> Self::Entry {
+        value
+    }
+
+    fn revert(value: &mut T, _: &Self::Entry, previous_entry: &Self::Entry) {
+        *value = previous_entry.clone();
+    }
+
+    fn apply(value: &mut T, entry: &Self::Entry) {
+        *value = entry.clone();
+    }
+
+    fn rebase(&mut self, entry: Self::Entry) -> Self::Entry {
+        entry
+    }
+
+    fn reset(&mut self, initial: T) -> Self::Entry {
+        initial
+    }
+}
+
+//Only the current value is stored as a full copy, all other values are stored as the delta to their previous value
 #[derive(Debug)]
-pub struct UndoHistory<T> {
-    history: VecDeque<T>,
+pub struct Deltas<T> {
+    current: T,
+}
+
+impl<T: Diff> UndoStorage<T> for Deltas<T> {
+    //The initial value has no delta
+    type Entry = Option<T::Delta>;
+
+    fn new(initial: T) -> (Self, Self::Entry) {
+        (Self { current: initial }, None)
+    }
+
+    fn current<'a>(&'a self, _: &'a Self::Entry) -> &'a T {
+        &self.current
+    }
+
+    fn current_mut(&mut self) -> Option<&mut T> {
+        Some(&mut self.current)
+    }
+
+    fn commit(&mut self, value: T) -> Self::Entry {
+        let delta = self.current.diff(&value);
+        self.current = value;
+
+        Some(delta)
+    }
+
+    fn revert(value: &mut T, entry: &Self::Entry, _: &Self::Entry) {
+        if let Some(delta) = entry {
+            value.revert(delta);
+        }
+    }
+
+    fn apply(value: &mut T, entry: &Self::Entry) {
+        if let Some(delta) = entry {
+            value.apply(delta);
+        }
+    }
+
+    fn rebase(&mut self, _: Self::Entry) -> Self::Entry {
+        None
+    }
+
+    fn reset(&mut self, initial: T) -> Self::Entry {
+        self.current = initial;
+
+        None
+    }
+}
+
+#[derive(Debug)]
+pub struct UndoHistory<T, S: UndoStorage<T> = Snapshots> {
+    storage: S,
+    history: VecDeque<S::Entry>,
     current_index: usize,
 }
 
-impl<T> UndoHistory<T> {
+impl<T: Clone> UndoHistory<T> {
     pub fn new(capacity: usize, initial: T) -> Self {
+        Self::with_storage(capacity, initial)
+    }
+
+    //Iterates from the oldest to the newest value (Including values which can be redone)
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.history.iter()
+    }
+}
+
+impl<T, S: UndoStorage<T>> UndoHistory<T, S> {
+    pub fn with_storage(capacity: usize, initial: T) -> Self {
         if capacity == 0 {
             panic!("Capacity must be > 0");
         }
 
+        let (storage, initial) = S::new(initial);
+
         let mut history = VecDeque::with_capacity(capacity);
         history.push_back(initial);
 
         Self {
+            storage,
             history,
             current_index: 0,
         }
@@ -29,9 +176,13 @@ impl<T> UndoHistory<T> {
             return None;
         }
 
+        if let Some(current) = self.storage.current_mut() {
+            S::revert(current, &self.history[self.current_index], &self.history[self.current_index - 1]);
+        }
+
         self.current_index -= 1;
 
-        self.history.get(self.current_index)
+        Some(self.current())
     }
 
     pub fn redo(&mut self) -> Option<&T> {
@@ -40,24 +191,30 @@ impl<T> UndoHistory<T> {
         }
 
         self.current_index += 1;
-        
-        self.history.get(self.current_index)
+
+        if let Some(current) = self.storage.current_mut() {
+            S::apply(current, &self.history[self.current_index]);
+        }
+
+        Some(self.current())
     }
 
     pub fn commit_change(&mut self, value: T) {
         self.history.truncate(self.current_index + 1);
 
+        let entry = self.storage.commit(value);
+
         if self.history.len() == self.history.capacity() {
             self.history.pop_front();
         }else {
             self.current_index += 1;
         }
 
-        self.history.push_back(value);
+        self.history.push_back(entry);
     }
 
     pub fn current(&self) -> &T {
-        &self.history[self.current_index]
+        self.storage.current(&self.history[self.current_index])
     }
 
     pub fn current_index(&self) -> usize {
@@ -66,14 +223,352 @@ impl<T> UndoHistory<T> {
 
     pub fn clear(&mut self) {
         //Last element of history is current value and should be the new initial value
-        self.history.swap_remove_back(0);
-        self.history.truncate(1);
+        while self.redo().is_some() {}
+
+        let current = self.history.pop_back().unwrap();
+        let initial = self.storage.rebase(current);
+
+        self.history.clear();
+        self.history.push_back(initial);
         self.current_index = 0;
     }
 
     pub fn clear_with_new_initial(&mut self, initial_value: T) {
+        let initial = self.storage.reset(initial_value);
+
         self.history.clear();
-        self.history.push_back(initial_value);
+        self.history.push_back(initial);
+        self.current_index = 0;
+    }
+}
+
+#[derive(Debug)]
+struct UndoTreeNode<E> {
+    entry: E,
+    parent: Option<usize>,
+    children: Vec<usize>,
+
+    //Index of the child which is used for redo
+    selected_child_index: usize,
+}
+
+//Undo history which keeps all branches: Committing a change after undoing creates a new branch
+#[derive(Debug)]
+pub struct UndoTree<T, S: UndoStorage<T> = Snapshots> {
+    storage: S,
+    nodes: HashMap<usize, UndoTreeNode<S::Entry>>,
+    capacity: usize,
+
+    root_id: usize,
+    current_id: usize,
+    latest_id: usize,
+    next_id: usize,
+
+    //Depth of the current node (Updated whenever the current node or the root changes)
+    current_index: usize,
+}
+
+impl<T: Clone> UndoTree<T> {
+    pub fn new(capacity: usize, initial: T) -> Self {
+        Self::with_storage(capacity, initial)
+    }
+}
+
+impl<T, S: UndoStorage<T>> UndoTree<T, S> {
+    pub fn with_storage(capacity: usize, initial: T) -> Self {
+        if capacity == 0 {
+            panic!("Capacity must be > 0");
+        }
+
+        let (storage, initial) = S::new(initial);
+
+        let mut tree = Self {
+            storage,
+            nodes: HashMap::with_capacity(capacity),
+            capacity,
+
+            root_id: 0,
+            current_id: 0,
+            latest_id: 0,
+            next_id: 0,
+
+            current_index: 0,
+        };
+        tree.insert_root(initial);
+
+        tree
+    }
+
+    fn node(&self, id: usize) -> &UndoTreeNode<S::Entry> {
+        &self.nodes[&id]
+    }
+
+    fn node_mut(&mut self, id: usize) -> &mut UndoTreeNode<S::Entry> {
+        self.nodes.get_mut(&id).unwrap()
+    }
+
+    //Returns the IDs from the root to the node
+    fn path(&self, id: usize) -> Vec<usize> {
+        let mut path = vec![id];
+        while let Some(parent_id) = self.node(*path.last().unwrap()).parent {
+            path.push(parent_id);
+        }
+        path.reverse();
+
+        path
+    }
+
+    //Makes the node the current node and selects the path from the root to the node for redo
+    fn select_node(&mut self, id: usize) {
+        let path = self.path(id);
+
+        //Go back to the nearest common ancestor of the current node and the node
+        while !path.contains(&self.current_id) {
+            let parent_id = self.node(self.current_id).parent.unwrap();
+
+            if let Some(current) = self.storage.current_mut() {
+                S::revert(current, &self.nodes[&self.current_id].entry, &self.nodes[&parent_id].entry);
+            }
+
+            self.current_id = parent_id;
+        }
+
+        let common_ancestor_index = path.iter().position(|id| *id == self.current_id).unwrap();
+        for id in path[common_ancestor_index + 1..].iter() {
+            if let Some(current) = self.storage.current_mut() {
+                S::apply(current, &self.nodes[id].entry);
+            }
+        }
+        self.current_id = id;
+        self.current_index = path.len() - 1;
+
+        for ids in path.windows(2) {
+            let parent = self.node_mut(ids[0]);
+            parent.selected_child_index = parent.children.iter().position(|id| *id == ids[1]).unwrap();
+        }
+    }
+
+    pub fn undo(&mut self) -> Option<&T> {
+        let parent_id = self.node(self.current_id).parent?;
+
+        self.select_node(parent_id);
+
+        Some(self.current())
+    }
+
+    pub fn redo(&mut self) -> Option<&T> {
+        let current = self.node(self.current_id);
+        let child_id = *current.children.get(current.selected_child_index)?;
+
+        if let Some(current) = self.storage.current_mut() {
+            S::apply(current, &self.nodes[&child_id].entry);
+        }
+
+        self.current_id = child_id;
+        self.current_index += 1;
+
+        Some(self.current())
+    }
+
+    pub fn commit_change(&mut self, value: T) {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let entry = self.storage.commit(value);
+        self.nodes.insert(id, UndoTreeNode {
+            entry,
+            parent: Some(self.current_id),
+            children: Vec::new(),
+            selected_child_index: 0,
+        });
+
+        let current = self.node_mut(self.current_id);
+        current.children.push(id);
+        current.selected_child_index = current.children.len() - 1;
+
+        self.current_id = id;
+        self.latest_id = id;
+        self.current_index += 1;
+
+        if self.nodes.len() > self.capacity {
+            self.remove_oldest_node();
+        }
+    }
+
+    //The root is removed if it is not part of a branch, otherwise the oldest branch tip is removed
+    fn remove_oldest_node(&mut self) {
+        let root = self.node(self.root_id);
+        if root.children.len() == 1 && self.root_id != self.current_id {
+            let new_root_id = root.children[0];
+
+            self.nodes.remove(&self.root_id);
+            self.root_id = new_root_id;
+            self.current_index -= 1;
+
+            let new_root = self.nodes.remove(&new_root_id).unwrap();
+            let entry = self.storage.rebase(new_root.entry);
+            self.nodes.insert(new_root_id, UndoTreeNode {
+                entry,
+                parent: None,
+                ..new_root
+            });
+        }else {
+            let oldest_tip_id = self.nodes.iter().
+                    filter(|(id, node)| node.children.is_empty() && **id != self.current_id).
+                    map(|(id, _)| *id).
+                    min();
+            let Some(oldest_tip_id) = oldest_tip_id else {
+                return;
+            };
+
+            let oldest_tip = self.nodes.remove(&oldest_tip_id).unwrap();
+            if let Some(parent_id) = oldest_tip.parent {
+                let parent = self.node_mut(parent_id);
+                let index = parent.children.iter().position(|id| *id == oldest_tip_id).unwrap();
+                parent.children.remove(index);
+
+                if parent.selected_child_index > index || parent.selected_child_index == parent.children.len() {
+                    parent.selected_child_index = parent.selected_child_index.saturating_sub(1);
+                }
+            }
+        }
+
+        if !self.nodes.contains_key(&self.latest_id) {
+            self.latest_id = self.current_id;
+        }
+    }
+
+    //Returns the ID of the nearest node (Starting with the current node) which has siblings
+    fn branch_node_id(&self) -> Option<usize> {
+        let mut id = self.current_id;
+        while let Some(parent_id) = self.node(id).parent {
+            if self.node(parent_id).children.len() > 1 {
+                return Some(id);
+            }
+
+            id = parent_id;
+        }
+
+        None
+    }
+
+    //Returns the index of the current branch and the branch count at the nearest branch point
+    pub fn branch_position(&self) -> Option<(usize, usize)> {
+        let branch_node_id = self.branch_node_id()?;
+        let siblings = &self.node(self.node(branch_node_id).parent.unwrap()).children;
+
+        Some((siblings.iter().position(|id| *id == branch_node_id).unwrap(), siblings.len()))
+    }
+
+    fn switch_branch(&mut self, is_next: bool) -> Option<&T> {
+        let branch_node_id = self.branch_node_id()?;
+        let siblings = &self.node(self.node(branch_node_id).parent.unwrap()).children;
+
+        let index = siblings.iter().position(|id| *id == branch_node_id).unwrap();
+        let index = if is_next {
+            index + 1
+        }else {
+            index.checked_sub(1)?
+        };
+        let mut id = *siblings.get(index)?;
+
+        //Go to the tip of the branch
+        loop {
+            let node = self.node(id);
+            let Some(child_id) = node.children.get(node.selected_child_index) else {
+                break;
+            };
+
+            id = *child_id;
+        }
+
+        self.select_node(id);
+
+        Some(self.current())
+    }
+
+    //Goes to the tip of the previous branch at the nearest branch point
+    pub fn previous_branch(&mut self) -> Option<&T> {
+        self.switch_branch(false)
+    }
+
+    //Goes to the tip of the next branch at the nearest branch point
+    pub fn next_branch(&mut self) -> Option<&T> {
+        self.switch_branch(true)
+    }
+
+    //Goes to the most recently committed change
+    pub fn latest_branch_tip(&mut self) -> Option<&T> {
+        if self.latest_id == self.current_id {
+            return None;
+        }
+
+        self.select_node(self.latest_id);
+
+        Some(self.current())
+    }
+
+    pub fn current(&self) -> &T {
+        self.storage.current(&self.node(self.current_id).entry)
+    }
+
+    //Depth of the current value in the tree
+    pub fn current_index(&self) -> usize {
+        self.current_index
+    }
+
+    //Calls the function for each value from the root to the tip of the current branch (Including values which can be redone)
+    pub fn for_each_in_branch(&self, mut f: impl FnMut(&T)) where T: Clone {
+        let path = self.path(self.current_id);
+
+        let mut value = self.current().clone();
+        for ids in path.windows(2).rev() {
+            S::revert(&mut value, &self.node(ids[1]).entry, &self.node(ids[0]).entry);
+        }
+
+        f(&value);
+
+        let mut id = self.root_id;
+        loop {
+            let node = self.node(id);
+            let Some(child_id) = node.children.get(node.selected_child_index) else {
+                break;
+            };
+
+            id = *child_id;
+
+            S::apply(&mut value, &self.node(id).entry);
+            f(&value);
+        }
+    }
+
+    fn insert_root(&mut self, entry: S::Entry) {
+        self.nodes.clear();
+        self.nodes.insert(self.next_id, UndoTreeNode {
+            entry,
+            parent: None,
+            children: Vec::new(),
+            selected_child_index: 0,
+        });
+
+        self.root_id = self.next_id;
+        self.current_id = self.next_id;
+        self.latest_id = self.next_id;
+        self.next_id += 1;
         self.current_index = 0;
     }
+
+    pub fn clear(&mut self) {
+        //Current value should be the new initial value
+        let current = self.nodes.remove(&self.current_id).unwrap();
+        let initial = self.storage.rebase(current.entry);
+
+        self.insert_root(initial);
+    }
+
+    pub fn clear_with_new_initial(&mut self, initial_value: T) {
+        let initial = self.storage.reset(initial_value);
+
+        self.insert_root(initial);
+    }
 }
@@ -495,3 +495,312 @@ fn clear_with_new_initial() {
     assert_eq!(undo_history.history.capacity(), 5);
     assert_eq!(undo_history.current_index, 0);
 }
+
+#[test]
+fn iter() {
+    let mut undo_history = UndoHistory::new(3, 1);
+    assert_eq!(undo_history.iter().copied().collect::<Vec<_>>(), vec![1]);
+
+    undo_history.commit_change(2);
+    undo_history.commit_change(3);
+    undo_history.undo();
+    assert_eq!(undo_history.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3]);
+
+    undo_history.redo();
+    undo_history.commit_change(4);
+    assert_eq!(undo_history.iter().copied().collect::<Vec<_>>(), vec![2, 3, 4]);
+}
+
+#[test]
+#[should_panic(expected = "Capacity must be > 0")]
+fn undo_tree_invalid_capacity() {
+    UndoTree::<()>::new(0, ());
+}
+
+#[test]
+fn undo_tree_linear_history() {
+    let mut undo_tree = UndoTree::new(5, 1);
+    assert_eq!(undo_tree.current(), &1);
+    assert_eq!(undo_tree.current_index(), 0);
+    assert_eq!(undo_tree.undo(), None);
+    assert_eq!(undo_tree.redo(), None);
+
+    undo_tree.commit_change(2);
+    undo_tree.commit_change(3);
+    assert_eq!(undo_tree.current(), &3);
+    assert_eq!(undo_tree.current_index(), 2);
+
+    assert_eq!(undo_tree.undo(), Some(&2));
+    assert_eq!(undo_tree.undo(), Some(&1));
+    assert_eq!(undo_tree.undo(), None);
+    assert_eq!(undo_tree.current_index(), 0);
+
+    assert_eq!(undo_tree.redo(), Some(&2));
+    assert_eq!(undo_tree.redo(), Some(&3));
+    assert_eq!(undo_tree.redo(), None);
+
+    assert_eq!(branch(&undo_tree), vec![1, 2, 3]);
+    assert_eq!(undo_tree.branch_position(), None);
+    assert_eq!(undo_tree.previous_branch(), None);
+    assert_eq!(undo_tree.next_branch(), None);
+}
+
+#[test]
+fn undo_tree_keeps_branches() {
+    let mut undo_tree = UndoTree::new(10, 1);
+    undo_tree.commit_change(2);
+    undo_tree.commit_change(3);
+
+    undo_tree.undo();
+    undo_tree.commit_change(4);
+    undo_tree.commit_change(5);
+    assert_eq!(branch(&undo_tree), vec![1, 2, 4, 5]);
+    assert_eq!(undo_tree.branch_position(), Some((1, 2)));
+
+    //The old branch is not removed
+    assert_eq!(undo_tree.previous_branch(), Some(&3));
+    assert_eq!(undo_tree.current_index(), 2);
+    assert_eq!(undo_tree.branch_position(), Some((0, 2)));
+    assert_eq!(branch(&undo_tree), vec![1, 2, 3]);
+    assert_eq!(undo_tree.previous_branch(), None);
+
+    //Switching goes to the tip of the branch
+    assert_eq!(undo_tree.next_branch(), Some(&5));
+    assert_eq!(undo_tree.next_branch(), None);
+
+    //Redo follows the selected branch
+    undo_tree.undo();
+    undo_tree.undo();
+    assert_eq!(undo_tree.current(), &2);
+    assert_eq!(undo_tree.redo(), Some(&4));
+
+    assert_eq!(undo_tree.previous_branch(), Some(&3));
+    undo_tree.undo();
+    assert_eq!(undo_tree.redo(), Some(&3));
+}
+
+#[test]
+fn undo_tree_latest_branch_tip() {
+    let mut undo_tree = UndoTree::new(10, 1);
+    assert_eq!(undo_tree.latest_branch_tip(), None);
+
+    undo_tree.commit_change(2);
+    undo_tree.commit_change(3);
+    undo_tree.undo();
+    undo_tree.undo();
+    undo_tree.commit_change(4);
+    undo_tree.commit_change(5);
+
+    undo_tree.previous_branch();
+    assert_eq!(undo_tree.current(), &3);
+
+    assert_eq!(undo_tree.latest_branch_tip(), Some(&5));
+    assert_eq!(undo_tree.latest_branch_tip(), None);
+    assert_eq!(branch(&undo_tree), vec![1, 4, 5]);
+
+    undo_tree.undo();
+    undo_tree.undo();
+    assert_eq!(undo_tree.latest_branch_tip(), Some(&5));
+}
+
+#[test]
+fn undo_tree_capacity() {
+    let mut undo_tree = UndoTree::new(3, 1);
+    undo_tree.commit_change(2);
+    undo_tree.commit_change(3);
+    undo_tree.commit_change(4);
+    assert_eq!(undo_tree.nodes.len(), 3);
+    assert_eq!(branch(&undo_tree), vec![2, 3, 4]);
+    assert_eq!(undo_tree.current_index(), 2);
+
+    //The oldest branch tip is removed if the root is a branch point
+    undo_tree.undo();
+    undo_tree.undo();
+    undo_tree.commit_change(5);
+    assert_eq!(undo_tree.nodes.len(), 3);
+    assert_eq!(branch(&undo_tree), vec![2, 5]);
+    assert_eq!(undo_tree.branch_position(), Some((1, 2)));
+
+    undo_tree.undo();
+    assert_eq!(undo_tree.redo(), Some(&5));
+
+    undo_tree.commit_change(6);
+    assert_eq!(undo_tree.nodes.len(), 3);
+    assert_eq!(branch(&undo_tree), vec![2, 5, 6]);
+    assert_eq!(undo_tree.branch_position(), None);
+
+    let mut undo_tree = UndoTree::new(1, 1);
+    undo_tree.commit_change(2);
+    assert_eq!(undo_tree.current(), &2);
+    assert_eq!(undo_tree.undo(), None);
+}
+
+#[test]
+fn undo_tree_clear() {
+    let mut undo_tree = UndoTree::new(5, 1);
+    undo_tree.commit_change(2);
+    undo_tree.commit_change(3);
+    undo_tree.undo();
+    undo_tree.commit_change(4);
+
+    undo_tree.clear();
+    assert_eq!(undo_tree.current(), &4);
+    assert_eq!(undo_tree.nodes.len(), 1);
+    assert_eq!(undo_tree.undo(), None);
+    assert_eq!(undo_tree.latest_branch_tip(), None);
+
+    undo_tree.commit_change(5);
+    undo_tree.clear_with_new_initial(21);
+    assert_eq!(undo_tree.current(), &21);
+    assert_eq!(undo_tree.nodes.len(), 1);
+    assert_eq!(undo_tree.redo(), None);
+}
+
+fn branch<T: Clone, S: UndoStorage<T>>(undo_tree: &UndoTree<T, S>) -> Vec<T> {
+    let mut values = Vec::new();
+    undo_tree.for_each_in_branch(|value| values.push(value.clone()));
+
+    values
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Cells(Vec<u8>);
+
+impl Diff for Cells {
+    type Delta = Vec<(usize, u8, u8)>;
+
+    fn diff(&self, new: &Self) -> Self::Delta {
+        self.0.iter().
+                zip(new.0.iter()).
+                enumerate().
+                filter(|(_, (old_cell, new_cell))| old_cell != new_cell).
+                map(|(i, (old_cell, new_cell))| (i, *old_cell, *new_cell)).
+                collect()
+    }
+
+    fn apply(&mut self, delta: &Self::Delta) {
+        for (i, _, new_cell) in delta {
+            self.0[*i] = *new_cell;
+        }
+    }
+
+    fn revert(&mut self, delta: &Self::Delta) {
+        for (i, old_cell, _) in delta {
+            self.0[*i] = *old_cell;
+        }
+    }
+}
+
+//Deterministic sequence of operations (Linear congruential generator)
+struct Operations(u64);
+
+impl Operations {
+    fn next(&mut self, count: u64) -> u64 {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+
+        (self.0 >> 33) % count
+    }
+
+    fn next_cells(&mut self, cells: &Cells) -> Cells {
+        let mut cells = cells.clone();
+        let i = self.next(cells.0.len() as u64) as usize;
+        cells.0[i] = self.next(256) as u8;
+
+        cells
+    }
+}
+
+#[test]
+fn deltas_delta_storage() {
+    let mut undo_history = UndoHistory::<Cells, Deltas<Cells>>::with_storage(5, Cells(vec![0; 4]));
+    undo_history.commit_change(Cells(vec![1, 0, 0, 0]));
+    undo_history.commit_change(Cells(vec![1, 2, 0, 0]));
+
+    assert_eq!(undo_history.history.len(), 3);
+    assert!(undo_history.history[0].is_none());
+    assert_eq!(undo_history.history[2], Some(vec![(1, 0, 2)]));
+
+    undo_history.undo();
+    undo_history.clear();
+    assert_eq!(undo_history.current(), &Cells(vec![1, 2, 0, 0]));
+    assert_eq!(undo_history.history.len(), 1);
+    assert!(undo_history.history[0].is_none());
+}
+
+#[test]
+fn undo_history_snapshots_and_deltas_are_equivalent() {
+    for seed in 0..20 {
+        let mut operations = Operations(seed);
+
+        let initial = Cells(vec![0; 6]);
+        let mut snapshots = UndoHistory::<Cells, Snapshots>::with_storage(8, initial.clone());
+        let mut deltas = UndoHistory::<Cells, Deltas<Cells>>::with_storage(8, initial);
+
+        for _ in 0..500 {
+            match operations.next(10) {
+                0..=3 => {
+                    let value = operations.next_cells(snapshots.current());
+                    snapshots.commit_change(value.clone());
+                    deltas.commit_change(value);
+                },
+                4..=6 => assert_eq!(snapshots.undo(), deltas.undo()),
+                7 | 8 => assert_eq!(snapshots.redo(), deltas.redo()),
+                _ if operations.next(4) == 0 => {
+                    snapshots.clear();
+                    deltas.clear();
+                },
+                _ => {
+                    let value = operations.next_cells(snapshots.current());
+                    snapshots.clear_with_new_initial(value.clone());
+                    deltas.clear_with_new_initial(value);
+                },
+            }
+
+            assert_eq!(snapshots.current(), deltas.current());
+            assert_eq!(snapshots.current_index(), deltas.current_index());
+            assert_eq!(snapshots.history.len(), deltas.history.len());
+        }
+    }
+}
+
+#[test]
+fn undo_tree_snapshots_and_deltas_are_equivalent() {
+    for seed in 0..20 {
+        let mut operations = Operations(seed);
+
+        let initial = Cells(vec![0; 6]);
+        let mut snapshots = UndoTree::<Cells, Snapshots>::with_storage(12, initial.clone());
+        let mut deltas = UndoTree::<Cells, Deltas<Cells>>::with_storage(12, initial);
+
+        for _ in 0..500 {
+            match operations.next(16) {
+                0..=4 => {
+                    let value = operations.next_cells(snapshots.current());
+                    snapshots.commit_change(value.clone());
+                    deltas.commit_change(value);
+                },
+                5..=7 => assert_eq!(snapshots.undo(), deltas.undo()),
+                8 | 9 => assert_eq!(snapshots.redo(), deltas.redo()),
+                10 => assert_eq!(snapshots.previous_branch(), deltas.previous_branch()),
+                11 => assert_eq!(snapshots.next_branch(), deltas.next_branch()),
+                12 => assert_eq!(snapshots.latest_branch_tip(), deltas.latest_branch_tip()),
+                13 | 14 => {},
+                _ if operations.next(4) == 0 => {
+                    snapshots.clear();
+                    deltas.clear();
+                },
+                _ => {
+                    let value = operations.next_cells(snapshots.current());
+                    snapshots.clear_with_new_initial(value.clone());
+                    deltas.clear_with_new_initial(value);
+                },
+            }
+
+            assert_eq!(snapshots.current(), deltas.current());
+            assert_eq!(snapshots.current_index(), deltas.current_index());
+            assert_eq!(snapshots.current_index(), snapshots.path(snapshots.current_id).len() - 1);
+            assert_eq!(snapshots.branch_position(), deltas.branch_position());
+            assert_eq!(branch(&snapshots), branch(&deltas));
+        }
+    }
+}
@@ -1,33 +1,133 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::error::Error;
-use std::ffi::OsString;
-use std::fmt::{Debug, Display, Formatter};
+use std::fmt::{Debug, Display, Formatter, Write as _};
 use std::mem;
 use std::fs::File;
-use std::io::Read;
-use std::path::Path;
-use crate::game::audio::AudioHandler;
+use std::cell::Cell;
+use std::io::{Read, Write as _};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::game::achievement::{Achievement, AchievementError, AchievementEvent, Achievements};
+use crate::game::audio::{AudioBackend, AudioHandler, Music, SoundCategory, SoundEvent, SoundPack, SoundPackError};
 use crate::game::help_page::HelpPage;
-use crate::game::level::{Level, LevelPack, Tile};
-use crate::game::screen::{Screen, ScreenId, ScreenInGame, ScreenLevelEditor, ScreenLevelPackEditor, ScreenSelectLevel, ScreenSelectLevelPack, ScreenSelectLevelPackEditor, ScreenStartMenu};
-use crate::game::screen::dialog::{Dialog, DialogType};
-use crate::io::{Console, Key};
+use crate::game::input::Action;
+use crate::game::history::{Attempt, AttemptResult};
+use crate::game::level::{Level, LevelPack};
+use crate::game::profile::Profiles;
+use crate::game::settings::{FeedbackMode, Settings, SettingsError};
+use crate::game::save_game::{LevelChange, SaveGameError};
+use crate::game::screen::{Screen, ScreenId, ScreenInGame, ScreenLevelEditor, ScreenLevelPackEditor, ScreenLevelPackRepair, ScreenSelectLevel, ScreenSelectLevelPack, ScreenSelectLevelPackEditor, ScreenStartMenu, ScreenStatistics, ScreenAchievements, ScreenSettings};
+use crate::game::suspend::{SuspendError, SuspendedLevel};
+use crate::game::screen::dialog::{Dialog, DialogOk, DialogType};
+use crate::io::{Color, Console, Key};
 
 mod level;
+mod data_dir;
+mod profile;
+mod save_game;
+mod history;
+mod statistics;
+mod achievement;
+mod input;
+mod settings;
+mod suspend;
 mod screen;
 mod help_page;
 mod audio;
 
+#[cfg(test)]
+mod test_utils;
+
+//Level packs which could not be loaded are kept in order to show their errors instead of refusing to start
+struct BrokenLevelPack {
+    id: String,
+    path: String,
+    lvl_data: Option<String>,
+    error: String,
+}
+
+impl BrokenLevelPack {
+    pub fn new(id: impl Into<String>, path: impl Into<String>, lvl_data: Option<String>, error: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            path: path.into(),
+            lvl_data,
+            error: error.into(),
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn lvl_data(&self) -> Option<&str> {
+        self.lvl_data.as_deref()
+    }
+
+    pub fn error(&self) -> &str {
+        &self.error
+    }
+
+    pub fn set_lvl_data(&mut self, lvl_data: impl Into<String>, error: impl Into<String>) {
+        self.lvl_data = Some(lvl_data.into());
+        self.error = error.into();
+    }
+}
+
+//Visual feedback of a sound effect (Shown by the flash and the event ticker feedback modes)
+#[derive(Debug, Clone, Copy)]
+struct Feedback {
+    text: &'static str,
+    category: SoundCategory,
+
+    update_count: usize,
+}
+
+//Corrupt save games are reported at the start menu one after another
+struct SaveGameWarning {
+    level_pack_id: String,
+    error: SaveGameError,
+    has_backup: bool,
+}
+
+impl SaveGameWarning {
+    pub fn new(level_pack_id: impl Into<String>, error: SaveGameError, has_backup: bool) -> Self {
+        Self {
+            level_pack_id: level_pack_id.into(),
+            error,
+            has_backup,
+        }
+    }
+
+    pub fn level_pack_id(&self) -> &str {
+        &self.level_pack_id
+    }
+
+    pub fn error(&self) -> &SaveGameError {
+        &self.error
+    }
+
+    pub fn has_backup(&self) -> bool {
+        self.has_backup
+    }
+}
+
 struct EditorState {
     level_packs: Vec<LevelPack>,
+    broken_level_packs: Vec<BrokenLevelPack>,
     selected_level_pack_index: usize,
     selected_level_index: usize,
 }
 
 impl EditorState {
-    pub fn new(level_packs: Vec<LevelPack>) -> Self {
+    pub fn new(level_packs: Vec<LevelPack>, broken_level_packs: Vec<BrokenLevelPack>) -> Self {
         Self {
             level_packs,
+            broken_level_packs,
             selected_level_pack_index: Default::default(),
             selected_level_index: Default::default(),
         }
@@ -37,6 +137,16 @@ impl EditorState {
         self.level_packs.len()
     }
 
+    //Broken level packs are listed after the level packs
+    pub fn get_level_pack_entry_count(&self) -> usize {
+        self.level_packs.len() + self.broken_level_packs.len()
+    }
+
+    pub fn has_level_pack_id(&self, id: &str) -> bool {
+        self.level_packs.iter().any(|level_pack| level_pack.id() == id) ||
+                self.broken_level_packs.iter().any(|level_pack| level_pack.id() == id)
+    }
+
     pub fn get_level_pack_index(&self) -> usize {
         self.selected_level_pack_index
     }
@@ -49,6 +159,44 @@ impl EditorState {
         self.level_packs.get_mut(self.selected_level_pack_index)
     }
 
+    pub fn get_current_broken_level_pack(&self) -> Option<&BrokenLevelPack> {
+        self.selected_level_pack_index.checked_sub(self.level_packs.len()).
+                and_then(|index| self.broken_level_packs.get(index))
+    }
+
+    pub fn get_current_broken_level_pack_mut(&mut self) -> Option<&mut BrokenLevelPack> {
+        self.selected_level_pack_index.checked_sub(self.level_packs.len()).
+                and_then(|index| self.broken_level_packs.get_mut(index))
+    }
+
+    //Returns the index of the repaired level pack
+    pub fn replace_current_broken_level_pack(&mut self, level_pack: LevelPack) -> Option<usize> {
+        let broken_index = self.selected_level_pack_index.checked_sub(self.level_packs.len())?;
+        if broken_index >= self.broken_level_packs.len() {
+            return None;
+        }
+
+        self.broken_level_packs.remove(broken_index);
+
+        let index = self.level_packs.binary_search_by_key(
+            &level_pack.id().to_string(),
+            |level_pack| level_pack.id().to_string(),
+        ).unwrap_or_else(|index| index);
+
+        self.level_packs.insert(index, level_pack);
+
+        Some(index)
+    }
+
+    pub fn remove_current_broken_level_pack(&mut self) -> Option<BrokenLevelPack> {
+        let broken_index = self.selected_level_pack_index.checked_sub(self.level_packs.len())?;
+        if broken_index >= self.broken_level_packs.len() {
+            return None;
+        }
+
+        Some(self.broken_level_packs.remove(broken_index))
+    }
+
     pub fn set_level_pack_index(&mut self, level_pack_index: usize) {
         self.selected_level_pack_index = level_pack_index;
     }
@@ -77,6 +225,10 @@ impl EditorState {
 struct GameState {
     current_screen_id: ScreenId,
     should_call_on_set_screen: bool,
+    should_call_on_continue: bool,
+
+    //Screen which is shown again if the settings screen is closed
+    settings_return_screen_id: ScreenId,
 
     is_help: bool,
     dialog: Option<Box<dyn Dialog>>,
@@ -91,18 +243,51 @@ struct GameState {
 
     found_secret_main_level_pack: bool,
 
+    //Errors of loading the game are shown one after another in the start menu
+    startup_errors: Vec<String>,
+    save_game_warnings: Vec<SaveGameWarning>,
+
+    settings: Settings,
+
+    achievements: Result<Achievements, AchievementError>,
+    achievement_notifications: VecDeque<Achievement>,
+    achievement_notification_update_count: usize,
+
+    suspended_level: Option<SuspendedLevel>,
+    is_resuming_suspended_level: bool,
+
     should_exit: bool,
 
     editor_state: EditorState,
 
-    audio_handler: Option<AudioHandler>,
+    //None: No audio device exists
+    audio_backend: Option<Box<dyn AudioBackend>>,
+
+    //Set by play_sound_event() which only borrows the game state immutably
+    feedback: Cell<Option<Feedback>>,
+
+    //The bell is written after the screen was drawn (Writing it while drawing would interleave it with the output of the console)
+    is_bell_pending: Cell<bool>,
+
+    //Chooses between the sound effects of an event (Xorshift)
+    random_state: Cell<u64>,
 }
 
 impl GameState {
-    fn new(level_packs: Vec<LevelPack>, editor_level_packs: Vec<LevelPack>) -> Self {
+    fn new(level_packs: Vec<LevelPack>, editor_level_packs: Vec<LevelPack>, broken_editor_level_packs: Vec<BrokenLevelPack>,
+           audio_backend: Option<Box<dyn AudioBackend>>) -> Self {
+        //The state of xorshift must not be 0
+        let random_seed = SystemTime::now().duration_since(UNIX_EPOCH).
+                map(|time| time.as_nanos() as u64).
+                unwrap_or_default() | 1;
+
+
         Self {
             current_screen_id: ScreenId::StartMenu,
             should_call_on_set_screen: Default::default(),
+            should_call_on_continue: Default::default(),
+
+            settings_return_screen_id: ScreenId::StartMenu,
 
             is_help: Default::default(),
             dialog: Default::default(),
@@ -117,11 +302,29 @@ impl GameState {
 
             found_secret_main_level_pack: Default::default(),
 
+            startup_errors: Default::default(),
+            save_game_warnings: Default::default(),
+
+            settings: Default::default(),
+
+            achievements: Ok(Default::default()),
+            achievement_notifications: Default::default(),
+            achievement_notification_update_count: Default::default(),
+
+            suspended_level: Default::default(),
+            is_resuming_suspended_level: Default::default(),
+
             should_exit: Default::default(),
 
-            editor_state: EditorState::new(editor_level_packs),
+            editor_state: EditorState::new(editor_level_packs, broken_editor_level_packs),
 
-            audio_handler: AudioHandler::new().ok(),
+            audio_backend,
+
+            feedback: Default::default(),
+
+            is_bell_pending: Default::default(),
+
+            random_state: Cell::new(random_seed),
         }
     }
 
@@ -166,14 +369,101 @@ impl GameState {
         self.is_player_background
     }
 
+    //The current screen is shown again without calling on_set_screen if the settings screen is closed
+    pub fn open_settings(&mut self) {
+        self.settings_return_screen_id = self.current_screen_id.clone();
+
+        self.set_screen(ScreenId::Settings);
+    }
+
+    pub fn close_settings(&mut self) {
+        self.current_screen_id = self.settings_return_screen_id.clone();
+        self.should_call_on_continue = true;
+    }
+
+    fn settings_file() -> Result<PathBuf, Box<dyn Error>> {
+        Ok(Game::get_or_create_config_folder()?.join(Game::SETTINGS_FILE))
+    }
+
+    //The default settings are used if the settings could not be loaded
+    fn load_settings(&mut self) -> Result<(), SettingsError> {
+        self.settings = Settings::default();
+
+        let settings_file = Self::settings_file().
+                map_err(|err| SettingsError::new(format!("Cannot load settings: {err}")))?;
+
+        self.settings = Settings::read(&settings_file)?;
+
+        Ok(())
+    }
+
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    //The embedded sound effects are used if the sound pack could not be loaded (Invalid files of the sound pack are reported as an error)
+    fn load_sound_pack(&mut self) -> Result<(), Box<dyn Error>> {
+        let Some(audio_backend) = &self.audio_backend else {
+            return Ok(());
+        };
+        audio_backend.set_sound_pack(None)?;
+
+        let Some(name) = self.settings.sound_pack() else {
+            return Ok(());
+        };
+
+        let sound_pack = SoundPack::load(&Game::get_or_create_sound_pack_folder()?.join(name))?;
+        let invalid_files = sound_pack.invalid_files().join("\n");
+
+        audio_backend.set_sound_pack(Some(sound_pack))?;
+
+        if !invalid_files.is_empty() {
+            return Err(Box::new(SoundPackError::new(format!("Invalid files in sound pack \"{}\":\n{}", name, invalid_files))));
+        }
+
+        Ok(())
+    }
+
+    //The settings are changed even if they could not be saved
+    pub fn set_settings(&mut self, settings: Settings) -> Result<(), Box<dyn Error>> {
+        let is_sound_pack_changed = self.settings.sound_pack() != settings.sound_pack();
+        self.settings = settings;
+
+        if is_sound_pack_changed && let Err(err) = self.load_sound_pack() {
+            self.open_dialog(Box::new(DialogOk::new_error(format!("Cannot load sound pack: {}", err))));
+        }
+
+        self.settings.write(&Self::settings_file()?)
+    }
+
+    pub fn key_action(&self, key: Key) -> Option<Action> {
+        self.settings.key_bindings().action(key)
+    }
+
+    //Keys which are bound to navigation actions are mapped to the menu key of the action (Used by screens without actions)
+    pub fn menu_key(&self, key: Key) -> Key {
+        self.key_action(key).
+                and_then(|action| action.menu_key()).
+                unwrap_or(key)
+    }
+
+    //Keys which are used for text input are never mapped (Used by screens with text input)
+    pub fn text_input_menu_key(&self, key: Key) -> Key {
+        if input::is_text_key(key) {
+            key
+        }else {
+            self.menu_key(key)
+        }
+    }
+
     pub fn open_help_page(&mut self) {
-        self.play_sound_effect(audio::BOOK_OPEN_EFFECT);
+        self.play_sound_event(SoundEvent::BookOpen);
 
         self.is_help = true;
     }
 
     pub fn close_help_page(&mut self) {
-        self.play_sound_effect(audio::UI_SELECT_EFFECT);
+        self.play_sound_event(SoundEvent::UiSelect);
 
         self.is_help = false;
     }
@@ -195,7 +485,7 @@ impl GameState {
                 self.play_sound_effect_ui_error();
             },
             DialogType::SecretFound => {
-                self.play_sound_effect(audio::SECRET_FOUND_EFFECT);
+                self.play_sound_event(SoundEvent::SecretFound);
             },
         }
     }
@@ -215,7 +505,11 @@ impl GameState {
             let secret_level_pack = LevelPack::read_from_save_game("secret", "build-in:secret", Game::MAP_SECRET)?;
 
             //Save immediately in order to keep secret level pack after game restart if not yet played
-            secret_level_pack.save_save_game()?;
+            //(An existing save game is not overwritten, because it might be corrupt and must be kept for the backup)
+            let save_game_file = Game::get_or_create_save_game_folder()?.join("secret.lvl.sav");
+            if !std::fs::exists(&save_game_file)? {
+                secret_level_pack.save_save_game()?;
+            }
 
             self.level_packs.insert(4, secret_level_pack);
         }
@@ -223,27 +517,445 @@ impl GameState {
         Ok(())
     }
 
+    //Must be called after the save games of the current profile were loaded
+    fn load_found_secrets(&mut self) -> Result<(), Box<dyn Error>> {
+        let save_game_file = Game::get_or_create_save_game_folder()?.join("secret.lvl.sav");
+        if std::fs::exists(&save_game_file).is_ok_and(|exists| exists) {
+            self.on_found_secret_for_level_pack(1)?;
+        }
+
+        Ok(())
+    }
+
+    fn collect_save_game_warnings(&mut self) {
+        self.save_game_warnings.clear();
+
+        for level_pack in self.level_packs.iter_mut() {
+            if let Some(err) = level_pack.take_save_game_error() {
+                self.save_game_warnings.push(SaveGameWarning::new(level_pack.id(), err, level_pack.has_save_game_backup()));
+            }
+        }
+    }
+
+    fn load_achievements(&mut self) {
+        self.achievements = Game::get_or_create_save_game_folder().
+                map_err(|err| AchievementError::new(format!("Cannot load achievements: {err}"))).
+                and_then(|save_game_folder| Achievements::read(&save_game_folder.join(Game::ACHIEVEMENTS_FILE)));
+
+        self.achievement_notifications.clear();
+        self.achievement_notification_update_count = 0;
+    }
+
+    //Achievements are not tracked if they could not be loaded (The achievements file must not be overwritten)
+    pub fn achievements(&self) -> Result<&Achievements, &AchievementError> {
+        self.achievements.as_ref()
+    }
+
+    pub fn on_achievement_event(&mut self, event: AchievementEvent) {
+        let Ok(achievements) = &mut self.achievements else {
+            return;
+        };
+
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).
+                map_or(0, |duration| duration.as_secs());
+        let unlocked_achievements = achievements.on_event(event, timestamp);
+
+        let result = Game::get_or_create_save_game_folder().
+                and_then(|save_game_folder| achievements.write(&save_game_folder.join(Game::ACHIEVEMENTS_FILE)));
+        if let Err(err) = result {
+            self.open_dialog(Box::new(DialogOk::new_error(format!("Cannot save achievements: {}", err))));
+        }
+
+        if !unlocked_achievements.is_empty() {
+            self.play_sound_event(SoundEvent::AchievementUnlocked);
+
+            self.achievement_notifications.extend(unlocked_achievements);
+        }
+    }
+
+    fn update_achievement_notifications(&mut self) {
+        if self.achievement_notifications.is_empty() {
+            return;
+        }
+
+        self.achievement_notification_update_count += 1;
+        if self.achievement_notification_update_count >= Game::ACHIEVEMENT_NOTIFICATION_DURATION {
+            self.achievement_notification_update_count = 0;
+
+            self.achievement_notifications.pop_front();
+        }
+    }
+
+    fn suspended_level_file() -> Result<PathBuf, Box<dyn Error>> {
+        Ok(Game::get_or_create_save_game_folder()?.join(Game::SUSPENDED_LEVEL_FILE))
+    }
+
+    fn load_suspended_level(&mut self) -> Result<(), SuspendError> {
+        self.is_resuming_suspended_level = false;
+        self.suspended_level = None;
+
+        let suspended_level_file = Self::suspended_level_file().
+                map_err(|err| SuspendError::new(format!("Cannot load suspended level: {err}")))?;
+
+        self.suspended_level = SuspendedLevel::read(&suspended_level_file)?;
+
+        Ok(())
+    }
+
+    pub fn suspended_level(&self) -> Option<&SuspendedLevel> {
+        self.suspended_level.as_ref()
+    }
+
+    //Only one level can be suspended: An already suspended level is replaced
+    pub fn suspend_level(&mut self, suspended_level: SuspendedLevel) -> Result<(), Box<dyn Error>> {
+        suspended_level.write(&Self::suspended_level_file()?)?;
+
+        self.suspended_level = Some(suspended_level);
+
+        Ok(())
+    }
+
+    fn remove_suspended_level(&mut self) -> Result<Option<SuspendedLevel>, Box<dyn Error>> {
+        let Some(suspended_level) = self.suspended_level.take() else {
+            return Ok(None);
+        };
+
+        let suspended_level_file = Self::suspended_level_file()?;
+        if std::fs::exists(&suspended_level_file)? {
+            std::fs::remove_file(suspended_level_file)?;
+        }
+
+        Ok(Some(suspended_level))
+    }
+
+    //The attempt of the suspended level is recorded as abandoned
+    pub fn abandon_suspended_level(&mut self) -> Result<(), Box<dyn Error>> {
+        let Some(suspended_level) = self.remove_suspended_level()? else {
+            return Ok(());
+        };
+
+        let Some(level_pack) = self.level_packs.iter().
+                find(|level_pack| level_pack.id() == suspended_level.level_pack_id()) else {
+            return Ok(());
+        };
+
+        let is_level_unchanged = level_pack.levels().get(suspended_level.level_index()).
+                is_some_and(|level| level.level().content_hash() == suspended_level.level_hash());
+        if !is_level_unchanged {
+            return Ok(());
+        }
+
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).
+                map_or(0, |duration| duration.as_secs());
+        let current_state = &suspended_level.states()[suspended_level.current_state_index()];
+
+        level_pack.append_attempt(suspended_level.level_index(), &Attempt::new(
+            timestamp,
+            suspended_level.time(),
+            current_state.moves(),
+            current_state.pushes(),
+            suspended_level.undo_count(),
+            suspended_level.reset_count(),
+            AttemptResult::Abandoned,
+        ))
+    }
+
+    //Selects the level of the suspended level and opens the in-game screen
+    pub fn resume_suspended_level(&mut self) -> Result<(), Box<dyn Error>> {
+        let Some(suspended_level) = self.suspended_level.as_ref() else {
+            return Err(Box::new(GameError::new("No level is suspended")));
+        };
+
+        //External level packs are only loaded if they are passed as arguments: The suspended level is kept
+        let Some(level_pack_index) = self.level_packs.iter().
+                position(|level_pack| level_pack.id() == suspended_level.level_pack_id()) else {
+            return Err(Box::new(GameError::new(format!(
+                "The level pack \"{}\" is not loaded (Start the game with the level pack in order to resume the level)",
+                suspended_level.level_pack_id(),
+            ))));
+        };
+
+        let is_level_unchanged = self.level_packs[level_pack_index].levels().get(suspended_level.level_index()).
+                is_some_and(|level| level.level().content_hash() == suspended_level.level_hash());
+        if !is_level_unchanged {
+            self.remove_suspended_level()?;
+
+            return Err(Box::new(GameError::new("The suspended level was changed or removed")));
+        }
+
+        self.current_level_pack_index = level_pack_index;
+        self.current_level_index = suspended_level.level_index();
+        self.is_resuming_suspended_level = true;
+
+        self.set_screen(ScreenId::InGame);
+
+        Ok(())
+    }
+
+    //Returns the suspended level if it is resumed (The suspended level is removed)
+    pub fn take_resuming_suspended_level(&mut self) -> Result<Option<SuspendedLevel>, Box<dyn Error>> {
+        if !mem::replace(&mut self.is_resuming_suspended_level, false) {
+            return Ok(None);
+        }
+
+        self.remove_suspended_level()
+    }
+
+    pub fn current_profile(&self) -> String {
+        profile::current_profile()
+    }
+
+    pub fn profile_names(&self) -> Result<Vec<String>, Box<dyn Error>> {
+        Profiles::from_data_dirs()?.profile_names()
+    }
+
+    //Stats, found secrets and save game warnings are replaced with the data of the selected profile
+    //(Editor level packs are shared between all profiles)
+    pub fn select_profile(&mut self, name: &str) -> Result<(), Box<dyn Error>> {
+        Profiles::from_data_dirs()?.select_profile(name)?;
+
+        if self.found_secret_main_level_pack {
+            self.found_secret_main_level_pack = false;
+
+            self.level_packs.retain(|level_pack| level_pack.id() != "secret");
+        }
+
+        for level_pack in self.level_packs.iter_mut() {
+            level_pack.reload_save_game()?;
+        }
+
+        self.load_found_secrets()?;
+        self.collect_save_game_warnings();
+        self.load_achievements();
+        if let Err(err) = self.load_settings() {
+            self.open_dialog(Box::new(DialogOk::new_error(format!("Cannot load settings: {}", err))));
+        }
+        if let Err(err) = self.load_sound_pack() {
+            self.open_dialog(Box::new(DialogOk::new_error(format!("Cannot load sound pack: {}", err))));
+        }
+        if let Err(err) = self.load_suspended_level() {
+            self.open_dialog(Box::new(DialogOk::new_error(format!("Cannot resume level: {}", err))));
+        }
+
+        self.current_level_pack_index = 0;
+        self.current_level_index = 0;
+
+        Ok(())
+    }
+
+    pub fn create_profile(&mut self, name: &str) -> Result<(), Box<dyn Error>> {
+        Profiles::from_data_dirs()?.create_profile(name)
+    }
+
+    pub fn rename_profile(&mut self, name: &str, new_name: &str) -> Result<(), Box<dyn Error>> {
+        let profiles = Profiles::from_data_dirs()?;
+        profiles.rename_profile(name, new_name)?;
+
+        //The save games are not changed: Reloading is not required
+        if profile::current_profile() == name {
+            profiles.select_profile(new_name)?;
+        }
+
+        Ok(())
+    }
+
+    //If the current profile is deleted, the first remaining profile is selected
+    pub fn delete_profile(&mut self, name: &str) -> Result<(), Box<dyn Error>> {
+        let profiles = Profiles::from_data_dirs()?;
+        profiles.delete_profile(name)?;
+
+        if profile::current_profile() == name {
+            let profile_names = profiles.profile_names()?;
+            let Some(profile_name) = profile_names.first() else {
+                return Err(Box::new(GameError::new("No profile exists")));
+            };
+
+            self.select_profile(profile_name)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn pop_startup_error(&mut self) -> Option<String> {
+        if self.startup_errors.is_empty() {
+            None
+        }else {
+            Some(self.startup_errors.remove(0))
+        }
+    }
+
+    pub fn pop_save_game_warning(&mut self) -> Option<SaveGameWarning> {
+        if self.save_game_warnings.is_empty() {
+            None
+        }else {
+            Some(self.save_game_warnings.remove(0))
+        }
+    }
+
+    //Returns the ID of the level pack and the changes of the first level pack with changed levels
+    pub fn take_level_changes(&mut self) -> Option<(String, Vec<LevelChange>)> {
+        self.level_packs.iter_mut().
+                find_map(|level_pack| {
+                    let level_changes = level_pack.take_level_changes();
+
+                    (!level_changes.is_empty()).then(|| (level_pack.id().to_string(), level_changes))
+                })
+    }
+
+    pub fn restore_save_game_backup(&mut self, level_pack_id: &str) -> Result<(), Box<dyn Error>> {
+        let level_pack = self.level_packs.iter_mut().
+                find(|level_pack| level_pack.id() == level_pack_id).
+                ok_or_else(|| GameError::new(format!("Level pack \"{}\" does not exist", level_pack_id)))?;
+
+        level_pack.restore_save_game_backup()
+    }
+
+    pub fn has_found_secret(&self) -> bool {
+        self.found_secret_main_level_pack
+    }
+
     pub fn on_found_secret(&mut self) -> Result<(), Box<dyn Error>> {
+        self.on_achievement_event(AchievementEvent::SecretFound);
+
         self.on_found_secret_for_level_pack(self.current_level_pack_index)
     }
 
     pub fn play_sound_effect_ui_dialog_open(&self) {
-        self.play_sound_effect(audio::UI_DIALOG_OPEN_EFFECT);
+        self.play_sound_event(SoundEvent::DialogOpen);
     }
 
     pub fn play_sound_effect_ui_select(&self) {
-        self.play_sound_effect(audio::UI_SELECT_EFFECT);
+        self.play_sound_event(SoundEvent::UiSelect);
     }
 
     pub fn play_sound_effect_ui_error(&self) {
-        self.play_sound_effect(audio::UI_ERROR_EFFECT);
+        self.play_sound_event(SoundEvent::UiError);
+    }
+
+    //Auto uses the event ticker if no audio device exists
+    pub fn feedback_mode(&self) -> FeedbackMode {
+        match self.settings.feedback_mode() {
+            FeedbackMode::Auto if self.audio_backend.is_some() => FeedbackMode::Sound,
+            FeedbackMode::Auto => FeedbackMode::Ticker,
+
+            feedback_mode => feedback_mode,
+        }
+    }
+
+    pub fn play_sound_event(&self, sound_event: SoundEvent) {
+        self.play_panned_sound_event(sound_event, None);
+    }
+
+    //The sound is panned by the x position in the level if stereo panning is enabled
+    pub fn play_sound_event_at(&self, sound_event: SoundEvent, x: usize, level_width: usize) {
+        let pan = (self.settings.is_stereo_panning_enabled() && level_width > 1).
+                then(|| 2.0 * x as f32 / (level_width - 1) as f32 - 1.0);
+
+        self.play_panned_sound_event(sound_event, pan);
+    }
+
+    //One of the sound effects which are mapped to the event is played (Events which are mapped to "none" are not played)
+    fn play_panned_sound_event(&self, sound_event: SoundEvent, pan: Option<f32>) {
+        match self.feedback_mode() {
+            FeedbackMode::Sound => {
+                let volume = self.settings.effective_volume(sound_event.category());
+                if volume <= 0.0 {
+                    return;
+                }
+
+                let sound_effects = self.settings.sound_mapping().sound_effects(sound_event);
+                if sound_effects.is_empty() {
+                    return;
+                }
+                let sound_effect = sound_effects[self.next_random() as usize % sound_effects.len()];
+
+                if let Some(audio_backend) = &self.audio_backend {
+                    let _ = audio_backend.play_sound_event(sound_event, sound_effect, volume, pan);
+                }
+            },
+
+            //The bell is muted like the sound
+            FeedbackMode::Bell => {
+                if sound_event.feedback_text().is_some() && self.settings.is_sound_enabled() {
+                    self.is_bell_pending.set(true);
+                }
+            },
+
+            _ => {
+                if let Some(text) = sound_event.feedback_text() {
+                    self.feedback.set(Some(Feedback {
+                        text,
+                        category: sound_event.category(),
+
+                        update_count: 0,
+                    }));
+                }
+            },
+        }
     }
 
-    pub fn play_sound_effect(&self, sound_effect: &'static [u8]) {
-        if let Some(audio_handler) = &self.audio_handler {
-            let _ = audio_handler.play_sound_effect(sound_effect);
+    fn next_random(&self) -> u64 {
+        let mut state = self.random_state.get();
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+
+        self.random_state.set(state);
+
+        state
+    }
+
+    fn update_feedback(&mut self) {
+        let duration = if self.feedback_mode() == FeedbackMode::Flash {
+            Game::FLASH_FEEDBACK_DURATION
+        }else {
+            Game::TICKER_FEEDBACK_DURATION
+        };
+
+        if let Some(mut feedback) = self.feedback.get() {
+            feedback.update_count += 1;
+
+            self.feedback.set((feedback.update_count < duration).then_some(feedback));
         }
     }
+
+    //The music of the current level pack is played in game (Also while the settings are opened in game)
+    fn update_music(&mut self) {
+        let screen_id = if self.current_screen_id == ScreenId::Settings {
+            &self.settings_return_screen_id
+        }else {
+            &self.current_screen_id
+        };
+
+        let music = if *screen_id == ScreenId::InGame {
+            self.get_current_level_pack().
+                    map(|level_pack| level_pack.music().cloned().
+                            unwrap_or_else(|| Music::track(audio::DEFAULT_PACK_MUSIC_TRACK).unwrap()))
+        }else {
+            Music::track(audio::MENU_MUSIC_TRACK)
+        };
+
+        let volume = self.settings.effective_music_volume();
+        if let Some(audio_backend) = &self.audio_backend {
+            let _ = audio_backend.update_music(music.as_ref(), volume, self.is_help);
+        }
+    }
+
+    //Playing sound effects are stopped if the sound is muted
+    pub fn toggle_mute(&mut self) {
+        let mut settings = self.settings.clone();
+        settings.set_sound_enabled(!settings.is_sound_enabled());
+
+        if !settings.is_sound_enabled() && let Some(audio_backend) = &self.audio_backend {
+            let _ = audio_backend.stop_all();
+        }
+
+        if let Err(err) = self.set_settings(settings) {
+            self.open_dialog(Box::new(DialogOk::new_error(format!("Cannot save settings: {}", err))));
+        }
+
+        self.play_sound_effect_ui_select();
+    }
 }
 
 pub struct Game<'a> {
@@ -266,9 +978,17 @@ impl <'a> Game<'a> {
 
     pub const MAX_LEVEL_PACK_ID_LEN: usize = 16;
 
-    const PLAYER_BACKGROUND_DELAY: i32 = 12;
+    //Notifications are shown for 3 seconds (25 updates per second)
+    const ACHIEVEMENT_NOTIFICATION_DURATION: usize = 75;
+
+    //Visual feedback of sound effects (Updates)
+    const FLASH_FEEDBACK_DURATION: usize = 5;
+    const TICKER_FEEDBACK_DURATION: usize = 38;
 
-    const SAVE_GAME_FOLDER: &'static str = "ConsoleSokoban";
+    const ACHIEVEMENTS_FILE: &'static str = "achievements.sav";
+    const SETTINGS_FILE: &'static str = "settings.cfg";
+    const SOUND_PACK_FOLDER: &'static str = "sound-packs";
+    const SUSPENDED_LEVEL_FILE: &'static str = "suspended_level.sav";
 
     const MAP_TUTORIAL: &'static str = include_str!("../resources/tutorial.lvl");
     const MAP_MAIN: &'static str = include_str!("../resources/main.lvl");
@@ -277,24 +997,35 @@ impl <'a> Game<'a> {
 
     const MAP_SECRET: &'static str = include_str!("../resources/secret.lvl");
 
-    pub fn get_or_create_save_game_folder() -> Result<OsString, Box<dyn Error>> {
-        let mut directory = if cfg!(windows) {
-            let mut home_drive = std::env::var_os("HOMEDRIVE").
-                    ok_or(GameError::new("$HOMEDRIVE not set!"))?;
-            home_drive.push(&std::env::var_os("HOMEPATH").
-                    ok_or(GameError::new("$HOMEPATH not set!"))?);
+    //Contains the save games of the current profile
+    pub fn get_or_create_save_game_folder() -> Result<PathBuf, Box<dyn Error>> {
+        let directory = Profiles::from_data_dirs()?.profile_data_dir(&profile::current_profile());
+        std::fs::create_dir_all(&directory)?;
 
-            home_drive
-        }else {
-            std::env::var_os("HOME").
-                    ok_or(GameError::new("$HOME not set!"))?
-        };
+        Ok(directory)
+    }
+
+    //Contains the settings of the current profile
+    pub fn get_or_create_config_folder() -> Result<PathBuf, Box<dyn Error>> {
+        let directory = Profiles::from_data_dirs()?.profile_config_dir(&profile::current_profile());
+        std::fs::create_dir_all(&directory)?;
+
+        Ok(directory)
+    }
+
+    //Editor level packs are shared between all profiles
+    pub fn get_or_create_editor_level_pack_folder() -> Result<PathBuf, Box<dyn Error>> {
+        let directory = data_dir::data_dir()?;
+        std::fs::create_dir_all(directory)?;
+
+        Ok(directory.to_path_buf())
+    }
 
-        directory.push("/.jddev0/");
-        directory.push(Self::SAVE_GAME_FOLDER);
+    //Sound packs are shared between all profiles
+    pub fn get_or_create_sound_pack_folder() -> Result<PathBuf, Box<dyn Error>> {
+        let directory = data_dir::data_dir()?.join(Self::SOUND_PACK_FOLDER);
         std::fs::create_dir_all(&directory)?;
 
-        directory.push("/");
         Ok(directory)
     }
 
@@ -308,16 +1039,25 @@ impl <'a> Game<'a> {
             ))));
         }
 
+        let (data_dir_override, args) = data_dir::parse_args(std::env::args().skip(1))?;
+        data_dir::init(data_dir_override)?;
+        Profiles::from_data_dirs()?.init()?;
+
         let screens = HashMap::from_iter([
             (ScreenId::StartMenu, Box::new(ScreenStartMenu::new()) as Box<dyn Screen>),
 
             (ScreenId::SelectLevelPack, Box::new(ScreenSelectLevelPack::new()) as Box<dyn Screen>),
             (ScreenId::SelectLevel, Box::new(ScreenSelectLevel::new()) as Box<dyn Screen>),
 
+            (ScreenId::Statistics, Box::new(ScreenStatistics::new()) as Box<dyn Screen>),
+            (ScreenId::Achievements, Box::new(ScreenAchievements::new()) as Box<dyn Screen>),
+            (ScreenId::Settings, Box::new(ScreenSettings::new()) as Box<dyn Screen>),
+
             (ScreenId::InGame, Box::new(ScreenInGame::new()) as Box<dyn Screen>),
 
             (ScreenId::SelectLevelPackEditor, Box::new(ScreenSelectLevelPackEditor::new()) as Box<dyn Screen>),
             (ScreenId::LevelPackEditor, Box::new(ScreenLevelPackEditor::new()) as Box<dyn Screen>),
+            (ScreenId::LevelPackRepair, Box::new(ScreenLevelPackRepair::new()) as Box<dyn Screen>),
             (ScreenId::LevelEditor, Box::new(ScreenLevelEditor::new()) as Box<dyn Screen>),
         ]);
 
@@ -329,211 +1069,245 @@ impl <'a> Game<'a> {
             LevelPack::read_from_save_game("demon", "build-in:demon", Self::MAP_DEMON)?,
         ]);
 
-        for arg in std::env::args().
-                skip(1) {
-            if !arg.ends_with(".lvl") {
-                return Err(Box::new(GameError::new(format!(
-                    "Invalid level pack \"{}\": The file extension of level pack must be \".lvl\"",
-                    arg
-                ))));
+        let mut broken_level_packs = Vec::new();
+        for arg in args {
+            if level_packs.len() >= LevelPack::MAX_LEVEL_PACK_COUNT {
+                broken_level_packs.push(BrokenLevelPack::new(&arg, &arg, None, format!(
+                    "Too many level packs (max: {})",
+                    LevelPack::MAX_LEVEL_PACK_COUNT,
+                )));
+
+                continue;
             }
 
-            let level_pack_path = Path::new(&arg);
+            match Self::load_level_pack(&arg, &level_packs) {
+                Ok(level_pack) => level_packs.push(level_pack),
+                Err(err) => broken_level_packs.push(BrokenLevelPack::new(&arg, &arg, None, err.to_string())),
+            }
+        }
 
-            let level_pack_file_name = if let Some(file_name) = level_pack_path.file_name() {
-                if let Some(file_name) = file_name.to_str() {
-                    file_name
-                }else {
-                    return Err(Box::new(GameError::new(format!(
-                        "Error while loading level pack \"{}\": Invalid file name",
-                        arg
-                    ))));
-                }
-            }else {
-                return Err(Box::new(GameError::new(format!(
-                    "Error while loading level pack \"{}\": File name is missing",
-                    arg
-                ))));
-            };
+        let editor_level_pack_folder = Game::get_or_create_editor_level_pack_folder()?;
+        let mut editor_level_pack_files = std::fs::read_dir(editor_level_pack_folder)?.
+                filter(|entry| entry.as_ref().
+                        is_ok_and(|entry| entry.path().is_file())).
+                map(|entry| entry.unwrap()).
+                filter(|entry| entry.file_name().to_str().is_some_and(|file_name| file_name.ends_with(".lvl.edit"))).
+                map(|entry| {
+                    let file_name = entry.file_name();
+                    let file_name = file_name.to_str().unwrap();
 
-            let mut level_pack_file = match File::open(level_pack_path) {
-                Ok(file) => file,
-                Err(err) => return Err(Box::new(GameError::new(format!(
-                    "Error while loading level pack \"{}\": {}",
-                    arg, err
-                )))),
-            };
+                    (file_name[..file_name.len() - 9].to_string(), entry.path().to_str().unwrap().to_string())
+                }).
+                collect::<Vec<_>>();
+        editor_level_pack_files.sort();
 
-            let mut level_pack_data = String::new();
-            if let Err(err) = level_pack_file.read_to_string(&mut level_pack_data) {
-                return Err(Box::new(GameError::new(format!(
-                    "Error while loading level pack \"{}\": {}",
-                    arg, err
-                ))));
+        let mut editor_level_packs = Vec::with_capacity(LevelPack::MAX_LEVEL_PACK_COUNT);
+        let mut broken_editor_level_packs = Vec::new();
+        for (level_pack_id, path) in editor_level_pack_files {
+            let level_pack_data = match std::fs::read_to_string(&path) {
+                Ok(level_pack_data) => level_pack_data,
+                Err(err) => {
+                    broken_editor_level_packs.push(BrokenLevelPack::new(&level_pack_id, &path, None, format!(
+                        "Error while loading editor level pack \"{}\": {}",
+                        path, err
+                    )));
+
+                    continue;
+                },
             };
 
-            let level_pack_id = &level_pack_file_name[..level_pack_file_name.len() - 4];
-            if level_pack_id.len() > Self::MAX_LEVEL_PACK_ID_LEN {
-                return Err(Box::new(GameError::new(format!(
-                    "Error while loading level pack \"{}\": Level pack ID is too long (Max: {})",
-                    arg, Self::MAX_LEVEL_PACK_ID_LEN
-                ))));
-            }
+            if editor_level_packs.len() >= LevelPack::MAX_LEVEL_PACK_COUNT {
+                broken_editor_level_packs.push(BrokenLevelPack::new(&level_pack_id, &path, Some(level_pack_data), format!(
+                    "Too many level packs (max: {})",
+                    LevelPack::MAX_LEVEL_PACK_COUNT,
+                )));
 
-            if level_pack_id == "secret" {
-                return Err(Box::new(GameError::new(format!("Level pack \"{}\" already exists!", level_pack_id))));
+                continue;
             }
 
-            for id in level_packs.iter().
-                    map(|level_pack| level_pack.id()) {
-                if id == level_pack_id {
-                    return Err(Box::new(GameError::new(format!("Level pack \"{}\" already exists!", level_pack_id))));
-                }
+            match Self::load_editor_level_pack(&level_pack_id, &path, &level_pack_data) {
+                Ok(level_pack) => editor_level_packs.push(level_pack),
+                Err(err) => broken_editor_level_packs.push(BrokenLevelPack::new(&level_pack_id, &path, Some(level_pack_data), err.to_string())),
             }
+        }
 
-            level_packs.push(LevelPack::read_from_save_game(level_pack_id, &arg, level_pack_data)?);
+        let broken_level_packs_message = Self::broken_level_packs_message(
+            broken_level_packs.iter().chain(broken_editor_level_packs.iter()),
+            broken_level_packs.len() + broken_editor_level_packs.len(),
+        );
+
+        let audio_backend = AudioHandler::new().ok().
+                map(|audio_handler| Box::new(audio_handler) as Box<dyn AudioBackend>);
+        let mut game_state = GameState::new(level_packs, editor_level_packs, broken_editor_level_packs, audio_backend);
+
+        game_state.load_found_secrets()?;
+        game_state.load_achievements();
+
+        let settings_result = game_state.load_settings();
+        let sound_pack_result = game_state.load_sound_pack();
+        let suspended_level_result = game_state.load_suspended_level();
+
+        game_state.startup_errors.extend(broken_level_packs_message);
+        game_state.startup_errors.extend(game_state.level_packs.iter().
+                filter_map(Self::skipped_levels_message));
+        game_state.startup_errors.extend(game_state.level_packs.iter().
+                filter_map(|level_pack| level_pack.music_error().
+                        map(|err| format!("Cannot play the music of \"{}\": {}", level_pack.id(), err))));
+        if let Err(err) = settings_result {
+            game_state.startup_errors.push(format!("Cannot load settings: {}", err));
+        }
+        if let Err(err) = sound_pack_result {
+            game_state.startup_errors.push(format!("Cannot load sound pack: {}", err));
         }
+        if let Err(err) = suspended_level_result {
+            game_state.startup_errors.push(format!("Cannot resume level: {}", err));
+        }
+
+        game_state.collect_save_game_warnings();
+
+        Ok(Self {
+            console,
 
-        if level_packs.len() > LevelPack::MAX_LEVEL_PACK_COUNT {
+            screens,
+            help_page: HelpPage::new(),
+
+            game_state,
+        })
+    }
+
+    fn load_level_pack(arg: &str, level_packs: &[LevelPack]) -> Result<LevelPack, Box<dyn Error>> {
+        let is_xsb = arg.ends_with(".xsb") || arg.ends_with(".sok");
+        if !arg.ends_with(".lvl") && !is_xsb {
             return Err(Box::new(GameError::new(format!(
-                "Too many level packs ({}, max: {})",
-                level_packs.len(),
-                LevelPack::MAX_LEVEL_PACK_COUNT,
+                "Invalid level pack \"{}\": The file extension of level pack must be \".lvl\", \".xsb\" or \".sok\"",
+                arg
             ))));
         }
 
-        for level_pack in level_packs.iter() {
-            if level_pack.level_count() == 0 {
-                return Err(Box::new(GameError::new(format!(
-                    "Error while loading level pack \"{}\": Level pack contains no levels",
-                    level_pack.id()
-                ))));
-            }
+        let level_pack_path = Path::new(arg);
 
-            if level_pack.level_count() > LevelPack::MAX_LEVEL_COUNT_PER_PACK {
+        let level_pack_file_name = if let Some(file_name) = level_pack_path.file_name() {
+            if let Some(file_name) = file_name.to_str() {
+                file_name
+            }else {
                 return Err(Box::new(GameError::new(format!(
-                    "Error while loading level pack \"{}\": Level pack contains too many levels ({}, max: {})",
-                    level_pack.id(),
-                    level_pack.level_count(),
-                    LevelPack::MAX_LEVEL_COUNT_PER_PACK,
+                    "Error while loading level pack \"{}\": Invalid file name",
+                    arg
                 ))));
             }
+        }else {
+            return Err(Box::new(GameError::new(format!(
+                "Error while loading level pack \"{}\": File name is missing",
+                arg
+            ))));
+        };
 
-            for (i, level) in level_pack.levels().iter().
-                    map(|level| level.level()).
-                    enumerate() {
-                if level.width() > Self::LEVEL_MAX_WIDTH || level.height() > Self::LEVEL_MAX_HEIGHT {
-                    return Err(Box::new(GameError::new(format!(
-                        "Error while loading level pack \"{}\": Level {} is too large (Max: {}x{})",
-                        level_pack.id(),
-                        i + 1,
-                        Self::LEVEL_MAX_WIDTH,
-                        Self::LEVEL_MAX_HEIGHT,
-                    ))));
-                }
+        let mut level_pack_file = match File::open(level_pack_path) {
+            Ok(file) => file,
+            Err(err) => return Err(Box::new(GameError::new(format!(
+                "Error while loading level pack \"{}\": {}",
+                arg, err
+            )))),
+        };
 
-                let player_tile_count = level.tiles().iter().filter(|tile| **tile == Tile::Player).count();
-                if player_tile_count == 0 {
-                    return Err(Box::new(GameError::new(format!(
-                        "Error while loading level pack \"{}\": Level {} does not contain a player tile",
-                        level_pack.id(),
-                        i + 1,
-                    ))));
-                }else if player_tile_count > 1 {
-                    return Err(Box::new(GameError::new(format!(
-                        "Error while loading level pack \"{}\": Level {} contains too many player tiles",
-                        level_pack.id(),
-                        i + 1,
-                    ))));
-                }
+        let mut level_pack_data = String::new();
+        if let Err(err) = level_pack_file.read_to_string(&mut level_pack_data) {
+            return Err(Box::new(GameError::new(format!(
+                "Error while loading level pack \"{}\": {}",
+                arg, err
+            ))));
+        };
+
+        let level_pack_id = &level_pack_file_name[..level_pack_file_name.len() - 4];
+        if level_pack_id.len() > Self::MAX_LEVEL_PACK_ID_LEN {
+            return Err(Box::new(GameError::new(format!(
+                "Error while loading level pack \"{}\": Level pack ID is too long (Max: {})",
+                arg, Self::MAX_LEVEL_PACK_ID_LEN
+            ))));
+        }
+
+        if level_pack_id == "secret" {
+            return Err(Box::new(GameError::new(format!("Level pack \"{}\" already exists!", level_pack_id))));
+        }
+
+        for id in level_packs.iter().
+                map(|level_pack| level_pack.id()) {
+            if id == level_pack_id {
+                return Err(Box::new(GameError::new(format!("Level pack \"{}\" already exists!", level_pack_id))));
             }
         }
 
-        let mut editor_level_packs = Vec::with_capacity(LevelPack::MAX_LEVEL_PACK_COUNT);
+        //Level packs are validated before their save game is loaded
+        if is_xsb {
+            LevelPack::read_from_xsb_save_game(level_pack_id, arg, level_pack_data)
+        }else {
+            LevelPack::read_from_save_game(level_pack_id, arg, level_pack_data)
+        }
+    }
 
-        let save_game_folder = Game::get_or_create_save_game_folder()?;
-        for entry in std::fs::read_dir(save_game_folder)?.
-                filter(|entry| entry.as_ref().
-                        is_ok_and(|entry| entry.path().is_file())).
-                map(|entry| entry.unwrap()) {
-            if entry.file_name().to_str().is_some_and(|file_name| file_name.ends_with(".lvl.edit")) {
-                let file_name = entry.file_name();
-                let file_name = file_name.to_str().unwrap();
-                let level_pack_id = &file_name[..file_name.len() - 9];
-
-                let mut level_pack_file = match File::open(entry.path()) {
-                    Ok(file) => file,
-                    Err(err) => return Err(Box::new(GameError::new(format!(
-                        "Error while loading editor level pack \"{}\": {}",
-                        file_name, err
-                    )))),
-                };
+    pub fn load_editor_level_pack(id: &str, path: &str, lvl_data: &str) -> Result<LevelPack, Box<dyn Error>> {
+        let level_pack = LevelPack::read_editor_level_pack(id, path, lvl_data)?;
+        level_pack.validate(true)?;
 
-                let mut level_pack_data = String::new();
-                if let Err(err) = level_pack_file.read_to_string(&mut level_pack_data) {
-                    return Err(Box::new(GameError::new(format!(
-                        "Error while loading editor level pack \"{}\": {}",
-                        file_name, err
-                    ))));
-                };
+        Ok(level_pack)
+    }
 
-                editor_level_packs.push(LevelPack::read_from_save_game(level_pack_id, entry.path().to_str().unwrap(), level_pack_data)?);
-            }
+    //Returns a multi-line dialog message if levels of the level pack could not be imported
+    fn skipped_levels_message(level_pack: &LevelPack) -> Option<String> {
+        const MAX_SHOWN_ERRORS: usize = 7;
+
+        let errors = level_pack.skipped_levels_error()?.errors();
+
+        let mut message = format!("{} levels of \"{}\" were skipped:", errors.len(), level_pack.id());
+        for error in errors.iter().
+                take(MAX_SHOWN_ERRORS) {
+            let error = error.to_string();
+            let error = error.split_once(&format!("\"{}\"", level_pack.path())).
+                    map_or(error.as_str(), |(_, error)| error.trim_start_matches([',', ':']).trim_start());
+
+            let _ = write!(message, "\n  {}", error);
         }
 
-        if editor_level_packs.len() > LevelPack::MAX_LEVEL_PACK_COUNT {
-            return Err(Box::new(GameError::new(format!(
-                "Too many level packs ({}, max: {})",
-                editor_level_packs.len(),
-                LevelPack::MAX_LEVEL_PACK_COUNT,
-            ))));
+        if errors.len() > MAX_SHOWN_ERRORS {
+            let _ = write!(message, "\n... and {} more", errors.len() - MAX_SHOWN_ERRORS);
         }
 
-        for level_pack in editor_level_packs.iter() {
-            //Level pack for editor might be empty and might contain no player tile
+        Some(message)
+    }
 
-            if level_pack.level_count() > LevelPack::MAX_LEVEL_COUNT_PER_PACK {
-                return Err(Box::new(GameError::new(format!(
-                    "Error while loading editor level pack \"{}\": Level pack contains too many levels ({}, max: {})",
-                    level_pack.id(),
-                    level_pack.level_count(),
-                    LevelPack::MAX_LEVEL_COUNT_PER_PACK,
-                ))));
-            }
+    //Returns a multi-line dialog message (The first error of each level pack is shown without the level pack path)
+    fn broken_level_packs_message<'b>(broken_level_packs: impl Iterator<Item = &'b BrokenLevelPack>, count: usize) -> Option<String> {
+        const MAX_SHOWN_LEVEL_PACKS: usize = 7;
 
-            for (i, level) in level_pack.levels().iter().
-                    map(|level| level.level()).
-                    enumerate() {
-                if level.width() > Self::LEVEL_MAX_WIDTH || level.height() > Self::LEVEL_MAX_HEIGHT {
-                    return Err(Box::new(GameError::new(format!(
-                        "Error while loading editor level pack \"{}\": Level {} is too large (Max: {}x{})",
-                        level_pack.id(),
-                        i + 1,
-                        Self::LEVEL_MAX_WIDTH,
-                        Self::LEVEL_MAX_HEIGHT,
-                    ))));
-                }
-            }
+        if count == 0 {
+            return None;
         }
-        
-        editor_level_packs.sort_by_key(|level_pack| level_pack.id().to_string());
 
-        let mut game_state = GameState::new(level_packs, editor_level_packs);
+        let mut message = if count == 1 {
+            "1 level pack could not be loaded:".to_string()
+        }else {
+            format!("{} level packs could not be loaded:", count)
+        };
 
-        let mut save_game_file = Game::get_or_create_save_game_folder()?;
-        save_game_file.push("secret.lvl.sav");
-        if std::fs::exists(&save_game_file).is_ok_and(|exists| exists) {
-            game_state.on_found_secret_for_level_pack(1)?;
-        }
+        for broken_level_pack in broken_level_packs.take(MAX_SHOWN_LEVEL_PACKS) {
+            let mut error_lines = broken_level_pack.error().lines();
+            let first_error = error_lines.next().unwrap_or_default();
+            let first_error = first_error.split_once(&format!("\"{}\"", broken_level_pack.path())).
+                    map_or(first_error, |(_, error)| error.trim_start_matches([',', ':']).trim_start());
 
-        Ok(Self {
-            console,
+            let _ = write!(message, "\n{}:\n  {}", broken_level_pack.id(), first_error);
 
-            screens,
-            help_page: HelpPage::new(),
+            let additional_error_count = error_lines.count();
+            if additional_error_count > 0 {
+                let _ = write!(message, " (+{} more)", additional_error_count);
+            }
+        }
 
-            game_state,
-        })
+        if count > MAX_SHOWN_LEVEL_PACKS {
+            let _ = write!(message, "\n... and {} more", count - MAX_SHOWN_LEVEL_PACKS);
+        }
+
+        Some(message)
     }
 
     #[must_use]
@@ -555,27 +1329,51 @@ impl <'a> Game<'a> {
                     screen.on_set_screen(&mut self.game_state);
                 }
 
+                if mem::replace(&mut self.game_state.should_call_on_continue, false) {
+                    screen.on_continue(&mut self.game_state);
+                }
+
                 screen.update(&mut self.game_state);
             }
         }
 
+        self.game_state.update_achievement_notifications();
+        self.game_state.update_music();
+        self.game_state.update_feedback();
+
         //Player background
-        self.game_state.player_background_tmp += 1;
-        if self.game_state.player_background_tmp >= Self::PLAYER_BACKGROUND_DELAY + self.game_state.is_player_background as i32 {
-            //If isPlayerBackground: wait an additional update (25 updates per second, every half
-            //second: switch background/foreground colors [12 updates, 13 updates])
+        if self.game_state.settings.is_player_blink_enabled() {
+            let player_background_delay = self.game_state.settings.player_blink_rate().delay();
+
+            self.game_state.player_background_tmp += 1;
+            if self.game_state.player_background_tmp >= player_background_delay + self.game_state.is_player_background as i32 {
+                //If isPlayerBackground: wait an additional update (25 updates per second, with the normal blink rate every half
+                //second: switch background/foreground colors [12 updates, 13 updates])
+                self.game_state.player_background_tmp = 0;
+                self.game_state.is_player_background = !self.game_state.is_player_background;
+            }
+        }else {
             self.game_state.player_background_tmp = 0;
-            self.game_state.is_player_background = !self.game_state.is_player_background;
+            self.game_state.is_player_background = false;
         }
 
         self.draw();
+        self.ring_bell();
 
         false
     }
 
     fn update_key(&mut self, key: Key) {
+        //Muting works in every screen (Global actions are never bound to keys which are used for text input)
+        if self.game_state.key_action(key) == Some(Action::ToggleMute) {
+            self.game_state.toggle_mute();
+
+            return;
+        }
+
         let screen = self.screens.get_mut(&self.game_state.current_screen_id);
         if self.game_state.is_help {
+            let key = self.game_state.menu_key(key);
             if key == Key::F1 || key == Key::ESC {
                 self.game_state.close_help_page();
 
@@ -645,7 +1443,7 @@ impl <'a> Game<'a> {
         self.console.repaint();
 
         if self.game_state.is_help {
-            self.help_page.draw(self.console, Self::CONSOLE_MIN_WIDTH, Self::CONSOLE_MIN_HEIGHT);
+            self.help_page.draw(self.console, Self::CONSOLE_MIN_WIDTH, Self::CONSOLE_MIN_HEIGHT, self.game_state.settings().key_bindings());
 
             return;
         }
@@ -655,10 +1453,52 @@ impl <'a> Game<'a> {
             screen.draw(&self.game_state, self.console);
         }
 
+        //Achievement notifications do not block the input
+        if let Some(achievement) = self.game_state.achievement_notifications.front() {
+            let notification = format!(" Achievement unlocked: {} ", achievement.name());
+
+            self.console.set_cursor_pos(
+                (Self::CONSOLE_MIN_WIDTH - notification.len()) / 2,
+                Self::CONSOLE_MIN_HEIGHT - 1,
+            );
+            self.console.set_color(Color::Black, Color::Yellow);
+            self.console.draw_text(notification);
+            self.console.reset_color();
+        }else if let Some(feedback) = self.game_state.feedback.get() {
+            let color = match feedback.category {
+                SoundCategory::Ui => Color::LightRed,
+                SoundCategory::Gameplay => Color::LightYellow,
+                SoundCategory::Jingles => Color::LightGreen,
+            };
+
+            //The flash colors the whole bottom row
+            let text = if self.game_state.feedback_mode() == FeedbackMode::Flash {
+                " ".repeat(Self::CONSOLE_MIN_WIDTH)
+            }else {
+                format!(" {} ", feedback.text)
+            };
+
+            self.console.set_cursor_pos(
+                (Self::CONSOLE_MIN_WIDTH - text.len()) / 2,
+                Self::CONSOLE_MIN_HEIGHT - 1,
+            );
+            self.console.set_color(Color::Black, color);
+            self.console.draw_text(text);
+            self.console.reset_color();
+        }
+
         if let Some(dialog) = self.game_state.dialog.as_ref() {
             dialog.draw(self.console, Self::CONSOLE_MIN_WIDTH, Self::CONSOLE_MIN_HEIGHT);
         }
     }
+
+    //Multiple bells of one update are combined into one
+    fn ring_bell(&self) {
+        if self.game_state.is_bell_pending.take() {
+            let mut stdout = std::io::stdout();
+            let _ = stdout.write_all(b"\x07").and_then(|()| stdout.flush());
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -0,0 +1,274 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use std::str::FromStr;
+use crate::game::save_game;
+
+#[cfg(test)]
+mod tests;
+
+const PLAY_TIME_PREFIX: &str = "play-time,";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Achievement {
+    LevelPackCleared,
+    NoUndoClear,
+    SubParMoves,
+    SecretFound,
+    PlayedOneHour,
+    PlayedTenHours,
+    EditorLevelPackCreated,
+}
+
+impl Achievement {
+    pub const ALL: [Achievement; 7] = [
+        Self::LevelPackCleared,
+        Self::NoUndoClear,
+        Self::SubParMoves,
+        Self::SecretFound,
+        Self::PlayedOneHour,
+        Self::PlayedTenHours,
+        Self::EditorLevelPackCreated,
+    ];
+
+    //Used in the achievements file
+    fn id(&self) -> &'static str {
+        match self {
+            Self::LevelPackCleared => "level-pack-cleared",
+            Self::NoUndoClear => "no-undo-clear",
+            Self::SubParMoves => "sub-par-moves",
+            Self::SecretFound => "secret-found",
+            Self::PlayedOneHour => "played-one-hour",
+            Self::PlayedTenHours => "played-ten-hours",
+            Self::EditorLevelPackCreated => "editor-level-pack-created",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::LevelPackCleared => "Pack Master",
+            Self::NoUndoClear => "Steady Hands",
+            Self::SubParMoves => "Under Par",
+            Self::SecretFound => "Explorer",
+            Self::PlayedOneHour => "Warming Up",
+            Self::PlayedTenHours => "Dedicated",
+            Self::EditorLevelPackCreated => "Architect",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::LevelPackCleared => "Clear all levels of a level pack",
+            Self::NoUndoClear => "Clear a level without using undo",
+            Self::SubParMoves => "Clear a level within its par moves",
+            Self::SecretFound => "Find the secret",
+            Self::PlayedOneHour => "Play for 1 hour",
+            Self::PlayedTenHours => "Play for 10 hours",
+            Self::EditorLevelPackCreated => "Create a level pack in the level pack editor",
+        }
+    }
+}
+
+impl FromStr for Achievement {
+    type Err = AchievementError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL.into_iter().
+                find(|achievement| achievement.id() == s).
+                ok_or_else(|| AchievementError::new(format!("Unknown achievement \"{s}\"")))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AchievementEvent {
+    LevelCompleted {
+        moves: u32,
+
+        //Undos, branch switches and loaded save states
+        undo_count: u32,
+
+        //Levels without par moves cannot unlock the sub-par achievement
+        par_moves: Option<u32>,
+    },
+    LevelPackCleared,
+    SecretFound,
+    Played {
+        time: u64,
+    },
+    EditorLevelPackCreated,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Achievements {
+    //Achievements and the timestamp (Seconds since the unix epoch) at which they were unlocked
+    unlocked: Vec<(Achievement, u64)>,
+
+    //Time of all recorded attempts in milliseconds
+    play_time: u64,
+}
+
+impl Achievements {
+    const ONE_HOUR: u64 = 60 * 60 * 1000;
+
+    //Returns the default achievements if the file does not exist
+    pub fn read(path: &Path) -> Result<Self, AchievementError> {
+        if !std::fs::exists(path).map_err(|err| AchievementError::new(format!("Cannot check achievements: {err}")))? {
+            return Ok(Self::default());
+        }
+
+        let achievements_data = std::fs::read_to_string(path).
+                map_err(|err| AchievementError::new(format!("Cannot read achievements: {err}")))?;
+
+        Self::parse(&achievements_data)
+    }
+
+    pub fn parse(achievements_data: &str) -> Result<Self, AchievementError> {
+        let mut achievements = Self::default();
+
+        for (i, line) in achievements_data.lines().
+                map(|line| line.trim()).
+                enumerate().
+                filter(|(_, line)| !line.is_empty()) {
+            if let Some(play_time) = line.strip_prefix(PLAY_TIME_PREFIX) {
+                achievements.play_time = u64::from_str(play_time).
+                        map_err(|_| AchievementError::new(format!("Invalid play time \"{}\" (Line {})", play_time, i + 1)))?;
+
+                continue;
+            }
+
+            let Some((id, timestamp)) = line.split_once(",") else {
+                return Err(AchievementError::new(format!("Invalid achievement \"{}\" (Line {})", line, i + 1)));
+            };
+
+            let achievement = Achievement::from_str(id).
+                    map_err(|err| AchievementError::new(format!("{} (Line {})", err, i + 1)))?;
+            let timestamp = u64::from_str(timestamp).
+                    map_err(|_| AchievementError::new(format!("Invalid timestamp \"{}\" (Line {})", timestamp, i + 1)))?;
+
+            if !achievements.is_unlocked(achievement) {
+                achievements.unlocked.push((achievement, timestamp));
+            }
+        }
+
+        Ok(achievements)
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        save_game::write_atomically(path, &self.to_string())
+    }
+
+    pub fn is_unlocked(&self, achievement: Achievement) -> bool {
+        self.unlocked.iter().any(|(unlocked_achievement, _)| *unlocked_achievement == achievement)
+    }
+
+    pub fn unlock_timestamp(&self, achievement: Achievement) -> Option<u64> {
+        self.unlocked.iter().
+                find(|(unlocked_achievement, _)| *unlocked_achievement == achievement).
+                map(|(_, timestamp)| *timestamp)
+    }
+
+    pub fn unlocked_count(&self) -> usize {
+        self.unlocked.len()
+    }
+
+    pub fn play_time(&self) -> u64 {
+        self.play_time
+    }
+
+    //Returns the newly unlocked achievements
+    pub fn on_event(&mut self, event: AchievementEvent, timestamp: u64) -> Vec<Achievement> {
+        let achievements = match event {
+            AchievementEvent::LevelCompleted { moves, undo_count, par_moves } => {
+                let mut achievements = Vec::new();
+                if undo_count == 0 {
+                    achievements.push(Achievement::NoUndoClear);
+                }
+
+                if par_moves.is_some_and(|par_moves| moves <= par_moves) {
+                    achievements.push(Achievement::SubParMoves);
+                }
+
+                achievements
+            },
+            AchievementEvent::LevelPackCleared => vec![Achievement::LevelPackCleared],
+            AchievementEvent::SecretFound => vec![Achievement::SecretFound],
+            AchievementEvent::Played { time } => {
+                self.play_time += time;
+
+                let mut achievements = Vec::new();
+                if self.play_time >= Self::ONE_HOUR {
+                    achievements.push(Achievement::PlayedOneHour);
+                }
+
+                if self.play_time >= 10 * Self::ONE_HOUR {
+                    achievements.push(Achievement::PlayedTenHours);
+                }
+
+                achievements
+            },
+            AchievementEvent::EditorLevelPackCreated => vec![Achievement::EditorLevelPackCreated],
+        };
+
+        let mut unlocked_achievements = Vec::new();
+        for achievement in achievements {
+            if !self.is_unlocked(achievement) {
+                self.unlocked.push((achievement, timestamp));
+
+                unlocked_achievements.push(achievement);
+            }
+        }
+
+        unlocked_achievements
+    }
+}
+
+impl Display for Achievements {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}{}", PLAY_TIME_PREFIX, self.play_time)?;
+
+        for (achievement, timestamp) in self.unlocked.iter() {
+            writeln!(f, "{},{}", achievement.id(), timestamp)?;
+        }
+
+        Ok(())
+    }
+}
+
+//Formats a timestamp (Seconds since the unix epoch) as "YYYY-MM-DD" (UTC)
+pub fn timestamp_to_date(timestamp: u64) -> String {
+    //Civil from days algorithm (Howard Hinnant)
+    let days = (timestamp / 86400) as i64 + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    }else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + (month <= 2) as i64;
+
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+#[derive(Debug)]
+pub struct AchievementError {
+    message: String
+}
+
+impl AchievementError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self { message: message.into() }
+    }
+}
+
+impl Display for AchievementError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl Error for AchievementError {}
@@ -0,0 +1,63 @@
+use super::*;
+
+#[test]
+fn unlock_achievements_once() {
+    let mut achievements = Achievements::default();
+
+    let unlocked = achievements.on_event(AchievementEvent::LevelCompleted {
+        moves: 11,
+        undo_count: 0,
+        par_moves: Some(10),
+    }, 100);
+    assert_eq!(unlocked, [Achievement::NoUndoClear]);
+
+    let unlocked = achievements.on_event(AchievementEvent::LevelCompleted {
+        moves: 10,
+        undo_count: 1,
+        par_moves: Some(10),
+    }, 150);
+    assert_eq!(unlocked, [Achievement::SubParMoves]);
+
+    let unlocked = achievements.on_event(AchievementEvent::LevelCompleted {
+        moves: 10,
+        undo_count: 0,
+        par_moves: None,
+    }, 200);
+    assert!(unlocked.is_empty());
+
+    assert_eq!(achievements.unlock_timestamp(Achievement::NoUndoClear), Some(100));
+    assert!(!achievements.is_unlocked(Achievement::SecretFound));
+}
+
+#[test]
+fn unlock_play_time_achievements() {
+    let mut achievements = Achievements::default();
+
+    assert!(achievements.on_event(AchievementEvent::Played { time: 59 * 60 * 1000 }, 0).is_empty());
+    assert_eq!(achievements.on_event(AchievementEvent::Played { time: 60 * 1000 }, 0), [Achievement::PlayedOneHour]);
+    assert_eq!(achievements.play_time(), 60 * 60 * 1000);
+}
+
+#[test]
+fn achievements_round_trip() {
+    let mut achievements = Achievements::default();
+    achievements.on_event(AchievementEvent::SecretFound, 1700000000);
+    achievements.on_event(AchievementEvent::Played { time: 1234 }, 1700000001);
+
+    assert_eq!(achievements.to_string(), "play-time,1234\nsecret-found,1700000000\n");
+    assert_eq!(Achievements::parse(&achievements.to_string()).unwrap(), achievements);
+}
+
+#[test]
+fn parse_invalid_achievements() {
+    assert_eq!(Achievements::parse("play-time,x\n").unwrap_err().to_string(), "Invalid play time \"x\" (Line 1)");
+    assert_eq!(Achievements::parse("unknown,1\n").unwrap_err().to_string(), "Unknown achievement \"unknown\" (Line 1)");
+    assert_eq!(Achievements::parse("secret-found\n").unwrap_err().to_string(), "Invalid achievement \"secret-found\" (Line 1)");
+}
+
+#[test]
+fn format_timestamp_as_date() {
+    assert_eq!(timestamp_to_date(0), "1970-01-01");
+    assert_eq!(timestamp_to_date(951782400), "2000-02-29");
+    assert_eq!(timestamp_to_date(1700000000), "2023-11-14");
+}
@@ -1,47 +1,432 @@
+use std::collections::HashMap;
+#[cfg(test)]
+use std::cell::RefCell;
+#[cfg(test)]
+use std::rc::Rc;
 use std::error::Error;
-use std::io::Cursor;
-use rodio::{Decoder, OutputStream, OutputStreamHandle, Source, StreamError};
+use std::fmt::{Display, Formatter};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use rodio::{OutputStream, OutputStreamHandle, Sink, Source};
+use rodio::source::ChannelVolume;
+use decoded::DecodedSound;
 
-pub const UI_SELECT_EFFECT: &[u8] = include_bytes!("../../assets/audio/ui_select.ogg");
-pub const UI_ERROR_EFFECT: &[u8] = include_bytes!("../../assets/audio/ui_error.ogg");
-pub const UI_DIALOG_OPEN_EFFECT: &[u8] = include_bytes!("../../assets/audio/ui_dialog_open.ogg");
+mod decoded;
+mod event;
+mod music;
+mod sound_pack;
 
-pub const BOOK_OPEN_EFFECT: &[u8] = include_bytes!("../../assets/audio/book_open.ogg");
-pub const BOOK_FLIP_EFFECT: &[u8] = include_bytes!("../../assets/audio/book_flip.ogg");
+#[cfg(test)]
+mod tests;
 
-pub const UNDO_REDO_EFFECT: &[u8] = include_bytes!("../../assets/audio/undo_redo.ogg");
+pub use event::{SoundEvent, SoundMapping};
+pub use music::{Music, DEFAULT_PACK_MUSIC_TRACK, MENU_MUSIC_TRACK};
+pub use sound_pack::{installed_sound_packs, SoundPack, SoundPackError};
 
-pub const SECRET_FOUND_EFFECT: &[u8] = include_bytes!("../../assets/audio/secret_found.ogg");
-pub const NO_PATH_EFFECT: &[u8] = include_bytes!("../../assets/audio/no_path.ogg");
-pub const LEVEL_COMPLETE_EFFECT: &[u8] = include_bytes!("../../assets/audio/level_complete.ogg");
-pub const LEVEL_PACK_COMPLETE_EFFECT: &[u8] = include_bytes!("../../assets/audio/level_pack_complete.ogg");
-pub const LEVEL_RESET: &[u8] = include_bytes!("../../assets/audio/level_reset.ogg");
-pub const STEP_EFFECT: &[u8] = include_bytes!("../../assets/audio/step.ogg");
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SoundCategory {
+    Ui,
+    Gameplay,
+    Jingles,
+}
 
-pub struct AudioHandler {
+impl SoundCategory {
+    pub const ALL: [SoundCategory; 3] = [
+        Self::Ui,
+        Self::Gameplay,
+        Self::Jingles,
+    ];
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SoundEffect {
+    name: &'static str,
+    data: &'static [u8],
+}
+
+impl SoundEffect {
+    const fn new(name: &'static str, data: &'static [u8]) -> Self {
+        Self { name, data }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+const UI_SELECT_EFFECT: SoundEffect = SoundEffect::new("ui_select", include_bytes!("../../assets/audio/ui_select.ogg"));
+const UI_ERROR_EFFECT: SoundEffect = SoundEffect::new("ui_error", include_bytes!("../../assets/audio/ui_error.ogg"));
+const UI_DIALOG_OPEN_EFFECT: SoundEffect = SoundEffect::new("ui_dialog_open", include_bytes!("../../assets/audio/ui_dialog_open.ogg"));
+
+const BOOK_OPEN_EFFECT: SoundEffect = SoundEffect::new("book_open", include_bytes!("../../assets/audio/book_open.ogg"));
+const BOOK_FLIP_EFFECT: SoundEffect = SoundEffect::new("book_flip", include_bytes!("../../assets/audio/book_flip.ogg"));
+
+const UNDO_REDO_EFFECT: SoundEffect = SoundEffect::new("undo_redo", include_bytes!("../../assets/audio/undo_redo.ogg"));
+
+const SECRET_FOUND_EFFECT: SoundEffect = SoundEffect::new("secret_found", include_bytes!("../../assets/audio/secret_found.ogg"));
+const NO_PATH_EFFECT: SoundEffect = SoundEffect::new("no_path", include_bytes!("../../assets/audio/no_path.ogg"));
+const LEVEL_COMPLETE_EFFECT: SoundEffect = SoundEffect::new("level_complete", include_bytes!("../../assets/audio/level_complete.ogg"));
+const LEVEL_PACK_COMPLETE_EFFECT: SoundEffect = SoundEffect::new("level_pack_complete", include_bytes!("../../assets/audio/level_pack_complete.ogg"));
+const LEVEL_RESET_EFFECT: SoundEffect = SoundEffect::new("level_reset", include_bytes!("../../assets/audio/level_reset.ogg"));
+const STEP_EFFECT: SoundEffect = SoundEffect::new("step", include_bytes!("../../assets/audio/step.ogg"));
+
+const ACHIEVEMENT_UNLOCKED_EFFECT: SoundEffect = SoundEffect::new("achievement_unlocked", include_bytes!("../../assets/audio/achievement_unlocked.wav"));
+
+//All sound effects which can be replaced by sound packs
+const SOUND_EFFECTS: [SoundEffect; 13] = [
+    UI_SELECT_EFFECT,
+    UI_ERROR_EFFECT,
+    UI_DIALOG_OPEN_EFFECT,
+
+    BOOK_OPEN_EFFECT,
+    BOOK_FLIP_EFFECT,
+
+    UNDO_REDO_EFFECT,
+
+    SECRET_FOUND_EFFECT,
+    NO_PATH_EFFECT,
+    LEVEL_COMPLETE_EFFECT,
+    LEVEL_PACK_COMPLETE_EFFECT,
+    LEVEL_RESET_EFFECT,
+    STEP_EFFECT,
+
+    ACHIEVEMENT_UNLOCKED_EFFECT,
+];
+
+//Sound effects are referenced by their name in the sound mapping of the settings
+fn sound_effect_from_name(name: &str) -> Option<SoundEffect> {
+    SOUND_EFFECTS.into_iter().find(|sound_effect| sound_effect.name == name)
+}
+
+struct Channel {
+    sound_event: SoundEvent,
+    sink: Sink,
+}
+
+struct MusicChannel {
+    music_id: String,
+    sink: Sink,
+
+    //Factor of the music volume which is changed while crossfading
+    fade_level: f32,
+}
+
+enum AudioCommand {
+    PlaySoundEvent(SoundEvent, SoundEffect, f32, Option<f32>),
+    UpdateMusic(Option<Music>, f32, bool),
+    SetSoundPack(Option<SoundPack>),
+    StopAll,
+}
+
+//Owns the output stream and plays all sounds (Commands are received from the game thread)
+struct AudioThread {
     _stream: OutputStream,
 
     stream_handle: OutputStreamHandle,
+
+    //Embedded sound effects which were decoded at startup
+    sound_effects: HashMap<&'static str, DecodedSound>,
+    sound_pack: Option<SoundPack>,
+
+    //Channels are removed after their sound effect has finished playing (Oldest channel first)
+    channels: Vec<Channel>,
+
+    music: Option<MusicChannel>,
+    fading_out_music: Vec<MusicChannel>,
+}
+
+impl AudioThread {
+    //Runs until the audio handler is dropped
+    fn run(mut self, receiver: Receiver<AudioCommand>) {
+        for command in receiver {
+            //Errors are ignored: The game continues without the sound
+            let _ = match command {
+                AudioCommand::PlaySoundEvent(sound_event, sound_effect, volume, pan) => self.play_sound_event(sound_event, sound_effect, volume, pan),
+                AudioCommand::UpdateMusic(music, volume, is_paused) => self.update_music(music, volume, is_paused),
+                AudioCommand::SetSoundPack(sound_pack) => {
+                    self.sound_pack = sound_pack;
+
+                    Ok(())
+                },
+                AudioCommand::StopAll => {
+                    self.stop_all();
+
+                    Ok(())
+                },
+            };
+        }
+    }
+
+    fn play_sound_event(&mut self, sound_event: SoundEvent, sound_effect: SoundEffect, volume: f32, pan: Option<f32>) -> Result<(), Box<dyn Error>> {
+        self.channels.retain(|channel| !channel.sink.empty());
+
+        if let Some(cancelled_category) = sound_event.cancelled_category() {
+            self.channels.retain(|channel| {
+                let is_cancelled = channel.sound_event.category() == cancelled_category;
+                if is_cancelled {
+                    channel.sink.stop();
+                }
+
+                !is_cancelled
+            });
+        }
+
+        let instance_count = self.channels.iter().
+                filter(|channel| channel.sound_event == sound_event).
+                count();
+        if instance_count >= sound_event.max_instances() {
+            let oldest_index = self.channels.iter().
+                    position(|channel| channel.sound_event == sound_event).
+                    unwrap();
+
+            self.channels.remove(oldest_index).sink.stop();
+        }
+
+        //Files of the sound pack replace the embedded sound effects
+        let Some(sound) = self.sound_pack.as_ref().
+                and_then(|sound_pack| sound_pack.sound_effect(sound_effect)).
+                or_else(|| self.sound_effects.get(sound_effect.name)) else {
+            return Ok(());
+        };
+
+        let sink = Sink::try_new(&self.stream_handle)?;
+        sink.set_volume(volume);
+        if let Some(pan) = pan {
+            let source = sound.source();
+            let channel_volumes = pan_channel_volumes(pan, source.channels());
+
+            sink.append(ChannelVolume::new(source, channel_volumes));
+        }else {
+            sink.append(sound.source());
+        }
+
+        self.channels.push(Channel { sound_event, sink });
+
+        Ok(())
+    }
+
+    fn update_music(&mut self, music: Option<Music>, volume: f32, is_paused: bool) -> Result<(), Box<dyn Error>> {
+        const FADE_STEP: f32 = 1.0 / 25.0;
+
+        if self.music.as_ref().map(|music| music.music_id.as_str()) != music.as_ref().map(Music::id) {
+            self.fading_out_music.extend(self.music.take());
+
+            if let Some(music) = music {
+                let sink = Sink::try_new(&self.stream_handle)?;
+                sink.set_volume(0.0);
+                sink.append(music.source());
+
+                self.music = Some(MusicChannel {
+                    music_id: music.id().to_string(),
+                    sink,
+
+                    fade_level: 0.0,
+                });
+            }
+        }
+
+        if is_paused {
+            for music in self.music.iter().chain(self.fading_out_music.iter()) {
+                music.sink.pause();
+            }
+
+            return Ok(());
+        }
+
+        if let Some(music) = &mut self.music {
+            music.fade_level = (music.fade_level + FADE_STEP).min(1.0);
+        }
+        for music in self.fading_out_music.iter_mut() {
+            music.fade_level -= FADE_STEP;
+        }
+        self.fading_out_music.retain(|music| music.fade_level > 0.0);
+
+        for music in self.music.iter().chain(self.fading_out_music.iter()) {
+            music.sink.set_volume(music.fade_level * volume);
+            music.sink.play();
+        }
+
+        Ok(())
+    }
+
+    fn stop_all(&mut self) {
+        for channel in self.channels.drain(..) {
+            channel.sink.stop();
+        }
+    }
+}
+
+//Returns the volumes of the left and the right output channel (The channels of the sound are mixed to mono)
+fn pan_channel_volumes(pan: f32, channels: u16) -> Vec<f32> {
+    let pan = pan.clamp(-1.0, 1.0);
+    let channels = channels.max(1) as f32;
+
+    //The sound is not attenuated in the center
+    vec![(1.0 - pan).min(1.0) / channels, (1.0 + pan).min(1.0) / channels]
+}
+
+//Decodes all embedded sound effects (Sound effects which cannot be decoded are not played)
+fn decode_sound_effects() -> HashMap<&'static str, DecodedSound> {
+    SOUND_EFFECTS.iter().
+            filter_map(|sound_effect| DecodedSound::decode(sound_effect.data).ok().
+                    map(|sound| (sound_effect.name, sound))).
+            collect()
+}
+
+//Plays the sounds of the game (Implemented by the audio handler and by a recording backend for tests)
+pub trait AudioBackend {
+    //The volume is a factor (1.0 is the original volume), the pan ranges from -1.0 (Left) to 1.0 (Right) (None: The sound is not panned)
+    fn play_sound_event(&self, sound_event: SoundEvent, sound_effect: SoundEffect, volume: f32, pan: Option<f32>) -> Result<(), Box<dyn Error>>;
+
+    //Must be called every update: Tracks are crossfaded within a second (25 updates per second)
+    fn update_music(&self, music: Option<&Music>, volume: f32, is_paused: bool) -> Result<(), Box<dyn Error>>;
+
+    //None: The embedded sound effects are used
+    fn set_sound_pack(&self, sound_pack: Option<SoundPack>) -> Result<(), Box<dyn Error>>;
+
+    fn stop_all(&self) -> Result<(), Box<dyn Error>>;
+}
+
+//Sounds are decoded and played on the audio thread (Playing a sound does not block the game thread)
+pub struct AudioHandler {
+    sender: Sender<AudioCommand>,
 }
 
 impl AudioHandler {
-    pub fn new() -> Result<Self, StreamError> {
-        let output_stream = OutputStream::try_default();
-        let (_stream, stream_handle) = output_stream?;
+    pub fn new() -> Result<Self, Box<dyn Error>> {
+        let (sender, receiver) = mpsc::channel();
+
+        //The output stream cannot be moved between threads and must therefore be opened on the audio thread
+        let (stream_result_sender, stream_result_receiver) = mpsc::sync_channel(1);
+        thread::Builder::new().
+                name("audio".to_string()).
+                spawn(move || {
+                    let (_stream, stream_handle) = match OutputStream::try_default() {
+                        Ok(output_stream) => output_stream,
+                        Err(err) => {
+                            let _ = stream_result_sender.send(Err(err.to_string()));
+
+                            return;
+                        },
+                    };
+                    let _ = stream_result_sender.send(Ok(()));
 
-        Ok(Self {
-            _stream,
+                    let audio_thread = AudioThread {
+                        _stream,
 
-            stream_handle,
-        })
+                        stream_handle,
+
+                        sound_effects: decode_sound_effects(),
+                        sound_pack: None,
+
+                        channels: Vec::new(),
+
+                        music: None,
+                        fading_out_music: Vec::new(),
+                    };
+                    audio_thread.run(receiver);
+                })?;
+
+        stream_result_receiver.recv()?.map_err(|err| AudioError::new(format!("Cannot open audio output: {err}")))?;
+
+        Ok(Self { sender })
+    }
+
+    fn send(&self, command: AudioCommand) -> Result<(), Box<dyn Error>> {
+        self.sender.send(command).
+                map_err(|_| AudioError::new("Audio thread has stopped"))?;
+
+        Ok(())
+    }
+}
+
+impl AudioBackend for AudioHandler {
+    fn play_sound_event(&self, sound_event: SoundEvent, sound_effect: SoundEffect, volume: f32, pan: Option<f32>) -> Result<(), Box<dyn Error>> {
+        self.send(AudioCommand::PlaySoundEvent(sound_event, sound_effect, volume, pan))
     }
 
-    pub fn play_sound_effect(&self, sound_effect: &'static [u8]) -> Result<(), Box<dyn Error>> {
-        let cursor = Cursor::new(sound_effect);
-        let source = Decoder::new(cursor)?;
+    fn update_music(&self, music: Option<&Music>, volume: f32, is_paused: bool) -> Result<(), Box<dyn Error>> {
+        self.send(AudioCommand::UpdateMusic(music.cloned(), volume, is_paused))
+    }
 
-        self.stream_handle.play_raw(source.convert_samples())?;
+    fn set_sound_pack(&self, sound_pack: Option<SoundPack>) -> Result<(), Box<dyn Error>> {
+        self.send(AudioCommand::SetSoundPack(sound_pack))
+    }
 
+    fn stop_all(&self) -> Result<(), Box<dyn Error>> {
+        self.send(AudioCommand::StopAll)
+    }
+}
+
+#[cfg(test)]
+#[derive(Debug, Clone, Copy)]
+struct RecordedSoundEvent {
+    sound_event: SoundEvent,
+    pan: Option<f32>,
+}
+
+//Records all played sound events instead of playing them (Clones share the recorded sound events)
+#[cfg(test)]
+#[derive(Debug, Default, Clone)]
+pub struct RecordingAudioBackend {
+    sound_events: Rc<RefCell<Vec<RecordedSoundEvent>>>,
+}
+
+#[cfg(test)]
+impl RecordingAudioBackend {
+    pub fn sound_events(&self) -> Vec<SoundEvent> {
+        self.sound_events.borrow().iter().
+                map(|recorded| recorded.sound_event).
+                collect()
+    }
+
+    pub fn pans(&self) -> Vec<Option<f32>> {
+        self.sound_events.borrow().iter().
+                map(|recorded| recorded.pan).
+                collect()
+    }
+
+    pub fn clear(&self) {
+        self.sound_events.borrow_mut().clear();
+    }
+}
+
+#[cfg(test)]
+impl AudioBackend for RecordingAudioBackend {
+    fn play_sound_event(&self, sound_event: SoundEvent, _sound_effect: SoundEffect, _volume: f32, pan: Option<f32>) -> Result<(), Box<dyn Error>> {
+        self.sound_events.borrow_mut().push(RecordedSoundEvent { sound_event, pan });
+
+        Ok(())
+    }
+
+    fn update_music(&self, _music: Option<&Music>, _volume: f32, _is_paused: bool) -> Result<(), Box<dyn Error>> {
         Ok(())
     }
+
+    fn set_sound_pack(&self, _sound_pack: Option<SoundPack>) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    fn stop_all(&self) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct AudioError {
+    message: String
 }
+
+impl AudioError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self { message: message.into() }
+    }
+}
+
+impl Display for AudioError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl Error for AudioError {}
@@ -0,0 +1,74 @@
+use std::error::Error;
+use std::io::Cursor;
+use std::sync::Arc;
+use std::time::Duration;
+use rodio::{Decoder, Source};
+
+//Sound which was decoded once into memory (Playing it does not decode it again)
+#[derive(Debug, Clone)]
+pub struct DecodedSound {
+    channels: u16,
+    sample_rate: u32,
+
+    samples: Arc<[f32]>,
+}
+
+impl DecodedSound {
+    pub fn decode(data: impl AsRef<[u8]> + Send + Sync + 'static) -> Result<Self, Box<dyn Error>> {
+        let decoder = Decoder::new(Cursor::new(data))?;
+
+        let channels = decoder.channels();
+        let sample_rate = decoder.sample_rate();
+        let samples = decoder.convert_samples::<f32>().collect::<Arc<[f32]>>();
+
+        Ok(Self { channels, sample_rate, samples })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    //The samples are shared with the returned source
+    pub fn source(&self) -> DecodedSoundSource {
+        DecodedSoundSource {
+            sound: self.clone(),
+            index: 0,
+        }
+    }
+}
+
+pub struct DecodedSoundSource {
+    sound: DecodedSound,
+    index: usize,
+}
+
+impl Iterator for DecodedSoundSource {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let sample = self.sound.samples.get(self.index).copied();
+        self.index += 1;
+
+        sample
+    }
+}
+
+impl Source for DecodedSoundSource {
+    fn current_frame_len(&self) -> Option<usize> {
+        Some(self.sound.samples.len().saturating_sub(self.index))
+    }
+
+    fn channels(&self) -> u16 {
+        self.sound.channels
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.sound.sample_rate
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        let frames = self.sound.samples.len() as f64 / self.sound.channels as f64;
+
+        Some(Duration::from_secs_f64(frames / self.sound.sample_rate as f64))
+    }
+}
@@ -0,0 +1,186 @@
+use std::str::FromStr;
+use crate::game::audio::{self, SoundCategory, SoundEffect};
+use crate::game::settings::SettingsError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SoundEvent {
+    Step,
+    Blocked,
+    Undo,
+    Reset,
+    LevelComplete,
+    PackComplete,
+    SecretFound,
+    UiSelect,
+    UiError,
+    DialogOpen,
+    BookOpen,
+    BookFlip,
+    AchievementUnlocked,
+}
+
+impl SoundEvent {
+    pub const ALL: [SoundEvent; 13] = [
+        Self::Step,
+        Self::Blocked,
+        Self::Undo,
+        Self::Reset,
+        Self::LevelComplete,
+        Self::PackComplete,
+        Self::SecretFound,
+        Self::UiSelect,
+        Self::UiError,
+        Self::DialogOpen,
+        Self::BookOpen,
+        Self::BookFlip,
+        Self::AchievementUnlocked,
+    ];
+
+    fn id(&self) -> &'static str {
+        match self {
+            Self::Step => "step",
+            Self::Blocked => "blocked",
+            Self::Undo => "undo",
+            Self::Reset => "reset",
+            Self::LevelComplete => "level-complete",
+            Self::PackComplete => "pack-complete",
+            Self::SecretFound => "secret-found",
+            Self::UiSelect => "ui-select",
+            Self::UiError => "ui-error",
+            Self::DialogOpen => "dialog-open",
+            Self::BookOpen => "book-open",
+            Self::BookFlip => "book-flip",
+            Self::AchievementUnlocked => "achievement-unlocked",
+        }
+    }
+
+    pub fn category(&self) -> SoundCategory {
+        match self {
+            Self::Step | Self::Blocked | Self::Undo | Self::Reset => SoundCategory::Gameplay,
+            Self::LevelComplete | Self::PackComplete | Self::SecretFound | Self::AchievementUnlocked => SoundCategory::Jingles,
+            Self::UiSelect | Self::UiError | Self::DialogOpen | Self::BookOpen | Self::BookFlip => SoundCategory::Ui,
+        }
+    }
+
+    //The oldest sound of the event is stopped if more sounds of the event would be playing at the same time
+    pub fn max_instances(&self) -> usize {
+        match self {
+            Self::Step | Self::Undo => 3,
+            Self::Blocked | Self::UiSelect | Self::BookFlip => 2,
+
+            _ => 1,
+        }
+    }
+
+    //Sounds of this category which are still playing are stopped if the event is played
+    pub fn cancelled_category(&self) -> Option<SoundCategory> {
+        match self {
+            Self::LevelComplete | Self::PackComplete => Some(SoundCategory::Gameplay),
+
+            _ => None,
+        }
+    }
+
+    //Used by the feedback modes without sound (None: The event has no feedback in these modes)
+    pub fn feedback_text(&self) -> Option<&'static str> {
+        match self {
+            Self::Blocked => Some("No path"),
+            Self::Reset => Some("Level reset"),
+            Self::LevelComplete => Some("Level complete"),
+            Self::PackComplete => Some("Level pack complete"),
+            Self::SecretFound => Some("Secret found"),
+            Self::UiError => Some("Error"),
+
+            _ => None,
+        }
+    }
+
+    fn default_sound_effect(&self) -> SoundEffect {
+        match self {
+            Self::Step => audio::STEP_EFFECT,
+            Self::Blocked => audio::NO_PATH_EFFECT,
+            Self::Undo => audio::UNDO_REDO_EFFECT,
+            Self::Reset => audio::LEVEL_RESET_EFFECT,
+            Self::LevelComplete => audio::LEVEL_COMPLETE_EFFECT,
+            Self::PackComplete => audio::LEVEL_PACK_COMPLETE_EFFECT,
+            Self::SecretFound => audio::SECRET_FOUND_EFFECT,
+            Self::UiSelect => audio::UI_SELECT_EFFECT,
+            Self::UiError => audio::UI_ERROR_EFFECT,
+            Self::DialogOpen => audio::UI_DIALOG_OPEN_EFFECT,
+            Self::BookOpen => audio::BOOK_OPEN_EFFECT,
+            Self::BookFlip => audio::BOOK_FLIP_EFFECT,
+            Self::AchievementUnlocked => audio::ACHIEVEMENT_UNLOCKED_EFFECT,
+        }
+    }
+}
+
+impl FromStr for SoundEvent {
+    type Err = SettingsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL.into_iter().
+                find(|sound_event| sound_event.id() == s).
+                ok_or_else(|| SettingsError::new(format!("Unknown sound event \"{s}\"")))
+    }
+}
+
+//Custom mappings replace the default sound effect of their event (An empty list of sound effects mutes the event)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SoundMapping {
+    custom_mappings: Vec<(SoundEvent, Vec<SoundEffect>)>,
+}
+
+impl SoundMapping {
+    //An existing custom mapping of the event is replaced
+    pub fn set_custom_mapping(&mut self, sound_event: SoundEvent, sound_effects: Vec<SoundEffect>) {
+        self.custom_mappings.retain(|(custom_event, _)| *custom_event != sound_event);
+        self.custom_mappings.push((sound_event, sound_effects));
+    }
+
+    //One of the returned sound effects is chosen randomly if the event is played
+    pub fn sound_effects(&self, sound_event: SoundEvent) -> Vec<SoundEffect> {
+        self.custom_mappings.iter().
+                find(|(custom_event, _)| *custom_event == sound_event).
+                map(|(_, sound_effects)| sound_effects.clone()).
+                unwrap_or_else(|| vec![sound_event.default_sound_effect()])
+    }
+
+    //Parses "event,effect|effect" or "event,none" of a custom mapping line
+    pub fn parse_custom_mapping(&mut self, value: &str) -> Result<(), SettingsError> {
+        let Some((sound_event, sound_effects)) = value.split_once(",") else {
+            return Err(SettingsError::new(format!("Invalid sound mapping \"{value}\"")));
+        };
+
+        let sound_event = SoundEvent::from_str(sound_event)?;
+        let sound_effects = if sound_effects == "none" {
+            Vec::new()
+        }else {
+            sound_effects.split("|").
+                    map(|name| audio::sound_effect_from_name(name).
+                            ok_or_else(|| SettingsError::new(format!("Unknown sound effect \"{name}\"")))).
+                    collect::<Result<Vec<_>, _>>()?
+        };
+
+        self.set_custom_mapping(sound_event, sound_effects);
+
+        Ok(())
+    }
+
+    //Returns "event,effect|effect" or "event,none" of all custom mappings
+    pub fn custom_mapping_strs(&self) -> Vec<String> {
+        self.custom_mappings.iter().
+                map(|(sound_event, sound_effects)| {
+                    let sound_effects = if sound_effects.is_empty() {
+                        "none".to_string()
+                    }else {
+                        sound_effects.iter().
+                                map(|sound_effect| sound_effect.name()).
+                                collect::<Vec<_>>().
+                                join("|")
+                    };
+
+                    format!("{},{}", sound_event.id(), sound_effects)
+                }).
+                collect()
+    }
+}
@@ -0,0 +1,185 @@
+use std::error::Error;
+use std::path::Path;
+use std::time::Duration;
+use rodio::Source;
+use rodio::source::{self, SineWave};
+use super::AudioError;
+use super::decoded::DecodedSound;
+use super::sound_pack::FILE_EXTENSIONS;
+
+pub const MENU_MUSIC_TRACK: &str = "menu";
+
+//Played for level packs without music or with music which cannot be played
+pub const DEFAULT_PACK_MUSIC_TRACK: &str = "puzzle";
+
+//Amplitude of the generated sine waves (The volume of the settings is applied on top of it)
+const AMPLITUDE: f32 = 0.25;
+
+//Music of a level pack: An embedded track or an audio file which is looped
+#[derive(Debug, Clone)]
+pub enum Music {
+    Track(&'static MusicTrack),
+    File {
+        path: String,
+        sound: DecodedSound,
+    },
+}
+
+impl Music {
+    pub fn track(id: &str) -> Option<Self> {
+        music_track(id).map(Self::Track)
+    }
+
+    //Music files are resolved relative to the directory of the level pack file
+    pub fn load(level_pack_path: &str, music: &str) -> Result<Self, Box<dyn Error>> {
+        let is_file = Path::new(music).extension().
+                and_then(|extension| extension.to_str()).
+                is_some_and(|extension| FILE_EXTENSIONS.contains(&extension.to_ascii_lowercase().as_str()));
+        if !is_file {
+            let Some(track) = Self::track(music) else {
+                let track_ids = MUSIC_TRACKS.iter().
+                        map(|track| track.id).
+                        collect::<Vec<_>>();
+
+                return Err(Box::new(AudioError::new(format!(
+                    "The music track \"{}\" is unknown (Use one of {} or an audio file: .{})",
+                    music, track_ids.join(", "), FILE_EXTENSIONS.join(", ."),
+                ))));
+            };
+
+            return Ok(track);
+        }
+
+        let path = Path::new(level_pack_path).parent().
+                unwrap_or(Path::new("")).
+                join(music);
+        let data = std::fs::read(&path).
+                map_err(|err| AudioError::new(format!("Cannot read the music file \"{}\": {}", path.display(), err)))?;
+        let sound = DecodedSound::decode(data).
+                map_err(|err| AudioError::new(format!("Cannot decode the music file \"{}\": {}", path.display(), err)))?;
+        if sound.is_empty() {
+            return Err(Box::new(AudioError::new(format!("The music file \"{}\" has no audio data", path.display()))));
+        }
+
+        Ok(Self::File {
+            path: path.display().to_string(),
+            sound,
+        })
+    }
+
+    //Track id or file path (Music is only restarted if the id changes)
+    pub fn id(&self) -> &str {
+        match self {
+            Self::Track(track) => track.id(),
+            Self::File { path, .. } => path,
+        }
+    }
+
+    //Returns an endless source of the music
+    pub fn source(&self) -> Box<dyn Source<Item = f32> + Send> {
+        match self {
+            Self::Track(track) => Box::new(track.source()),
+            Self::File { sound, .. } => {
+                let sound = sound.clone();
+
+                Box::new(source::from_iter(std::iter::repeat_with(move || sound.source())))
+            },
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct MusicTrack {
+    id: &'static str,
+
+    //Milliseconds
+    note_duration: u64,
+
+    //MIDI note numbers (0 is a rest)
+    notes: &'static [u8],
+}
+
+impl MusicTrack {
+    pub fn id(&self) -> &'static str {
+        self.id
+    }
+
+    //Returns an endless source of the track
+    pub fn source(&self) -> impl Source<Item = f32> + Send + 'static {
+        let notes = self.notes.iter().
+                map(|note| {
+                    let frequency = if *note == 0 {
+                        0.0
+                    }else {
+                        440.0 * 2.0_f32.powf((*note as f32 - 69.0) / 12.0)
+                    };
+
+                    //Every note fades out until the next note starts
+                    let mut note = SineWave::new(frequency).take_duration(Duration::from_millis(self.note_duration));
+                    note.set_filter_fadeout();
+
+                    note
+                }).
+                collect::<Vec<_>>();
+
+        source::from_iter(notes).
+                amplify(AMPLITUDE).
+                repeat_infinite()
+    }
+}
+
+const MUSIC_TRACKS: [MusicTrack; 5] = [
+    MusicTrack {
+        id: MENU_MUSIC_TRACK,
+        note_duration: 250,
+        notes: &[
+            60, 64, 67, 72, 67, 64,
+            57, 60, 64, 69, 64, 60,
+            53, 57, 60, 65, 60, 57,
+            55, 59, 62, 67, 62, 59,
+        ],
+    },
+    MusicTrack {
+        id: "calm",
+        note_duration: 350,
+        notes: &[
+            64, 67, 72, 67,
+            65, 69, 72, 69,
+            62, 65, 69, 65,
+            60, 64, 67, 0,
+        ],
+    },
+    MusicTrack {
+        id: "puzzle",
+        note_duration: 200,
+        notes: &[
+            57, 0, 60, 64, 62, 0, 60, 57,
+            55, 0, 59, 62, 60, 59, 57, 0,
+            53, 0, 57, 60, 59, 57, 55, 0,
+            52, 56, 59, 64, 62, 59, 56, 0,
+        ],
+    },
+    MusicTrack {
+        id: "tense",
+        note_duration: 180,
+        notes: &[
+            45, 45, 48, 45, 51, 45, 50, 48,
+            44, 44, 47, 44, 50, 44, 48, 47,
+        ],
+    },
+    MusicTrack {
+        id: "mystery",
+        note_duration: 300,
+        notes: &[
+            62, 0, 69, 65,
+            61, 0, 67, 64,
+            62, 65, 69, 74,
+            73, 0, 0, 0,
+        ],
+    },
+];
+
+pub fn music_track(id: &str) -> Option<&'static MusicTrack> {
+    MUSIC_TRACKS.iter().
+            find(|music_track| music_track.id == id)
+}
@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use crate::game::audio::{SoundEffect, SOUND_EFFECTS};
+use crate::game::audio::decoded::DecodedSound;
+
+#[cfg(test)]
+mod tests;
+
+//Sound effect files must be named after their sound effect ("step.ogg", "level_complete.wav", ...)
+pub const FILE_EXTENSIONS: [&str; 4] = ["ogg", "wav", "flac", "mp3"];
+
+//Returns the names of all sound pack folders (Sorted by name)
+pub fn installed_sound_packs(sound_pack_folder: &Path) -> Result<Vec<String>, Box<dyn Error>> {
+    if !std::fs::exists(sound_pack_folder)? {
+        return Ok(Vec::new());
+    }
+
+    let mut names = Vec::new();
+    for entry in std::fs::read_dir(sound_pack_folder)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() && let Some(name) = entry.file_name().to_str() {
+            names.push(name.to_string());
+        }
+    }
+    names.sort();
+
+    Ok(names)
+}
+
+#[derive(Debug)]
+pub struct SoundPack {
+    //Sound effects without a valid file use the embedded sound effect
+    sound_effects: HashMap<&'static str, DecodedSound>,
+
+    //Messages of files which could not be read or decoded
+    invalid_files: Vec<String>,
+}
+
+impl SoundPack {
+    pub fn load(path: &Path) -> Result<Self, SoundPackError> {
+        if !path.is_dir() {
+            return Err(SoundPackError::new(format!("Sound pack folder \"{}\" does not exist", path.display())));
+        }
+
+        let mut sound_effects = HashMap::new();
+        let mut invalid_files = Vec::new();
+        for sound_effect in SOUND_EFFECTS {
+            let Some(file) = FILE_EXTENSIONS.iter().
+                    map(|extension| path.join(format!("{}.{}", sound_effect.name, extension))).
+                    find(|file| file.is_file()) else {
+                continue;
+            };
+
+            let file_name = file.file_name().unwrap_or_default().to_string_lossy().to_string();
+            match std::fs::read(&file) {
+                Ok(data) => match Self::decode(data) {
+                    Ok(sound) => {
+                        sound_effects.insert(sound_effect.name, sound);
+                    },
+                    Err(err) => invalid_files.push(format!("{file_name}: {err}")),
+                },
+                Err(err) => invalid_files.push(format!("{file_name}: {err}")),
+            }
+        }
+
+        Ok(Self {
+            sound_effects,
+
+            invalid_files,
+        })
+    }
+
+    //Files are decoded completely if the sound pack is loaded (Errors are found before playing and playing does not decode again)
+    fn decode(data: Vec<u8>) -> Result<DecodedSound, Box<dyn Error>> {
+        let sound = DecodedSound::decode(data)?;
+        if sound.is_empty() {
+            return Err(Box::new(SoundPackError::new("No audio data")));
+        }
+
+        Ok(sound)
+    }
+
+    pub fn sound_effect(&self, sound_effect: SoundEffect) -> Option<&DecodedSound> {
+        self.sound_effects.get(sound_effect.name)
+    }
+
+    pub fn invalid_files(&self) -> &[String] {
+        &self.invalid_files
+    }
+}
+
+#[derive(Debug)]
+pub struct SoundPackError {
+    message: String
+}
+
+impl SoundPackError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self { message: message.into() }
+    }
+}
+
+impl Display for SoundPackError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl Error for SoundPackError {}
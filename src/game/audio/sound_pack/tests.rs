@@ -0,0 +1,34 @@
+use super::*;
+use crate::game::audio::{LEVEL_COMPLETE_EFFECT, STEP_EFFECT, UI_SELECT_EFFECT};
+use crate::game::test_utils::TempDir;
+
+#[test]
+fn load_sound_pack() {
+    let temp_dir = TempDir::new("sound-packs");
+    let sound_pack_folder = temp_dir.join("sound-packs");
+
+    assert_eq!(installed_sound_packs(&sound_pack_folder).unwrap(), Vec::<String>::new());
+    assert!(SoundPack::load(&sound_pack_folder.join("retro")).is_err());
+
+    let path = sound_pack_folder.join("retro");
+    std::fs::create_dir_all(&path).unwrap();
+    std::fs::create_dir_all(sound_pack_folder.join("classic")).unwrap();
+    std::fs::write(sound_pack_folder.join("readme.txt"), "Not a sound pack").unwrap();
+
+    std::fs::write(path.join("step.ogg"), UI_SELECT_EFFECT.data).unwrap();
+    std::fs::write(path.join("level_complete.wav"), "Not audio data").unwrap();
+    std::fs::write(path.join("unknown.ogg"), UI_SELECT_EFFECT.data).unwrap();
+
+    assert_eq!(installed_sound_packs(&sound_pack_folder).unwrap(), ["classic", "retro"]);
+
+    let sound_pack = SoundPack::load(&path).unwrap();
+    let ui_select_sound = DecodedSound::decode(UI_SELECT_EFFECT.data).unwrap();
+    let step_sound = sound_pack.sound_effect(STEP_EFFECT).unwrap();
+    assert_eq!(step_sound.source().collect::<Vec<_>>(), ui_select_sound.source().collect::<Vec<_>>());
+
+    //Invalid files fall back to the embedded sound effect
+    assert!(sound_pack.sound_effect(LEVEL_COMPLETE_EFFECT).is_none());
+    assert!(sound_pack.sound_effect(UI_SELECT_EFFECT).is_none());
+    assert_eq!(sound_pack.invalid_files().len(), 1);
+    assert!(sound_pack.invalid_files()[0].starts_with("level_complete.wav: "));
+}
@@ -0,0 +1,97 @@
+use std::io::Cursor;
+use std::time::{Duration, Instant};
+use rodio::{Decoder, Source};
+use crate::game::test_utils::TempDir;
+use super::*;
+
+#[test]
+fn decoded_sound_effects() {
+    let sound_effects = decode_sound_effects();
+    assert_eq!(sound_effects.len(), SOUND_EFFECTS.len());
+
+    for sound_effect in SOUND_EFFECTS {
+        let decoder = Decoder::new(Cursor::new(sound_effect.data)).unwrap();
+        let channels = decoder.channels();
+        let sample_rate = decoder.sample_rate();
+        let samples = decoder.convert_samples::<f32>().collect::<Vec<_>>();
+
+        let source = sound_effects[sound_effect.name].source();
+        assert_eq!(source.channels(), channels);
+        assert_eq!(source.sample_rate(), sample_rate);
+        assert_eq!(source.current_frame_len(), Some(samples.len()));
+        assert_eq!(source.collect::<Vec<_>>(), samples);
+    }
+}
+
+#[test]
+fn pan_channel_volumes_of_sounds() {
+    assert_eq!(pan_channel_volumes(0.0, 1), [1.0, 1.0]);
+    assert_eq!(pan_channel_volumes(-1.0, 1), [1.0, 0.0]);
+    assert_eq!(pan_channel_volumes(0.5, 1), [0.5, 1.0]);
+    assert_eq!(pan_channel_volumes(2.0, 1), [0.0, 1.0]);
+
+    //Stereo sounds are mixed to mono without getting louder
+    assert_eq!(pan_channel_volumes(-0.5, 2), [0.5, 0.25]);
+}
+
+#[test]
+fn load_level_pack_music() {
+    let temp_dir = TempDir::new("level-pack-music");
+    let level_pack_path = temp_dir.join("pack.lvl").display().to_string();
+    std::fs::write(temp_dir.join("theme.ogg"), SOUND_EFFECTS[0].data).unwrap();
+    std::fs::write(temp_dir.join("broken.ogg"), "no audio").unwrap();
+
+    let music = Music::load(&level_pack_path, "tense").unwrap();
+    assert!(matches!(music, Music::Track(_)));
+    assert_eq!(music.id(), "tense");
+
+    //Files are resolved next to the level pack and looped
+    let music = Music::load(&level_pack_path, "theme.ogg").unwrap();
+    assert!(matches!(music, Music::File { .. }));
+    assert_eq!(music.id(), temp_dir.join("theme.ogg").display().to_string());
+    let Music::File { sound, .. } = &music else { unreachable!() };
+    let sound_len = sound.source().count();
+    assert_eq!(music.source().take(sound_len * 2 + 1).count(), sound_len * 2 + 1);
+
+    let err = Music::load(&level_pack_path, "unknown").unwrap_err();
+    assert!(err.to_string().starts_with("The music track \"unknown\" is unknown"));
+    assert!(Music::load(&level_pack_path, "missing.ogg").is_err());
+    assert!(Music::load(&level_pack_path, "broken.ogg").is_err());
+}
+
+//Compares decoding for every playback with the cache (Run with "cargo test --release -- --ignored --nocapture audio_benchmark")
+#[test]
+#[ignore]
+fn audio_benchmark() {
+    const ITERATIONS: u32 = 20;
+
+    fn average(iterations: u32, mut f: impl FnMut()) -> Duration {
+        let start = Instant::now();
+        for _ in 0..iterations {
+            f();
+        }
+
+        start.elapsed() / iterations
+    }
+
+    println!("{:<22} {:>12} {:>12}", "Sound effect", "Decode", "Cached");
+
+    let sound_effects = decode_sound_effects();
+    let mut decode_sum = Duration::ZERO;
+    for sound_effect in SOUND_EFFECTS {
+        let decode = average(ITERATIONS, || {
+            let decoder = Decoder::new(Cursor::new(sound_effect.data)).unwrap();
+            std::hint::black_box(decoder.convert_samples::<f32>().count());
+        });
+
+        //Only the source of the cached samples is created for every playback
+        let cached = average(ITERATIONS, || {
+            std::hint::black_box(sound_effects[sound_effect.name].source());
+        });
+
+        decode_sum += decode;
+        println!("{:<22} {:>12?} {:>12?}", sound_effect.name, decode, cached);
+    }
+
+    println!("{:<22} {:>12?}", "Startup decoding", decode_sum);
+}
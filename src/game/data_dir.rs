@@ -0,0 +1,174 @@
+use std::error::Error;
+use std::ffi::OsString;
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+#[cfg(test)]
+mod tests;
+
+pub const DATA_DIR_ARG: &str = "--data-dir";
+pub const DATA_DIR_ENV_VAR: &str = "CONSOLE_SOKOBAN_DATA_DIR";
+
+const APP_FOLDER: &str = "ConsoleSokoban";
+
+//Marker file in the data folder which is created after the save games of the legacy folder were copied
+const LEGACY_MIGRATION_MARKER: &str = ".legacy-migrated";
+
+static DATA_DIRS: OnceLock<DataDirs> = OnceLock::new();
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct DataDirs {
+    data_dir: PathBuf,
+    config_dir: PathBuf,
+    legacy_data_dir: Option<PathBuf>,
+}
+
+impl DataDirs {
+    //Order: CLI flag, environment variable, XDG folders (Not on Windows)
+    fn resolve(data_dir_override: Option<PathBuf>, env_var: impl Fn(&str) -> Option<OsString>) -> Result<Self, DataDirError> {
+        let data_dir_override = data_dir_override.or_else(|| env_var(DATA_DIR_ENV_VAR).
+                filter(|data_dir| !data_dir.is_empty()).
+                map(PathBuf::from));
+        if let Some(data_dir) = data_dir_override {
+            return Ok(Self {
+                config_dir: data_dir.join("config"),
+                data_dir,
+                legacy_data_dir: None,
+            });
+        }
+
+        if cfg!(windows) {
+            let mut home_drive = env_var("HOMEDRIVE").
+                    ok_or(DataDirError::new("$HOMEDRIVE not set!"))?;
+            home_drive.push(&env_var("HOMEPATH").
+                    ok_or(DataDirError::new("$HOMEPATH not set!"))?);
+
+            let data_dir = PathBuf::from(home_drive).join(".jddev0").join(APP_FOLDER);
+
+            return Ok(Self {
+                config_dir: data_dir.join("config"),
+                data_dir,
+                legacy_data_dir: None,
+            });
+        }
+
+        let home = env_var("HOME").map(PathBuf::from);
+
+        //Relative XDG paths are invalid and must be ignored
+        let xdg_dir = |name: &str, fallback: &[&str]| env_var(name).
+                map(PathBuf::from).
+                filter(|dir| dir.is_absolute()).
+                or_else(|| home.as_ref().map(|home| fallback.iter().
+                        fold(home.clone(), |dir, folder| dir.join(folder)))).
+                map(|dir| dir.join(APP_FOLDER)).
+                ok_or(DataDirError::new(format!("${name} and $HOME not set!")));
+
+        Ok(Self {
+            data_dir: xdg_dir("XDG_DATA_HOME", &[".local", "share"])?,
+            config_dir: xdg_dir("XDG_CONFIG_HOME", &[".config"])?,
+            legacy_data_dir: home.map(|home| home.join(".jddev0").join(APP_FOLDER)),
+        })
+    }
+
+    //Save games of the legacy folder are copied once (The legacy folder is kept for older versions of the game)
+    fn migrate_legacy_data_dir(&self) -> Result<(), Box<dyn Error>> {
+        let Some(legacy_data_dir) = &self.legacy_data_dir else {
+            return Ok(());
+        };
+
+        let marker_file = self.data_dir.join(LEGACY_MIGRATION_MARKER);
+        if !legacy_data_dir.is_dir() || std::fs::exists(&marker_file)? {
+            return Ok(());
+        }
+
+        std::fs::create_dir_all(&self.data_dir)?;
+
+        for entry in std::fs::read_dir(legacy_data_dir)?.
+                filter_map(|entry| entry.ok()).
+                filter(|entry| entry.path().is_file()) {
+            let target = self.data_dir.join(entry.file_name());
+            if !std::fs::exists(&target)? {
+                std::fs::copy(entry.path(), target)?;
+            }
+        }
+
+        std::fs::write(marker_file, format!("{}\n", legacy_data_dir.display()))?;
+
+        Ok(())
+    }
+}
+
+//Removes the data folder flag from the arguments and returns its value
+pub fn parse_args(mut args: impl Iterator<Item = String>) -> Result<(Option<PathBuf>, Vec<String>), DataDirError> {
+    let mut data_dir = None;
+    let mut remaining_args = Vec::new();
+
+    while let Some(arg) = args.next() {
+        let value = if arg == DATA_DIR_ARG {
+            Some(args.next().
+                    ok_or(DataDirError::new(format!("Missing value for \"{DATA_DIR_ARG}\"")))?)
+        }else {
+            arg.strip_prefix(DATA_DIR_ARG).
+                    and_then(|value| value.strip_prefix("=")).
+                    map(|value| value.to_string())
+        };
+
+        match value {
+            Some(value) if value.is_empty() => {
+                return Err(DataDirError::new(format!("Missing value for \"{DATA_DIR_ARG}\"")));
+            },
+            Some(value) => data_dir = Some(PathBuf::from(value)),
+            None => remaining_args.push(arg),
+        }
+    }
+
+    Ok((data_dir, remaining_args))
+}
+
+//Must be called before the first use of the data folders (Otherwise the folders are resolved without CLI flag)
+pub fn init(data_dir_override: Option<PathBuf>) -> Result<(), Box<dyn Error>> {
+    let data_dirs = DataDirs::resolve(data_dir_override, |name| std::env::var_os(name))?;
+    data_dirs.migrate_legacy_data_dir()?;
+
+    let _ = DATA_DIRS.set(data_dirs);
+
+    Ok(())
+}
+
+fn data_dirs() -> Result<&'static DataDirs, DataDirError> {
+    if let Some(data_dirs) = DATA_DIRS.get() {
+        return Ok(data_dirs);
+    }
+
+    let data_dirs = DataDirs::resolve(None, |name| std::env::var_os(name))?;
+
+    Ok(DATA_DIRS.get_or_init(|| data_dirs))
+}
+
+pub fn data_dir() -> Result<&'static Path, DataDirError> {
+    Ok(&data_dirs()?.data_dir)
+}
+
+pub fn config_dir() -> Result<&'static Path, DataDirError> {
+    Ok(&data_dirs()?.config_dir)
+}
+
+#[derive(Debug)]
+pub struct DataDirError {
+    message: String
+}
+
+impl DataDirError {
+    fn new(message: impl Into<String>) -> Self {
+        Self { message: message.into() }
+    }
+}
+
+impl Display for DataDirError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl Error for DataDirError {}
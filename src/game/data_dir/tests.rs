@@ -0,0 +1,99 @@
+use super::*;
+use crate::game::test_utils::TempDir;
+
+fn resolve(data_dir_override: Option<&str>, env: &[(&str, &str)]) -> Result<DataDirs, DataDirError> {
+    DataDirs::resolve(data_dir_override.map(PathBuf::from), |name| env.iter().
+            find(|(key, _)| *key == name).
+            map(|(_, value)| OsString::from(value)))
+}
+
+fn args(args: &[&str]) -> impl Iterator<Item = String> {
+    args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>().into_iter()
+}
+
+#[test]
+fn parse_data_dir_arg() {
+    let (data_dir, remaining_args) = parse_args(args(&["a.lvl", "--data-dir", "/tmp/data", "b.xsb"])).unwrap();
+    assert_eq!(data_dir, Some(PathBuf::from("/tmp/data")));
+    assert_eq!(remaining_args, ["a.lvl", "b.xsb"]);
+
+    let (data_dir, remaining_args) = parse_args(args(&["--data-dir=/tmp/data"])).unwrap();
+    assert_eq!(data_dir, Some(PathBuf::from("/tmp/data")));
+    assert!(remaining_args.is_empty());
+
+    let (data_dir, _) = parse_args(args(&["a.lvl"])).unwrap();
+    assert_eq!(data_dir, None);
+
+    assert!(parse_args(args(&["--data-dir"])).is_err());
+    assert!(parse_args(args(&["--data-dir="])).is_err());
+}
+
+#[test]
+#[cfg(not(windows))]
+fn resolve_xdg_dirs() {
+    let data_dirs = resolve(None, &[
+        ("HOME", "/home/user"),
+        ("XDG_DATA_HOME", "/data"),
+        ("XDG_CONFIG_HOME", "/config"),
+    ]).unwrap();
+
+    assert_eq!(data_dirs.data_dir, PathBuf::from("/data/ConsoleSokoban"));
+    assert_eq!(data_dirs.config_dir, PathBuf::from("/config/ConsoleSokoban"));
+    assert_eq!(data_dirs.legacy_data_dir, Some(PathBuf::from("/home/user/.jddev0/ConsoleSokoban")));
+}
+
+#[test]
+#[cfg(not(windows))]
+fn resolve_xdg_dirs_fallback() {
+    //Relative XDG paths must be ignored
+    let data_dirs = resolve(None, &[
+        ("HOME", "/home/user"),
+        ("XDG_DATA_HOME", "relative/data"),
+    ]).unwrap();
+
+    assert_eq!(data_dirs.data_dir, PathBuf::from("/home/user/.local/share/ConsoleSokoban"));
+    assert_eq!(data_dirs.config_dir, PathBuf::from("/home/user/.config/ConsoleSokoban"));
+
+    assert!(resolve(None, &[]).is_err());
+}
+
+#[test]
+fn resolve_data_dir_override() {
+    let env = [
+        ("HOME", "/home/user"),
+        (DATA_DIR_ENV_VAR, "/env/data"),
+    ];
+
+    let data_dirs = resolve(None, &env).unwrap();
+    assert_eq!(data_dirs.data_dir, PathBuf::from("/env/data"));
+    assert_eq!(data_dirs.config_dir, PathBuf::from("/env/data/config"));
+    assert_eq!(data_dirs.legacy_data_dir, None);
+
+    //The CLI flag has priority over the environment variable
+    let data_dirs = resolve(Some("/arg/data"), &env).unwrap();
+    assert_eq!(data_dirs.data_dir, PathBuf::from("/arg/data"));
+}
+
+#[test]
+fn migrate_legacy_data_dir() {
+    let root = TempDir::new("migration");
+
+    let data_dirs = DataDirs {
+        data_dir: root.join("data"),
+        config_dir: root.join("config"),
+        legacy_data_dir: Some(root.join("legacy")),
+    };
+
+    std::fs::create_dir_all(root.join("legacy")).unwrap();
+    std::fs::write(root.join("legacy").join("main.lvl.sav"), "1\n").unwrap();
+
+    data_dirs.migrate_legacy_data_dir().unwrap();
+    assert_eq!(std::fs::read_to_string(root.join("data").join("main.lvl.sav")).unwrap(), "1\n");
+
+    //Migration is only done once
+    std::fs::write(root.join("legacy").join("main.lvl.sav"), "2\n").unwrap();
+    std::fs::write(root.join("legacy").join("demon.lvl.sav"), "1\n").unwrap();
+    data_dirs.migrate_legacy_data_dir().unwrap();
+    assert_eq!(std::fs::read_to_string(root.join("data").join("main.lvl.sav")).unwrap(), "1\n");
+    assert!(!std::fs::exists(root.join("data").join("demon.lvl.sav")).unwrap());
+}
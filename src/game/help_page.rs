@@ -1,4 +1,6 @@
-use crate::game::{audio, GameState};
+use crate::game::GameState;
+use crate::game::audio::SoundEvent;
+use crate::game::input::{self, Action, KeyBindings};
 use crate::io::{Color, Console, Key};
 
 #[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
@@ -164,7 +166,7 @@ impl HelpPage {
         }
     }
 
-    pub fn draw(&self, console: &Console, width: usize, height: usize) {
+    pub fn draw(&self, console: &Console, width: usize, height: usize, key_bindings: &KeyBindings) {
         console.set_color(Color::Yellow, Color::Default);
         console.set_underline(true);
         console.draw_text("Help menu");
@@ -186,7 +188,11 @@ impl HelpPage {
                 console.set_color(Color::LightRed, Color::Default);
                 console.draw_text("F1");
                 console.reset_color();
-                console.draw_text(": Open help menu");
+                console.draw_text(": Open help menu, ");
+                console.set_color(Color::LightRed, Color::Default);
+                console.draw_text(key_bindings.key_names(Action::ToggleMute));
+                console.reset_color();
+                console.draw_text(": Mute/Unmute sound (Works everywhere)");
 
                 console.set_underline(true);
                 console.set_color(Color::Cyan, Color::Default);
@@ -241,30 +247,36 @@ impl HelpPage {
                 console.set_color(Color::LightRed, Color::Default);
                 console.draw_text("ESC");
                 console.reset_color();
-                console.draw_text(": Exit window");
-
-                console.set_underline(true);
-                console.set_color(Color::Cyan, Color::Default);
-                console.set_cursor_pos(0, 17);
-                console.draw_text("1.1.4 Game controls\n");
+                console.draw_text(": Exit window\n");
 
-                console.set_underline(false);
                 console.set_color(Color::LightRed, Color::Default);
-                console.draw_text("Arrow keys");
+                console.draw_text("c");
                 console.reset_color();
-                console.draw_text(": Move position\n");
+                console.draw_text(": Continue, ");
                 console.set_color(Color::LightRed, Color::Default);
-                console.draw_text("r");
+                console.draw_text("p");
                 console.reset_color();
-                console.draw_text(": Reset level\n");
+                console.draw_text(": Profiles, ");
                 console.set_color(Color::LightRed, Color::Default);
-                console.draw_text("z");
+                console.draw_text("s");
                 console.reset_color();
-                console.draw_text(" / ");
+                console.draw_text(": Statistics, ");
                 console.set_color(Color::LightRed, Color::Default);
-                console.draw_text("y");
+                console.draw_text("a");
+                console.reset_color();
+                console.draw_text(": Achievements, ");
+                console.set_color(Color::LightRed, Color::Default);
+                console.draw_text("o");
                 console.reset_color();
-                console.draw_text(": Undo / Redo");
+                console.draw_text(": Settings");
+
+                console.set_underline(true);
+                console.set_color(Color::Cyan, Color::Default);
+                console.set_cursor_pos(0, 17);
+                console.draw_text("1.1.4 Game controls\n");
+
+                console.set_underline(false);
+                Self::draw_game_controls(console, width, key_bindings);
             },
             3 => {
                 console.set_color(Color::Green, Color::Default);
@@ -357,6 +369,42 @@ impl HelpPage {
                 console.set_color(Color::LightRed, Color::Default);
                 console.draw_text("F1");
                 console.reset_color();
+                console.draw_text(")\n[");
+                console.set_color(Color::Default, Color::Yellow);
+                console.draw_text("L");
+                console.reset_color();
+                console.draw_text("] \"Profile: \": Select profiles (The same as ");
+                console.set_color(Color::LightRed, Color::Default);
+                console.draw_text("p");
+                console.reset_color();
+                console.draw_text(")\n[");
+                console.set_color(Color::Default, Color::Yellow);
+                console.draw_text("L");
+                console.reset_color();
+                console.draw_text("] \"Statistics\"/\"Settings\": Show statistics/settings (The same as ");
+                console.set_color(Color::LightRed, Color::Default);
+                console.draw_text("s");
+                console.reset_color();
+                console.draw_text("/");
+                console.set_color(Color::LightRed, Color::Default);
+                console.draw_text("o");
+                console.reset_color();
+                console.draw_text(")\n[");
+                console.set_color(Color::Default, Color::Yellow);
+                console.draw_text("L");
+                console.reset_color();
+                console.draw_text("] \"Achievements\": Show achievements (The same as ");
+                console.set_color(Color::LightRed, Color::Default);
+                console.draw_text("a");
+                console.reset_color();
+                console.draw_text(")\n[");
+                console.set_color(Color::Default, Color::Yellow);
+                console.draw_text("L");
+                console.reset_color();
+                console.draw_text("] \"Continue\": Resume the suspended level (The same as ");
+                console.set_color(Color::LightRed, Color::Default);
+                console.draw_text("c");
+                console.reset_color();
                 console.draw_text(")");
             },
             4 => {
@@ -365,7 +413,8 @@ impl HelpPage {
 
                 console.set_underline(false);
                 console.reset_color();
-                console.draw_text("1) No arguments\n2) \"Path to level pack 1\" \"Path to level pack 2\" ...");
+                console.draw_text("1) No arguments\n2) \"Path to level pack 1\" \"Path to level pack 2\" ...\n\n");
+                console.draw_text("Level packs can be \".lvl\" files or standard Sokoban collections (\".xsb\" or\n\".sok\" files).");
             },
             5 => {
                 console.set_color(Color::Blue, Color::Default);
@@ -442,7 +491,7 @@ impl HelpPage {
                 console.set_color(Color::LightRed, Color::Default);
                 console.draw_text("ENTER");
                 console.reset_color();
-                console.draw_text(": Selects or creates a level pack");
+                console.draw_text(": Selects, creates or repairs (Red: Broken level pack) a level pack");
 
                 console.set_cursor_pos(0, 6);
                 console.set_color(Color::LightRed, Color::Default);
@@ -452,11 +501,17 @@ impl HelpPage {
 
                 console.set_cursor_pos(0, 7);
                 console.set_color(Color::LightRed, Color::Default);
+                console.draw_text("x");
+                console.reset_color();
+                console.draw_text(": Exports the selected level pack as XSB to the current directory");
+
+                console.set_cursor_pos(0, 8);
+                console.set_color(Color::LightRed, Color::Default);
                 console.draw_text("DELETE");
                 console.reset_color();
                 console.draw_text(": Deletes the selected level pack");
 
-                console.set_cursor_pos(0, 8);
+                console.set_cursor_pos(0, 9);
                 console.set_color(Color::LightRed, Color::Default);
                 console.draw_text("ESC");
                 console.reset_color();
@@ -464,25 +519,31 @@ impl HelpPage {
 
                 console.set_underline(true);
 
-                console.set_cursor_pos(0, 10);
+                console.set_cursor_pos(0, 11);
                 console.set_color(Color::Cyan, Color::Default);
                 console.draw_text("4.1.2 Level selection\n");
 
                 console.set_underline(false);
 
-                console.set_cursor_pos(0, 11);
+                console.set_cursor_pos(0, 12);
                 console.set_color(Color::LightRed, Color::Default);
                 console.draw_text("ENTER");
                 console.reset_color();
                 console.draw_text(": Selects or create a level");
 
-                console.set_cursor_pos(0, 12);
+                console.set_cursor_pos(0, 13);
+                console.set_color(Color::LightRed, Color::Default);
+                console.draw_text("e");
+                console.reset_color();
+                console.draw_text(": Exports the selected level as RLE string to the current directory");
+
+                console.set_cursor_pos(0, 14);
                 console.set_color(Color::LightRed, Color::Default);
                 console.draw_text("DELETE");
                 console.reset_color();
                 console.draw_text(": Deletes the selected level");
 
-                console.set_cursor_pos(0, 13);
+                console.set_cursor_pos(0, 15);
                 console.set_color(Color::LightRed, Color::Default);
                 console.draw_text("ESC");
                 console.reset_color();
@@ -503,19 +564,19 @@ impl HelpPage {
 
                 console.set_cursor_pos(0, 4);
                 console.set_color(Color::LightRed, Color::Default);
-                console.draw_text("z");
+                console.draw_text(key_bindings.key_names(Action::Undo));
                 console.reset_color();
                 console.draw_text(": Undo");
 
                 console.set_cursor_pos(0, 5);
                 console.set_color(Color::LightRed, Color::Default);
-                console.draw_text("y");
+                console.draw_text(key_bindings.key_names(Action::Redo));
                 console.reset_color();
                 console.draw_text(": Redo");
 
                 console.set_cursor_pos(0, 6);
                 console.set_color(Color::LightRed, Color::Default);
-                console.draw_text("r");
+                console.draw_text(key_bindings.key_names(Action::Reset));
                 console.reset_color();
                 console.draw_text(": Goes into the editing mode");
 
@@ -553,19 +614,35 @@ impl HelpPage {
 
                 console.set_cursor_pos(0, 13);
                 console.set_color(Color::LightRed, Color::Default);
-                console.draw_text("z");
+                console.draw_text(key_bindings.key_names(Action::Undo));
                 console.reset_color();
                 console.draw_text(": Undo");
 
                 console.set_cursor_pos(0, 14);
                 console.set_color(Color::LightRed, Color::Default);
-                console.draw_text("y");
+                console.draw_text(key_bindings.key_names(Action::Redo));
                 console.reset_color();
                 console.draw_text(": Redo");
 
                 console.set_cursor_pos(0, 15);
                 console.set_color(Color::LightRed, Color::Default);
-                console.draw_text("r");
+                console.draw_text(key_bindings.key_names(Action::PreviousBranch));
+                console.reset_color();
+                console.draw_text(" / ");
+                console.set_color(Color::LightRed, Color::Default);
+                console.draw_text(key_bindings.key_names(Action::NextBranch));
+                console.reset_color();
+                console.draw_text(": Goes to the previous / next branch of the edit history");
+
+                console.set_cursor_pos(0, 16);
+                console.set_color(Color::LightRed, Color::Default);
+                console.draw_text(key_bindings.key_names(Action::LatestBranch));
+                console.reset_color();
+                console.draw_text(": Goes to the latest change of the edit history");
+
+                console.set_cursor_pos(0, 17);
+                console.set_color(Color::LightRed, Color::Default);
+                console.draw_text(key_bindings.key_names(Action::Reset));
                 console.reset_color();
                 console.draw_text(": Goes into the playing mode");
             },
@@ -683,9 +760,70 @@ impl HelpPage {
         console.draw_text(format!("{}", Self::PAGE_COUNT));
     }
 
+    //Draws the keys of the active key bindings (Entries are wrapped to the width)
+    fn draw_game_controls(console: &Console, width: usize, key_bindings: &KeyBindings) {
+        //"Arrow keys" is shown instead of the arrow keys if every movement action can be used with its arrow key
+        let move_actions = [Action::MoveUp, Action::MoveLeft, Action::MoveDown, Action::MoveRight];
+        let has_arrow_keys = move_actions.iter().
+                all(|action| key_bindings.keys(*action).contains(&action.direction_key().unwrap()));
+
+        let mut move_key_names = move_actions.iter().
+                flat_map(|action| key_bindings.keys(*action).into_iter().
+                        filter(|key| !has_arrow_keys || Some(*key) != action.direction_key())).
+                filter_map(input::key_name).
+                collect::<Vec<_>>();
+        if has_arrow_keys {
+            move_key_names.push("Arrow keys");
+        }
+
+        let entries = [
+            (vec![move_key_names.join("/")], "Move position"),
+            (vec![key_bindings.key_names(Action::PreviousBranch), key_bindings.key_names(Action::NextBranch)], "Undo branches"),
+            (vec![key_bindings.key_names(Action::LatestBranch)], "Latest branch"),
+            (vec![key_bindings.key_names(Action::Reset)], "Reset level (Can be undone)"),
+            (vec![key_bindings.key_names(Action::SaveStateSlots)], "Save state slots"),
+            (vec![key_bindings.key_names(Action::Settings)], "Settings"),
+            (vec![key_bindings.key_names(Action::Undo), key_bindings.key_names(Action::Redo)], "Undo / Redo"),
+            (vec![key_bindings.key_names(Action::SaveState), key_bindings.key_names(Action::LoadState)], "Save / Load state of the selected slot"),
+        ];
+
+        let mut x = 0;
+        for (i, (key_names, description)) in entries.iter().
+                enumerate() {
+            let entry_len = key_names.iter().map(|key_names| key_names.chars().count()).sum::<usize>() +
+                    3 * (key_names.len() - 1) + 2 + description.chars().count();
+
+            if i > 0 {
+                if x + 2 + entry_len > width {
+                    console.draw_text("\n");
+                    x = 0;
+                }else {
+                    console.reset_color();
+                    console.draw_text(", ");
+                    x += 2;
+                }
+            }
+
+            for (j, key_names) in key_names.iter().
+                    enumerate() {
+                if j > 0 {
+                    console.reset_color();
+                    console.draw_text(" / ");
+                }
+
+                console.set_color(Color::LightRed, Color::Default);
+                console.draw_text(key_names);
+            }
+            console.reset_color();
+            console.draw_text(format!(": {}", description));
+
+            x += entry_len;
+        }
+    }
+
     pub fn on_key_pressed(&mut self, game_state: &mut GameState, key: Key) {
         if key == Key::UP {
-            game_state.play_sound_effect(audio::BOOK_FLIP_EFFECT);
+            game_state.play_sound_event(SoundEvent::BookFlip);
 
             self.page = if self.page == 0 {
                 Self::PAGE_COUNT - 1
@@ -693,7 +831,7 @@ impl HelpPage {
                 self.page - 1
             };
         }else if key == Key::DOWN {
-            game_state.play_sound_effect(audio::BOOK_FLIP_EFFECT);
+            game_state.play_sound_event(SoundEvent::BookFlip);
 
             self.page = if self.page == Self::PAGE_COUNT - 1 {
                 0
@@ -705,7 +843,7 @@ impl HelpPage {
 
     pub fn on_mouse_pressed(&mut self, _width: usize, height: usize, game_state: &mut GameState, column: usize, row: usize) {
         if row >= 2 && row < height - 2 &&  let Some(page_clicked) = self.table_of_contents.get_page_mouse_clicked(height, self.page, row as u32 - 2) {
-            game_state.play_sound_effect(audio::BOOK_FLIP_EFFECT);
+            game_state.play_sound_event(SoundEvent::BookFlip);
 
             self.page = page_clicked;
         }
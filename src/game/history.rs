@@ -0,0 +1,266 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::fs::OpenOptions;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+#[cfg(test)]
+mod tests;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttemptResult {
+    Completed,
+    Abandoned,
+}
+
+impl AttemptResult {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Completed => "completed",
+            Self::Abandoned => "abandoned",
+        }
+    }
+}
+
+impl FromStr for AttemptResult {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "completed" => Ok(Self::Completed),
+            "abandoned" => Ok(Self::Abandoned),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Attempt {
+    timestamp: u64,
+    time: u64,
+    moves: u32,
+    pushes: u32,
+    undo_count: u32,
+    reset_count: u32,
+    result: AttemptResult,
+}
+
+impl Attempt {
+    pub fn new(timestamp: u64, time: u64, moves: u32, pushes: u32, undo_count: u32, reset_count: u32, result: AttemptResult) -> Self {
+        Self {
+            timestamp,
+            time,
+            moves,
+            pushes,
+            undo_count,
+            reset_count,
+            result,
+        }
+    }
+
+    //Seconds since the unix epoch
+    pub fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+
+    pub fn time(&self) -> u64 {
+        self.time
+    }
+
+    pub fn moves(&self) -> u32 {
+        self.moves
+    }
+
+    pub fn pushes(&self) -> u32 {
+        self.pushes
+    }
+
+    pub fn undo_count(&self) -> u32 {
+        self.undo_count
+    }
+
+    pub fn reset_count(&self) -> u32 {
+        self.reset_count
+    }
+
+    pub fn result(&self) -> AttemptResult {
+        self.result
+    }
+}
+
+//History lines: "level number,level hash,timestamp,time,moves,pushes,undo count,reset count,result"
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct HistoryEntry {
+    level_number: usize,
+    level_hash: u64,
+    attempt: Attempt,
+}
+
+impl Display for HistoryEntry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f, "{},{:016x},{},{},{},{},{},{},{}",
+            self.level_number,
+            self.level_hash,
+            self.attempt.timestamp,
+            self.attempt.time,
+            self.attempt.moves,
+            self.attempt.pushes,
+            self.attempt.undo_count,
+            self.attempt.reset_count,
+            self.attempt.result.as_str(),
+        )
+    }
+}
+
+impl FromStr for HistoryEntry {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tokens = s.split(",").collect::<Vec<_>>();
+        let [level_number, level_hash, timestamp, time, moves, pushes, undo_count, reset_count, result] = tokens[..] else {
+            return Err(());
+        };
+
+        Ok(Self {
+            level_number: usize::from_str(level_number).map_err(|_| ())?,
+            level_hash: u64::from_str_radix(level_hash, 16).map_err(|_| ())?,
+            attempt: Attempt {
+                timestamp: u64::from_str(timestamp).map_err(|_| ())?,
+                time: u64::from_str(time).map_err(|_| ())?,
+                moves: u32::from_str(moves).map_err(|_| ())?,
+                pushes: u32::from_str(pushes).map_err(|_| ())?,
+                undo_count: u32::from_str(undo_count).map_err(|_| ())?,
+                reset_count: u32::from_str(reset_count).map_err(|_| ())?,
+                result: AttemptResult::from_str(result)?,
+            },
+        })
+    }
+}
+
+//The history is append-only: Existing attempts are never rewritten
+pub fn append_attempt(path: &Path, level_number: usize, level_hash: u64, attempt: &Attempt) -> Result<(), Box<dyn Error>> {
+    let mut file = OpenOptions::new().create(true).read(true).append(true).open(path)?;
+
+    //The last line is incomplete if the game crashed while writing: The attempt must not be appended to it
+    if file.metadata()?.len() > 0 {
+        let mut last_byte = [0];
+        file.seek(SeekFrom::End(-1))?;
+        file.read_exact(&mut last_byte)?;
+
+        if last_byte[0] != b'\n' {
+            writeln!(file)?;
+        }
+    }
+
+    writeln!(file, "{}", HistoryEntry { level_number, level_hash, attempt: *attempt })?;
+    file.flush()?;
+
+    Ok(())
+}
+
+fn read_entries(path: &Path) -> Result<Vec<HistoryEntry>, Box<dyn Error>> {
+    if !std::fs::exists(path)? {
+        return Ok(Vec::new());
+    }
+
+    let history_data = std::fs::read_to_string(path)?;
+
+    //Invalid lines are skipped (The last line might be incomplete if the game crashed while writing)
+    Ok(history_data.lines().
+            filter_map(|line| HistoryEntry::from_str(line.trim()).ok()).
+            collect())
+}
+
+//Attempts are matched to the level by the level hash (Attempts of changed levels are not returned)
+pub fn read_attempts(path: &Path, level_hash: u64) -> Result<Vec<Attempt>, Box<dyn Error>> {
+    Ok(read_entries(path)?.into_iter().
+            filter(|entry| entry.level_hash == level_hash).
+            map(|entry| entry.attempt).
+            collect())
+}
+
+//Returns the attempts of all levels (Including attempts of changed levels)
+pub fn read_all_attempts(path: &Path) -> Result<Vec<Attempt>, Box<dyn Error>> {
+    Ok(read_entries(path)?.into_iter().
+            map(|entry| entry.attempt).
+            collect())
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LevelHistory {
+    attempt_count: usize,
+    completed_count: usize,
+    average_time: Option<u64>,
+    average_moves: Option<u32>,
+    best_moves_progression: Vec<u32>,
+    reset_count: u64,
+}
+
+impl LevelHistory {
+    //Attempts must be ordered from oldest to newest
+    pub fn new(attempts: &[Attempt]) -> Self {
+        let completed_attempts = attempts.iter().
+                filter(|attempt| attempt.result == AttemptResult::Completed).
+                collect::<Vec<_>>();
+
+        let completed_count = completed_attempts.len();
+        let (average_time, average_moves) = if completed_count == 0 {
+            (None, None)
+        }else {
+            let time_sum = completed_attempts.iter().map(|attempt| attempt.time).sum::<u64>();
+            let moves_sum = completed_attempts.iter().map(|attempt| attempt.moves as u64).sum::<u64>();
+
+            (Some(time_sum / completed_count as u64), Some((moves_sum / completed_count as u64) as u32))
+        };
+
+        let mut best_moves_progression = Vec::new();
+        for attempt in completed_attempts {
+            if best_moves_progression.last().is_none_or(|best_moves| attempt.moves < *best_moves) {
+                best_moves_progression.push(attempt.moves);
+            }
+        }
+
+        let reset_count = attempts.iter().
+                map(|attempt| attempt.reset_count as u64).
+                sum();
+
+        Self {
+            attempt_count: attempts.len(),
+            completed_count,
+            average_time,
+            average_moves,
+            best_moves_progression,
+            reset_count,
+        }
+    }
+
+    pub fn attempt_count(&self) -> usize {
+        self.attempt_count
+    }
+
+    pub fn completed_count(&self) -> usize {
+        self.completed_count
+    }
+
+    //Average of completed attempts
+    pub fn average_time(&self) -> Option<u64> {
+        self.average_time
+    }
+
+    //Average of completed attempts
+    pub fn average_moves(&self) -> Option<u32> {
+        self.average_moves
+    }
+
+    //Contains the moves of each completed attempt which was a new personal best
+    pub fn best_moves_progression(&self) -> &[u32] {
+        &self.best_moves_progression
+    }
+
+    //Resets of all attempts
+    pub fn reset_count(&self) -> u64 {
+        self.reset_count
+    }
+}
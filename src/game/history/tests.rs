@@ -0,0 +1,73 @@
+use super::*;
+use crate::game::test_utils::TempDir;
+
+fn attempt(time: u64, moves: u32, result: AttemptResult) -> Attempt {
+    Attempt::new(1700000000, time, moves, 3, 1, 0, result)
+}
+
+#[test]
+fn history_entry_round_trip() {
+    let entry = HistoryEntry {
+        level_number: 3,
+        level_hash: 0xff,
+        attempt: Attempt::new(1700000000, 65432, 120, 17, 4, 2, AttemptResult::Completed),
+    };
+
+    assert_eq!(entry.to_string(), "3,00000000000000ff,1700000000,65432,120,17,4,2,completed");
+    assert_eq!(HistoryEntry::from_str(&entry.to_string()), Ok(entry));
+
+    assert!(HistoryEntry::from_str("3,00000000000000ff,1700000000,65432,120,17,4,2,unknown").is_err());
+    assert!(HistoryEntry::from_str("3,00000000000000ff,1700000000,654").is_err());
+}
+
+#[test]
+fn append_and_read_attempts() {
+    let temp_dir = TempDir::new("history");
+    let path = temp_dir.join("history.lvl.history");
+
+    assert!(read_attempts(&path, 1).unwrap().is_empty());
+
+    let first = attempt(1000, 10, AttemptResult::Abandoned);
+    let second = attempt(2000, 20, AttemptResult::Completed);
+    append_attempt(&path, 1, 1, &first).unwrap();
+    append_attempt(&path, 2, 2, &attempt(3000, 30, AttemptResult::Completed)).unwrap();
+    append_attempt(&path, 1, 1, &second).unwrap();
+
+    //Incomplete lines are skipped
+    let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+    write!(file, "1,0000000000000001,17000").unwrap();
+    drop(file);
+
+    assert_eq!(read_attempts(&path, 1).unwrap(), [first, second]);
+
+    //Attempts are not appended to incomplete lines
+    let third = attempt(4000, 40, AttemptResult::Completed);
+    append_attempt(&path, 1, 1, &third).unwrap();
+
+    assert_eq!(read_attempts(&path, 1).unwrap(), [first, second, third]);
+}
+
+#[test]
+fn level_history_stats() {
+    let level_history = LevelHistory::new(&[
+        attempt(5000, 40, AttemptResult::Completed),
+        attempt(1000, 5, AttemptResult::Abandoned),
+        attempt(3000, 50, AttemptResult::Completed),
+        attempt(4000, 30, AttemptResult::Completed),
+    ]);
+
+    assert_eq!(level_history.attempt_count(), 4);
+    assert_eq!(level_history.completed_count(), 3);
+    assert_eq!(level_history.average_time(), Some(4000));
+    assert_eq!(level_history.average_moves(), Some(40));
+    assert_eq!(level_history.best_moves_progression(), [40, 30]);
+    assert_eq!(level_history.reset_count(), 0);
+
+    let level_history = LevelHistory::new(&[
+        Attempt::new(1700000000, 5000, 40, 3, 1, 2, AttemptResult::Completed),
+        Attempt::new(1700000000, 1000, 5, 3, 1, 3, AttemptResult::Abandoned),
+    ]);
+    assert_eq!(level_history.reset_count(), 5);
+
+    assert_eq!(LevelHistory::new(&[attempt(1000, 5, AttemptResult::Abandoned)]).average_time(), None);
+}
@@ -0,0 +1,360 @@
+use std::str::FromStr;
+use crate::game::settings::SettingsError;
+use crate::io::Key;
+
+#[cfg(test)]
+mod tests;
+
+//Keys which can be bound to actions and their names (Used in the settings file and in the help page)
+const NAMED_KEYS: [(Key, &str); 89] = [
+    (Key::UP, "UP"), (Key::DOWN, "DOWN"), (Key::LEFT, "LEFT"), (Key::RIGHT, "RIGHT"),
+
+    (Key::F1, "F1"), (Key::F2, "F2"), (Key::F3, "F3"), (Key::F4, "F4"), (Key::F5, "F5"), (Key::F6, "F6"),
+    (Key::F7, "F7"), (Key::F8, "F8"), (Key::F9, "F9"), (Key::F10, "F10"), (Key::F11, "F11"), (Key::F12, "F12"),
+
+    (Key::ESC, "ESC"), (Key::DELETE, "DELETE"), (Key::ENTER, "ENTER"), (Key::TAB, "TAB"), (Key::SPACE, "SPACE"),
+
+    (Key::A, "a"), (Key::B, "b"), (Key::C, "c"), (Key::D, "d"), (Key::E, "e"), (Key::F, "f"), (Key::G, "g"),
+    (Key::H, "h"), (Key::I, "i"), (Key::J, "j"), (Key::K, "k"), (Key::L, "l"), (Key::M, "m"), (Key::N, "n"),
+    (Key::O, "o"), (Key::P, "p"), (Key::Q, "q"), (Key::R, "r"), (Key::S, "s"), (Key::T, "t"), (Key::U, "u"),
+    (Key::V, "v"), (Key::W, "w"), (Key::X, "x"), (Key::Y, "y"), (Key::Z, "z"),
+
+    (Key::DIGIT_0, "0"), (Key::DIGIT_1, "1"), (Key::DIGIT_2, "2"), (Key::DIGIT_3, "3"), (Key::DIGIT_4, "4"),
+    (Key::DIGIT_5, "5"), (Key::DIGIT_6, "6"), (Key::DIGIT_7, "7"), (Key::DIGIT_8, "8"), (Key::DIGIT_9, "9"),
+
+    (Key::EXCLAMATION_MARK, "!"), (Key::QUOTATION_MARK, "\""), (Key::NUMBER_SIGN, "#"), (Key::DOLLAR, "$"),
+    (Key::PERCENT_SIGN, "%"), (Key::AMPERSAND, "&"), (Key::APOSTROPHE, "'"), (Key::LEFT_PARENTHESIS, "("),
+    (Key::RIGHT_PARENTHESIS, ")"), (Key::ASTERISK, "*"), (Key::PLUS, "+"), (Key::COMMA, ","), (Key::MINUS, "-"),
+    (Key::DOT, "."), (Key::SLASH, "/"), (Key::COLON, ":"), (Key::SEMICOLON, ";"), (Key::LESS_THAN_SIGN, "<"),
+    (Key::EQUALS_SIGN, "="), (Key::GREATER_THAN_SIGN, ">"), (Key::QUESTION_MARK, "?"), (Key::AT_SIGN, "@"),
+    (Key::LEFT_BRACKET, "["), (Key::BACKSLASH, "\\"), (Key::RIGHT_BRACKET, "]"), (Key::CARET, "^"),
+    (Key::UNDERSCORE, "_"), (Key::BACKTICK, "`"), (Key::LEFT_CURLY_BRACKET, "{"), (Key::VERTICAL_BAR, "|"),
+    (Key::RIGHT_CURLY_BRACKET, "}"), (Key::TILDE, "~"),
+];
+
+pub fn key_name(key: Key) -> Option<&'static str> {
+    NAMED_KEYS.iter().
+            find(|(named_key, _)| *named_key == key).
+            map(|(_, name)| *name)
+}
+
+pub fn key_from_name(name: &str) -> Option<Key> {
+    NAMED_KEYS.iter().
+            find(|(_, key_name)| *key_name == name).
+            map(|(key, _)| *key)
+}
+
+//Keys which are typed in text fields (Letters, digits, symbols and SPACE)
+pub fn is_text_key(key: Key) -> bool {
+    key.to_ascii().is_some_and(|ascii| ascii.is_ascii_graphic() || ascii == b' ')
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+
+    Undo,
+    Redo,
+    Reset,
+
+    PreviousBranch,
+    NextBranch,
+    LatestBranch,
+
+    SaveStateSlots,
+    SaveState,
+    LoadState,
+
+    Settings,
+    Help,
+    Back,
+
+    ToggleMute,
+}
+
+impl Action {
+    pub const ALL: [Action; 17] = [
+        Self::MoveUp,
+        Self::MoveDown,
+        Self::MoveLeft,
+        Self::MoveRight,
+
+        Self::Undo,
+        Self::Redo,
+        Self::Reset,
+
+        Self::PreviousBranch,
+        Self::NextBranch,
+        Self::LatestBranch,
+
+        Self::SaveStateSlots,
+        Self::SaveState,
+        Self::LoadState,
+
+        Self::Settings,
+        Self::Help,
+        Self::Back,
+
+        Self::ToggleMute,
+    ];
+
+    //Used in the settings file
+    fn id(&self) -> &'static str {
+        match self {
+            Self::MoveUp => "move-up",
+            Self::MoveDown => "move-down",
+            Self::MoveLeft => "move-left",
+            Self::MoveRight => "move-right",
+
+            Self::Undo => "undo",
+            Self::Redo => "redo",
+            Self::Reset => "reset",
+
+            Self::PreviousBranch => "previous-branch",
+            Self::NextBranch => "next-branch",
+            Self::LatestBranch => "latest-branch",
+
+            Self::SaveStateSlots => "save-state-slots",
+            Self::SaveState => "save-state",
+            Self::LoadState => "load-state",
+
+            Self::Settings => "settings",
+            Self::Help => "help",
+            Self::Back => "back",
+
+            Self::ToggleMute => "toggle-mute",
+        }
+    }
+
+    //Global actions are handled in every screen (Also while text is entered)
+    pub fn is_global(&self) -> bool {
+        *self == Self::ToggleMute
+    }
+
+    //Keys of menus, the help page and the save state slots which are replaced by the keys of the action
+    pub fn menu_key(&self) -> Option<Key> {
+        match self {
+            Self::SaveStateSlots => Some(Key::TAB),
+            Self::Help => Some(Key::F1),
+            Self::Back => Some(Key::ESC),
+
+            _ => self.direction_key(),
+        }
+    }
+
+    //Movement actions are mapped to the arrow key of their direction
+    pub fn direction_key(&self) -> Option<Key> {
+        match self {
+            Self::MoveUp => Some(Key::UP),
+            Self::MoveDown => Some(Key::DOWN),
+            Self::MoveLeft => Some(Key::LEFT),
+            Self::MoveRight => Some(Key::RIGHT),
+
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for Action {
+    type Err = SettingsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL.into_iter().
+                find(|action| action.id() == s).
+                ok_or_else(|| SettingsError::new(format!("Unknown action \"{s}\"")))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum KeyPreset {
+    #[default]
+    Arrows,
+    Wasd,
+    Hjkl,
+}
+
+impl KeyPreset {
+    pub const ALL: [KeyPreset; 3] = [
+        Self::Arrows,
+        Self::Wasd,
+        Self::Hjkl,
+    ];
+
+    //Used in the settings file
+    fn id(&self) -> &'static str {
+        match self {
+            Self::Arrows => "arrows",
+            Self::Wasd => "wasd",
+            Self::Hjkl => "hjkl",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Arrows => "Arrows",
+            Self::Wasd => "WASD",
+            Self::Hjkl => "hjkl",
+        }
+    }
+
+    //The arrow keys can be used for moving with every preset
+    fn keys(&self, action: Action) -> &'static [Key] {
+        match (self, action) {
+            (Self::Wasd, Action::MoveUp) => &[Key::W, Key::UP],
+            (Self::Wasd, Action::MoveDown) => &[Key::S, Key::DOWN],
+            (Self::Wasd, Action::MoveLeft) => &[Key::A, Key::LEFT],
+            (Self::Wasd, Action::MoveRight) => &[Key::D, Key::RIGHT],
+
+            (Self::Hjkl, Action::MoveUp) => &[Key::K, Key::UP],
+            (Self::Hjkl, Action::MoveDown) => &[Key::J, Key::DOWN],
+            (Self::Hjkl, Action::MoveLeft) => &[Key::H, Key::LEFT],
+            (Self::Hjkl, Action::MoveRight) => &[Key::L, Key::RIGHT],
+            (Self::Hjkl, Action::LatestBranch) => &[Key::G],
+
+            (_, Action::MoveUp) => &[Key::UP],
+            (_, Action::MoveDown) => &[Key::DOWN],
+            (_, Action::MoveLeft) => &[Key::LEFT],
+            (_, Action::MoveRight) => &[Key::RIGHT],
+
+            (_, Action::Undo) => &[Key::Z],
+            (_, Action::Redo) => &[Key::Y],
+            (_, Action::Reset) => &[Key::R],
+
+            (_, Action::PreviousBranch) => &[Key::LEFT_BRACKET],
+            (_, Action::NextBranch) => &[Key::RIGHT_BRACKET],
+            (_, Action::LatestBranch) => &[Key::L],
+
+            (_, Action::SaveStateSlots) => &[Key::TAB],
+            (_, Action::SaveState) => &[Key::F5],
+            (_, Action::LoadState) => &[Key::F9],
+
+            (_, Action::Settings) => &[Key::F2],
+            (_, Action::Help) => &[Key::F1],
+            (_, Action::Back) => &[Key::ESC],
+
+            (_, Action::ToggleMute) => &[Key::F4],
+        }
+    }
+}
+
+impl FromStr for KeyPreset {
+    type Err = SettingsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL.into_iter().
+                find(|key_preset| key_preset.id() == s).
+                ok_or_else(|| SettingsError::new(format!("Unknown key preset \"{s}\"")))
+    }
+}
+
+//Custom bindings replace all keys of the preset for their action
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyBindings {
+    preset: KeyPreset,
+    custom_bindings: Vec<(Action, Key)>,
+}
+
+impl KeyBindings {
+    pub fn new(preset: KeyPreset) -> Self {
+        Self {
+            preset,
+            custom_bindings: Vec::new(),
+        }
+    }
+
+    pub fn preset(&self) -> KeyPreset {
+        self.preset
+    }
+
+    //Custom bindings are kept
+    pub fn set_preset(&mut self, preset: KeyPreset) {
+        self.preset = preset;
+    }
+
+    pub fn custom_bindings(&self) -> &[(Action, Key)] {
+        &self.custom_bindings
+    }
+
+    pub fn add_custom_binding(&mut self, action: Action, key: Key) {
+        if !self.custom_bindings.contains(&(action, key)) {
+            self.custom_bindings.push((action, key));
+        }
+    }
+
+    fn has_custom_keys(&self, action: Action) -> bool {
+        self.custom_bindings.iter().any(|(custom_action, _)| *custom_action == action)
+    }
+
+    pub fn keys(&self, action: Action) -> Vec<Key> {
+        if self.has_custom_keys(action) {
+            self.custom_bindings.iter().
+                    filter(|(custom_action, _)| *custom_action == action).
+                    map(|(_, key)| *key).
+                    collect()
+        }else {
+            self.preset.keys(action).to_vec()
+        }
+    }
+
+    //Custom bindings have priority over the keys of the preset
+    pub fn action(&self, key: Key) -> Option<Action> {
+        self.custom_bindings.iter().
+                find(|(_, custom_key)| *custom_key == key).
+                map(|(action, _)| *action).
+                or_else(|| Action::ALL.into_iter().
+                        filter(|action| !self.has_custom_keys(*action)).
+                        find(|action| self.preset.keys(*action).contains(&key)))
+    }
+
+    //Returns the names of the keys of the action separated by "/"
+    pub fn key_names(&self, action: Action) -> String {
+        self.keys(action).iter().
+                filter_map(|key| key_name(*key)).
+                collect::<Vec<_>>().
+                join("/")
+    }
+
+    //Parses "action,key" of a custom binding line
+    pub fn parse_custom_binding(&mut self, value: &str) -> Result<(), SettingsError> {
+        let Some((action, key)) = value.split_once(",") else {
+            return Err(SettingsError::new(format!("Invalid key binding \"{value}\"")));
+        };
+
+        let action = Action::from_str(action)?;
+        let key = key_from_name(key).
+                ok_or_else(|| SettingsError::new(format!("Unknown key \"{key}\"")))?;
+
+        //Global actions would prevent typing the key in text fields
+        if action.is_global() && is_text_key(key) {
+            return Err(SettingsError::new(format!(
+                "Key \"{}\" cannot be bound to \"{}\" (Only keys which are not used for text input are allowed)",
+                key_name(key).unwrap_or_default(),
+                action.id(),
+            )));
+        }
+
+        self.add_custom_binding(action, key);
+
+        Ok(())
+    }
+
+    pub fn preset_id(&self) -> &'static str {
+        self.preset.id()
+    }
+
+    //Returns "action,key" of all custom bindings
+    pub fn custom_binding_strs(&self) -> Vec<String> {
+        self.custom_bindings.iter().
+                filter_map(|(action, key)| key_name(*key).map(|key| format!("{},{}", action.id(), key))).
+                collect()
+    }
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
@@ -0,0 +1,90 @@
+use super::*;
+
+#[test]
+fn key_names() {
+    assert_eq!(key_name(Key::UP), Some("UP"));
+    assert_eq!(key_name(Key::Z), Some("z"));
+    assert_eq!(key_name(Key::COMMA), Some(","));
+
+    for (key, name) in NAMED_KEYS {
+        assert_eq!(key_from_name(name), Some(key));
+    }
+    assert_eq!(key_from_name("CTRL"), None);
+}
+
+#[test]
+fn preset_actions() {
+    let key_bindings = KeyBindings::new(KeyPreset::Arrows);
+    assert_eq!(key_bindings.action(Key::UP), Some(Action::MoveUp));
+    assert_eq!(key_bindings.action(Key::Z), Some(Action::Undo));
+    assert_eq!(key_bindings.action(Key::L), Some(Action::LatestBranch));
+    assert_eq!(key_bindings.action(Key::W), None);
+
+    let key_bindings = KeyBindings::new(KeyPreset::Wasd);
+    assert_eq!(key_bindings.action(Key::W), Some(Action::MoveUp));
+    assert_eq!(key_bindings.action(Key::UP), Some(Action::MoveUp));
+    assert_eq!(key_bindings.key_names(Action::MoveLeft), "a/LEFT");
+
+    let key_bindings = KeyBindings::new(KeyPreset::Hjkl);
+    assert_eq!(key_bindings.action(Key::L), Some(Action::MoveRight));
+    assert_eq!(key_bindings.action(Key::G), Some(Action::LatestBranch));
+
+    //Every action has a key in every preset
+    for preset in KeyPreset::ALL {
+        let key_bindings = KeyBindings::new(preset);
+        for action in Action::ALL {
+            assert!(!key_bindings.keys(action).is_empty());
+            assert_eq!(key_bindings.action(key_bindings.keys(action)[0]), Some(action));
+        }
+    }
+}
+
+#[test]
+fn custom_bindings() {
+    //Swapped undo and redo keys (German keyboard layout)
+    let mut key_bindings = KeyBindings::new(KeyPreset::Arrows);
+    key_bindings.add_custom_binding(Action::Undo, Key::Y);
+    key_bindings.add_custom_binding(Action::Redo, Key::Z);
+    key_bindings.add_custom_binding(Action::Redo, Key::Z);
+
+    assert_eq!(key_bindings.custom_bindings().len(), 2);
+    assert_eq!(key_bindings.action(Key::Y), Some(Action::Undo));
+    assert_eq!(key_bindings.action(Key::Z), Some(Action::Redo));
+    assert_eq!(key_bindings.keys(Action::Undo), [Key::Y]);
+
+    //Keys of the preset are replaced
+    key_bindings.add_custom_binding(Action::Reset, Key::BACKSLASH);
+    assert_eq!(key_bindings.action(Key::R), None);
+    assert_eq!(key_bindings.key_names(Action::Reset), "\\");
+
+    key_bindings.set_preset(KeyPreset::Wasd);
+    assert_eq!(key_bindings.action(Key::Y), Some(Action::Undo));
+    assert_eq!(key_bindings.action(Key::D), Some(Action::MoveRight));
+    assert_eq!(key_bindings.custom_binding_strs(), ["undo,y", "redo,z", "reset,\\"]);
+}
+
+#[test]
+fn global_actions_without_text_keys() {
+    assert!(is_text_key(Key::M));
+    assert!(is_text_key(Key::SPACE));
+    assert!(is_text_key(Key::TILDE));
+    assert!(!is_text_key(Key::F4));
+    assert!(!is_text_key(Key::ESC));
+    assert!(!is_text_key(Key::DELETE));
+
+    let mut key_bindings = KeyBindings::new(KeyPreset::Arrows);
+    assert!(key_bindings.parse_custom_binding("toggle-mute,m").is_err());
+    assert!(key_bindings.parse_custom_binding("toggle-mute,SPACE").is_err());
+    key_bindings.parse_custom_binding("toggle-mute,F8").unwrap();
+    key_bindings.parse_custom_binding("undo,m").unwrap();
+
+    assert_eq!(key_bindings.keys(Action::ToggleMute), [Key::F8]);
+    assert_eq!(key_bindings.action(Key::M), Some(Action::Undo));
+
+    //Text keys of the presets are never bound to global actions
+    for preset in KeyPreset::ALL {
+        for action in Action::ALL.into_iter().filter(Action::is_global) {
+            assert!(!preset.keys(action).iter().any(|key| is_text_key(*key)));
+        }
+    }
+}
@@ -1,10 +1,23 @@
-use crate::game::Game;
+use crate::collections::Diff;
+use crate::game::{Game, GameError};
+use crate::game::audio::Music;
+use crate::game::save_game::{self, LevelChange, LevelStats, SaveGame, SaveGameError};
+use crate::game::history::{self, Attempt, LevelHistory};
+use crate::game::settings::ColorTheme;
+use crate::game::suspend::{self, SaveState, SaveStateSlots};
 use std::error::Error;
+use std::path::PathBuf;
 use std::fmt::{Debug, Display, Formatter, Write as _};
 use std::fs::File;
 use std::io::Write;
 use std::str::FromStr;
 use crate::io::{Color, Console};
+use crate::game::level::xsb::LevelExportError;
+
+pub mod xsb;
+
+#[cfg(test)]
+mod tests;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Tile {
@@ -64,7 +77,7 @@ impl Tile {
 
             b's' | b'S' => Ok(Tile::Secret),
 
-            _ => Err(LevelLoadingError::new("Invalid tile")),
+            _ => Err(LevelLoadingError::new(LevelLoadingErrorCause::InvalidTile(a as char))),
         }
     }
 
@@ -98,81 +111,85 @@ impl Tile {
         }
     }
 
-    pub fn draw(&self, console: &Console, is_player_background: bool, inverted: bool) {
+    fn miniature_priority(&self) -> u8 {
         match self {
-            Tile::Empty => {
-                console.set_color_invertible(Color::LightBlue, Color::Default, inverted);
-                console.draw_text("-");
-            },
-            Tile::OneWayLeft => {
-                console.set_color_invertible(Color::LightBlue, Color::Default, inverted);
-                console.draw_text("<");
-            },
-            Tile::OneWayUp => {
-                console.set_color_invertible(Color::LightBlue, Color::Default, inverted);
-                console.draw_text("^");
-            },
-            Tile::OneWayRight => {
-                console.set_color_invertible(Color::LightBlue, Color::Default, inverted);
-                console.draw_text(">");
-            },
-            Tile::OneWayDown => {
-                console.set_color_invertible(Color::LightBlue, Color::Default, inverted);
-                console.draw_text("v");
-            },
-            Tile::Wall => {
-                console.set_color_invertible(Color::LightGreen, Color::Default, inverted);
-                console.draw_text("#");
-            },
-            Tile::Player => {
-                if is_player_background {
-                    console.set_color_invertible(Color::Default, Color::Yellow, inverted);
-                }else {
-                    console.set_color_invertible(Color::Yellow, Color::Default, inverted);
-                }
-                console.draw_text("P");
-            },
-            Tile::Key => {
-                console.set_color_invertible(Color::LightCyan, Color::Default, inverted);
-                console.draw_text("*");
-            },
-            Tile::KeyInGoal => {
-                console.set_color_invertible(Color::LightPink, Color::Default, inverted);
-                console.draw_text("*");
-            },
-            Tile::LockedDoor => {
-                console.set_color_invertible(Color::LightRed, Color::Default, inverted);
-                console.draw_text("=");
-            },
-            Tile::Box => {
-                console.set_color_invertible(Color::LightCyan, Color::Default, inverted);
-                console.draw_text("@");
-            },
-            Tile::BoxInGoal => {
-                console.set_color_invertible(Color::LightPink, Color::Default, inverted);
-                console.draw_text("@");
-            },
-            Tile::Goal => {
-                console.set_color_invertible(Color::LightRed, Color::Default, inverted);
-                console.draw_text("x");
-            },
-            Tile::Hole => {
-                console.set_color_invertible(Color::LightBlue, Color::Default, inverted);
-                console.draw_text("O");
-            },
-            Tile::BoxInHole => {
-                console.set_color_invertible(Color::Default, Color::LightBlue, inverted);
-                console.draw_text("@");
+            Tile::Player => 5,
+
+            Tile::Box | Tile::BoxInGoal | Tile::Key | Tile::KeyInGoal => 4,
+
+            Tile::Goal | Tile::Secret => 3,
+
+            Tile::OneWayLeft | Tile::OneWayUp | Tile::OneWayRight | Tile::OneWayDown | Tile::LockedDoor |
+            Tile::Hole | Tile::BoxInHole => 2,
+
+            Tile::Wall => 1,
+
+            Tile::Empty | Tile::DecorationBlank => 0,
+        }
+    }
+
+    fn display_char(&self) -> &'static str {
+        match self {
+            Tile::Empty => "-",
+            Tile::OneWayLeft => "<",
+            Tile::OneWayUp => "^",
+            Tile::OneWayRight => ">",
+            Tile::OneWayDown => "v",
+            Tile::Wall => "#",
+            Tile::Player => "P",
+            Tile::Key | Tile::KeyInGoal => "*",
+            Tile::LockedDoor => "=",
+            Tile::Box | Tile::BoxInGoal | Tile::BoxInHole => "@",
+            Tile::Goal => "x",
+            Tile::Hole => "O",
+            Tile::DecorationBlank => " ",
+            Tile::Secret => "+",
+        }
+    }
+
+    //Returns the foreground and background color
+    fn colors(&self, color_theme: ColorTheme) -> (Color, Color) {
+        match color_theme {
+            ColorTheme::Default => match self {
+                Tile::Empty | Tile::OneWayLeft | Tile::OneWayUp | Tile::OneWayRight | Tile::OneWayDown |
+                Tile::Hole | Tile::DecorationBlank | Tile::Secret => (Color::LightBlue, Color::Default),
+                Tile::Wall => (Color::LightGreen, Color::Default),
+                Tile::Player => (Color::Yellow, Color::Default),
+                Tile::Key | Tile::Box => (Color::LightCyan, Color::Default),
+                Tile::KeyInGoal | Tile::BoxInGoal => (Color::LightPink, Color::Default),
+                Tile::LockedDoor | Tile::Goal => (Color::LightRed, Color::Default),
+                Tile::BoxInHole => (Color::Default, Color::LightBlue),
             },
-            Tile::DecorationBlank => {
-                console.set_color_invertible(Color::LightBlue, Color::Default, inverted);
-                console.draw_text(" ");
+
+            ColorTheme::HighContrast => match self {
+                Tile::Empty | Tile::OneWayLeft | Tile::OneWayUp | Tile::OneWayRight | Tile::OneWayDown |
+                Tile::Hole | Tile::DecorationBlank | Tile::Secret => (Color::LightWhite, Color::Default),
+                Tile::Wall | Tile::BoxInHole => (Color::Black, Color::LightWhite),
+                Tile::Player => (Color::LightYellow, Color::Default),
+                Tile::Key | Tile::Box => (Color::Black, Color::LightCyan),
+                Tile::KeyInGoal | Tile::BoxInGoal => (Color::Black, Color::LightGreen),
+                Tile::LockedDoor => (Color::Black, Color::LightRed),
+                Tile::Goal => (Color::LightRed, Color::Default),
             },
-            Tile::Secret => {
-                console.set_color_invertible(Color::LightBlue, Color::Default, inverted);
-                console.draw_text("+");
+
+            //Tiles are only distinguished by their characters (The player is highlighted in order to be able to blink)
+            ColorTheme::Monochrome => match self {
+                Tile::Player => (Color::LightWhite, Color::Default),
+                Tile::BoxInHole => (Color::Default, Color::LightWhite),
+                _ => (Color::Default, Color::Default),
             },
-        };
+        }
+    }
+
+    pub fn draw(&self, console: &Console, color_theme: ColorTheme, is_player_background: bool, inverted: bool) {
+        let (foreground, background) = self.colors(color_theme);
+        if *self == Tile::Player && is_player_background {
+            console.set_color_invertible(background, foreground, inverted);
+        }else {
+            console.set_color_invertible(foreground, background, inverted);
+        }
+
+        console.draw_text(self.display_char());
     }
 }
 
@@ -308,7 +325,7 @@ impl Level {
         false
     }
 
-    pub fn draw(&self, console: &Console, x_offset: usize, y_offset: usize, is_player_background: bool, cursor_pos: Option<(usize, usize)>) {
+    pub fn draw(&self, console: &Console, x_offset: usize, y_offset: usize, color_theme: ColorTheme, is_player_background: bool, cursor_pos: Option<(usize, usize)>) {
         let mut tile_iter = self.tiles.iter();
 
         for i in 0..self.height {
@@ -316,7 +333,7 @@ impl Level {
 
             for j in 0..self.width {
                 if let Some(tile) = tile_iter.next() {
-                    tile.draw(console, is_player_background, cursor_pos.is_some_and(|(x, y)| x == j && y == i));
+                    tile.draw(console, color_theme, is_player_background, cursor_pos.is_some_and(|(x, y)| x == j && y == i));
                 }
             }
 
@@ -324,10 +341,72 @@ impl Level {
         }
     }
 
+    //Simple deadlock detection: Boxes which are stuck in a corner of walls can not be moved to a goal anymore
+    //(The level can still be completed if there are more boxes than goals)
+    pub fn has_deadlock(&self) -> bool {
+        let is_wall = |x: usize, y: usize| self.tiles[x % self.width + y % self.height * self.width] == Tile::Wall;
+
+        let mut stuck_box_count = 0;
+        let mut box_count = 0;
+        let mut goal_count = 0;
+        for (i, tile) in self.tiles.iter().
+                enumerate() {
+            match tile {
+                Tile::Box => {
+                    box_count += 1;
+
+                    //Levels wrap around at the edges
+                    let (x, y) = (i % self.width + self.width, i / self.width + self.height);
+                    let is_blocked_horizontally = is_wall(x - 1, y) || is_wall(x + 1, y);
+                    let is_blocked_vertically = is_wall(x, y - 1) || is_wall(x, y + 1);
+                    if is_blocked_horizontally && is_blocked_vertically {
+                        stuck_box_count += 1;
+                    }
+                },
+                Tile::Goal | Tile::KeyInGoal => goal_count += 1,
+
+                _ => {},
+            }
+        }
+
+        stuck_box_count > 0 && box_count - stuck_box_count < goal_count
+    }
+
+    //Each character of the miniature represents a block of tiles (The most important tile of the block is drawn)
+    pub fn draw_miniature(&self, console: &Console, x_offset: usize, y_offset: usize, max_width: usize, max_height: usize, color_theme: ColorTheme) {
+        let block_width = self.width.div_ceil(max_width.max(1));
+        let block_height = self.height.div_ceil(max_height.max(1));
+
+        for (i, y) in (0..self.height).step_by(block_height).enumerate() {
+            console.set_cursor_pos(x_offset, i + y_offset);
+
+            for x in (0..self.width).step_by(block_width) {
+                let tile = (y..(y + block_height).min(self.height)).
+                        flat_map(|y| (x..(x + block_width).min(self.width)).map(move |x| (x, y))).
+                        filter_map(|(x, y)| self.get_tile(x, y)).
+                        max_by_key(|tile| tile.miniature_priority()).
+                        unwrap_or(&Tile::Empty);
+
+                tile.draw(console, color_theme, false, false);
+            }
+        }
+
+        console.reset_color();
+    }
+
     pub fn to_str(&self) -> String {
-        let mut out = String::with_capacity(14 + self.width * self.height);
+        self.to_str_with_par_moves(None)
+    }
 
-        let _ = writeln!(out, "w: {}, h: {}", self.width, self.height);
+    //The par moves are not part of the level data (They are not included in the content hash)
+    pub fn to_str_with_par_moves(&self, par_moves: Option<u32>) -> String {
+        let mut out = String::with_capacity(24 + self.width * self.height);
+
+        let _ = write!(out, "w: {}, h: {}", self.width, self.height);
+        if let Some(par_moves) = par_moves {
+            let _ = write!(out, "{}{}", Self::PAR_MOVES_PREFIX, par_moves);
+        }
+        out += "\n";
         for row in self.tiles.chunks(self.width) {
             row.iter().map(|tile| (tile.to_ascii() as char).to_string()).for_each(|tile| out += &tile);
             out += "\n";
@@ -335,72 +414,193 @@ impl Level {
 
         out
     }
-}
 
-impl FromStr for Level {
-    type Err = LevelLoadingError;
+    pub fn to_rle_str(&self) -> Result<String, LevelExportError> {
+        xsb::level_to_rle(self)
+    }
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let lines = s.lines().collect::<Vec<_>>();
-        if lines.is_empty() {
-            return Err(LevelLoadingError::new("Level is invalid!"));
-        }
+    //FNV-1a hash of the level data (Used for matching save game stats to levels which were changed or moved)
+    pub fn content_hash(&self) -> u64 {
+        const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+        const FNV_PRIME: u64 = 0x100000001b3;
+
+        self.to_str().bytes().
+                fold(FNV_OFFSET_BASIS, |hash, byte| (hash ^ byte as u64).wrapping_mul(FNV_PRIME))
+    }
+}
+
+impl Level {
+    const PAR_MOVES_PREFIX: &'static str = ", par: ";
 
-        let line = lines.first().unwrap().trim();
+    //Parses "w: <width>, h: <height>" with an optional ", par: <moves>" suffix
+    fn parse_header(line_number: usize, line: &str) -> Result<(usize, usize, Option<u32>), LevelLoadingError> {
         if !line.starts_with("w: ") || !line.contains(", h: ") {
-            return Err(LevelLoadingError::new("Level is invalid!"));
+            return Err(LevelLoadingError::new(LevelLoadingErrorCause::MissingHeader).
+                    with_position(line_number, 1));
         }
 
-        let index = line.to_string().find(", h: ").unwrap();
+        let (line, par_moves) = match line.split_once(Self::PAR_MOVES_PREFIX) {
+            Some((line, par_moves)) => {
+                let Ok(par_moves) = u32::from_str(par_moves) else {
+                    return Err(LevelLoadingError::new(LevelLoadingErrorCause::InvalidHeader).
+                            with_position(line_number, line.len() + Self::PAR_MOVES_PREFIX.len() + 1));
+                };
 
-        let (width, height) = (&line[3..index], &line[index + 5..]);
-        let height = if let Ok(height) = usize::from_str(height) {
-            height
-        }else {
-            return Err(LevelLoadingError::new("Level is invalid!"));
+                (line, Some(par_moves))
+            },
+            None => (line, None),
         };
-        let width = if let Ok(width) = usize::from_str(width) {
-            width
-        }else {
-            return Err(LevelLoadingError::new("Level is invalid!"));
+
+        let index = line.find(", h: ").unwrap();
+
+        let (width, height) = (&line[3..index], &line[index + 5..]);
+        let (Ok(width), Ok(height)) = (usize::from_str(width), usize::from_str(height)) else {
+            return Err(LevelLoadingError::new(LevelLoadingErrorCause::InvalidHeader).
+                    with_position(line_number, 1));
         };
 
         if width == 0 || height == 0 {
-            return Err(LevelLoadingError::new("Level is invalid!"));
+            return Err(LevelLoadingError::new(LevelLoadingErrorCause::InvalidHeader).
+                    with_position(line_number, 1));
         }
 
-        let mut tiles = Vec::with_capacity(width * height);
+        Ok((width, height, par_moves))
+    }
 
-        for line in lines.into_iter().
-                skip(1).
-                map(|line| line.trim()) {
-            if line.len() != width {
-                return Err(LevelLoadingError::new("Level is invalid!"));
+    //Collects all errors of all rows instead of stopping at the first error
+    fn parse_rows(width: usize, rows: &[(usize, &str)]) -> Result<Vec<Tile>, Vec<LevelLoadingError>> {
+        let mut tiles = Vec::with_capacity(width * rows.len());
+        let mut errors = Vec::new();
+
+        for (line_number, row) in rows.iter().
+                copied() {
+            let row_len = row.chars().count();
+            if row_len != width {
+                errors.push(LevelLoadingError::new(LevelLoadingErrorCause::RowLengthMismatch {
+                    expected: width,
+                    actual: row_len,
+                }).with_position(line_number, row_len.min(width) + 1));
             }
 
-            for tile in line.bytes() {
-                tiles.push(Tile::from_ascii(tile)?);
+            for (column, tile) in row.chars().
+                    enumerate() {
+                let tile = if tile.is_ascii() {
+                    Tile::from_ascii(tile as u8)
+                }else {
+                    Err(LevelLoadingError::new(LevelLoadingErrorCause::InvalidTile(tile)))
+                };
+
+                match tile {
+                    Ok(tile) => tiles.push(tile),
+                    Err(err) => errors.push(err.with_position(line_number, column + 1)),
+                }
             }
         }
 
-        if tiles.len() != width * height {
-            return Err(LevelLoadingError::new("Level is invalid!"));
+        if errors.is_empty() {
+            Ok(tiles)
+        }else {
+            Err(errors)
+        }
+    }
+}
+
+impl FromStr for Level {
+    type Err = LevelLoadingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lines = s.lines().
+                enumerate().
+                map(|(i, line)| (i + 1, line.trim())).
+                collect::<Vec<_>>();
+        let Some((line_number, header)) = lines.first().copied() else {
+            return Err(LevelLoadingError::new(LevelLoadingErrorCause::MissingHeader));
+        };
+
+        let (width, height, _) = Self::parse_header(line_number, header)?;
+
+        let rows = &lines[1..];
+        if rows.len() != height {
+            return Err(LevelLoadingError::new(LevelLoadingErrorCause::HeightMismatch {
+                expected: height,
+                actual: rows.len(),
+            }).with_position(line_number, 1));
         }
 
+        let tiles = Self::parse_rows(width, rows).map_err(|mut errors| errors.remove(0))?;
+
         Ok(Self { width, height, tiles })
     }
 }
 
+//Changes between two levels: Only the changed tiles are stored if the size is the same
+#[derive(Debug)]
+pub enum LevelDelta {
+    Tiles(Vec<(usize, Tile, Tile)>),
+    Resized(Box<Level>, Box<Level>),
+}
+
+impl Diff for Level {
+    type Delta = LevelDelta;
+
+    fn diff(&self, new: &Self) -> Self::Delta {
+        if self.width != new.width || self.height != new.height {
+            return LevelDelta::Resized(Box::new(self.clone()), Box::new(new.clone()));
+        }
+
+        LevelDelta::Tiles(self.tiles.iter().
+                zip(new.tiles.iter()).
+                enumerate().
+                filter(|(_, (old_tile, new_tile))| old_tile != new_tile).
+                map(|(i, (old_tile, new_tile))| (i, old_tile.clone(), new_tile.clone())).
+                collect())
+    }
+
+    fn apply(&mut self, delta: &Self::Delta) {
+        match delta {
+            LevelDelta::Tiles(changed_tiles) => {
+                for (i, _, new_tile) in changed_tiles {
+                    self.tiles[*i] = new_tile.clone();
+                }
+            },
+            LevelDelta::Resized(_, new) => {
+                *self = new.as_ref().clone();
+            },
+        }
+    }
+
+    fn revert(&mut self, delta: &Self::Delta) {
+        match delta {
+            LevelDelta::Tiles(changed_tiles) => {
+                for (i, old_tile, _) in changed_tiles {
+                    self.tiles[*i] = old_tile.clone();
+                }
+            },
+            LevelDelta::Resized(old, _) => {
+                *self = old.as_ref().clone();
+            },
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct LevelWithStats {
     level: Level,
     best_time: Option<u64>,
-    best_moves: Option<u32>
+    best_moves: Option<u32>,
+
+    //Declared with an optional ", par: <moves>" suffix of the level header
+    par_moves: Option<u32>,
 }
 
 impl LevelWithStats {
     pub fn new(level: Level, best_time: Option<u64>, best_moves: Option<u32>) -> Self {
-        Self { level, best_time, best_moves }
+        Self { level, best_time, best_moves, par_moves: None }
+    }
+
+    pub fn with_par_moves(mut self, par_moves: Option<u32>) -> Self {
+        self.par_moves = par_moves;
+        self
     }
 
     pub fn level(&self) -> &Level {
@@ -418,6 +618,10 @@ impl LevelWithStats {
     pub fn best_moves(&self) -> Option<u32> {
         self.best_moves
     }
+
+    pub fn par_moves(&self) -> Option<u32> {
+        self.par_moves
+    }
 }
 
 #[derive(Debug)]
@@ -426,203 +630,323 @@ pub struct LevelPack {
     path: String,
     levels: Vec<LevelWithStats>,
 
+    //Declared with an optional "Music: <track or file>" line after the level count
+    music_track: Option<String>,
+
+    //Only loaded for playing (Level packs of the editor only keep the music track line)
+    music: Option<Music>,
+    music_error: Option<Box<dyn Error>>,
+
     min_level_not_completed: usize,
 
     level_pack_best_time_sum: Option<u64>,
     level_pack_best_moves_sum: Option<u32>,
+
+    save_game_error: Option<SaveGameError>,
+    level_changes: Vec<LevelChange>,
+
+    //Levels of XSB collections which could not be imported
+    skipped_levels_error: Option<LevelPackLoadingError>,
 }
 
 impl LevelPack {
     pub const MAX_LEVEL_PACK_COUNT: usize = 64;
     pub const MAX_LEVEL_COUNT_PER_PACK: usize = 191;
 
+    const MUSIC_TRACK_PREFIX: &'static str = "Music: ";
+
     pub fn new(id: impl Into<String>, path: impl Into<String>) -> Self {
         Self {
             id: id.into(),
             path: path.into(),
             levels: vec![],
 
+            music_track: Default::default(),
+            music: Default::default(),
+            music_error: Default::default(),
+
             min_level_not_completed: Default::default(),
             level_pack_best_time_sum: Default::default(),
             level_pack_best_moves_sum: Default::default(),
+
+            save_game_error: Default::default(),
+            level_changes: Default::default(),
+
+            skipped_levels_error: Default::default(),
         }
     }
 
     pub fn read_from_save_game(id: impl Into<String>, path: impl Into<String>, lvl_data: impl Into<String>) -> Result<Self, Box<dyn Error>> {
-        let id = id.into();
         let path = path.into();
         let lvl_data = lvl_data.into();
-
-        let mut levels = Vec::with_capacity(Self::MAX_LEVEL_COUNT_PER_PACK);
-        {
-            let lines = lvl_data.lines().collect::<Vec<_>>();
-            if lines.is_empty() {
-                return Err(Box::new(LevelLoadingError::new(format!(
-                    "The level pack file \"{path}\" is empty!"
-                ))));
+        let levels = Self::parse_levels(&path, &lvl_data)?;
+
+        let mut level_pack = Self::from_levels_with_save_game(id.into(), path, levels)?;
+        level_pack.music_track = Self::parse_music_track(&lvl_data);
+        if let Some(music_track) = &level_pack.music_track {
+            match Music::load(&level_pack.path, music_track) {
+                Ok(music) => level_pack.music = Some(music),
+                Err(err) => level_pack.music_error = Some(err),
             }
+        }
 
-            let line = lines.first().unwrap().trim();
-            if !line.starts_with("Levels: ") {
-                return Err(Box::new(LevelLoadingError::new(format!(
-                    "The level count is missing in the level pack file \"{path}\"!"
-                ))));
-            }
+        Ok(level_pack)
+    }
 
-            let line = &line[8..];
+    pub fn read_from_xsb_save_game(id: impl Into<String>, path: impl Into<String>, xsb_data: impl Into<String>) -> Result<Self, Box<dyn Error>> {
+        let path = path.into();
+        let (levels, skipped_level_errors) = xsb::parse_level_pack(&path, &xsb_data.into())?;
+        let levels = levels.into_iter().
+                map(|level| LevelWithStats::new(level, None, None)).
+                collect();
 
-            let level_count = if let Ok(level_count) = usize::from_str(line) {
-                if level_count > Self::MAX_LEVEL_COUNT_PER_PACK {
-                    return Err(Box::new(LevelLoadingError::new(format!(
-                        "There are too many levels in the level pack file \"{path}\" (Count: {line}, Max: {})!",
-                        Self::MAX_LEVEL_COUNT_PER_PACK
-                    ))));
-                }else {
-                    level_count
-                }
-            }else {
-                return Err(Box::new(LevelLoadingError::new(format!(
-                    "The level count \"{line}\" is invalid in the level pack file \"{path}\"!"
-                ))));
-            };
+        let mut level_pack = Self::from_levels_with_save_game(id.into(), path, levels)?;
+        if !skipped_level_errors.is_empty() {
+            level_pack.skipped_levels_error = Some(LevelPackLoadingError::new(skipped_level_errors));
+        }
 
-            let mut line_iter = lines.into_iter().
-                    skip(1).
-                    filter(|line| !line.trim().is_empty());
-            for i in 0..level_count {
-                let line = line_iter.next();
-                let Some(line) = line else {
-                    return Err(Box::new(LevelLoadingError::new(format!(
-                        "EOF was reached early in the level pack file \"{path}\" (Read: {} levels, Expected: {level_count} levels)!",
-                        i + 1
-                    ))));
-                };
+        Ok(level_pack)
+    }
 
-                if !line.starts_with("w: ") || !line.contains(", h: ") {
-                    return Err(Box::new(LevelLoadingError::new(format!(
-                        "Level {} is invalid in the level pack file \"{path}\"!",
-                        i + 1
-                    ))));
-                }
+    //Level packs for the editor have no save game
+    pub fn read_editor_level_pack(id: impl Into<String>, path: impl Into<String>, lvl_data: &str) -> Result<Self, LevelPackLoadingError> {
+        let mut level_pack = Self::new(id, path);
+        level_pack.levels = Self::parse_levels(&level_pack.path, lvl_data)?;
+        level_pack.music_track = Self::parse_music_track(lvl_data);
 
-                let index = line.to_string().find(", h: ").unwrap() + 5;
-                let height = if let Ok(height) = usize::from_str(&line[index..]) {
-                    height
-                }else {
-                    return Err(Box::new(LevelLoadingError::new(format!(
-                        "Level {} is invalid in the level pack file \"{path}\"!",
-                        i + 1
-                    ))));
-                };
+        Ok(level_pack)
+    }
 
-                let mut level_str = Vec::with_capacity(1 + height);
-                level_str.push(line);
-                for _ in 0..height {
-                    if let Some(line) = line_iter.next() {
-                        level_str.push(line);
-                    }else {
-                        return Err(Box::new(LevelLoadingError::new(format!(
-                            "EOF was reached early during parsing of level {} is invalid in the level pack file \"{path}\"!",
-                            i + 1
-                        ))));
-                    }
-                }
+    //Checks level pack data without loading the save game (Used for repairing broken level packs)
+    pub fn check_level_pack_data(path: &str, lvl_data: &str, is_editor_level_pack: bool) -> Result<(), LevelPackLoadingError> {
+        Self::read_editor_level_pack("", path, lvl_data)?.validate(is_editor_level_pack)
+    }
 
-                let level = Level::from_str(&level_str.join("\n"));
-                match level {
-                    Ok(level) => levels.push(level),
-                    Err(err) => {
-                        return Err(Box::new(LevelLoadingError::new(format!(
-                            "\"{}\" occurred during parsing of level {} is invalid in the level pack file \"{path}\"!",
-                            err, i + 1
-                        ))));
-                    },
-                }
-            }
+    fn parse_levels(path: &str, lvl_data: &str) -> Result<Vec<LevelWithStats>, LevelPackLoadingError> {
+        let mut levels = Vec::with_capacity(Self::MAX_LEVEL_COUNT_PER_PACK);
+        let mut errors = Vec::new();
+
+        let lines = lvl_data.lines().
+                enumerate().
+                map(|(i, line)| (i + 1, line.trim())).
+                collect::<Vec<_>>();
+        let Some((_, line)) = lines.first().copied() else {
+            return Err(LevelPackLoadingError::new(vec![
+                LevelLoadingError::new(LevelLoadingErrorCause::EmptyFile).with_pack_path(path),
+            ]));
+        };
 
-            if line_iter.next().is_some() {
-                return Err(Box::new(LevelLoadingError::new(format!(
-                    "Additional data was found after last level was parsed in the level pack file \"{path}\"!"
-                ))));
+        //Parsing continues without a valid level count in order to find errors in the levels as well
+        let level_count = if let Some(level_count) = line.strip_prefix("Levels: ") {
+            match usize::from_str(level_count) {
+                Ok(level_count) if level_count > Self::MAX_LEVEL_COUNT_PER_PACK => {
+                    errors.push(LevelLoadingError::new(LevelLoadingErrorCause::TooManyLevels {
+                        count: level_count,
+                        max: Self::MAX_LEVEL_COUNT_PER_PACK,
+                    }).with_position(1, 9).with_pack_path(path));
+
+                    None
+                },
+                Ok(level_count) => Some(level_count),
+                Err(_) => {
+                    errors.push(LevelLoadingError::new(LevelLoadingErrorCause::InvalidLevelCount(level_count.to_string())).
+                            with_position(1, 9).with_pack_path(path));
+
+                    None
+                },
             }
-        }
+        }else {
+            errors.push(LevelLoadingError::new(LevelLoadingErrorCause::MissingLevelCount).
+                    with_position(1, 1).with_pack_path(path));
 
-        let mut save_game_file = Game::get_or_create_save_game_folder()?;
-        save_game_file.push(&id);
-        save_game_file.push(".lvl.sav");
+            None
+        };
 
-        let mut min_level_not_completed= Default::default();
-        let mut level_stats: Vec<(Option<u64>, Option<u32>)> = vec![Default::default(); Self::MAX_LEVEL_COUNT_PER_PACK];
-        'read_save_game: {
-            if std::fs::exists(&save_game_file)? {
-                let save_game_data = std::fs::read_to_string(&save_game_file)?;
+        let lines = lines.into_iter().
+                skip(1).
+                filter(|(_, line)| !line.is_empty()).
+                collect::<Vec<_>>();
 
-                let lines = save_game_data.lines().collect::<Vec<_>>();
-                if lines.is_empty() {
-                    //TODO add warning message (could not load save file '&id + ".lvl.sav"')
+        //The music track line is parsed by parse_music_track()
+        let mut index = if lines.first().is_some_and(|(_, line)| line.starts_with(Self::MUSIC_TRACK_PREFIX)) {
+            1
+        }else {
+            0
+        };
+        let mut level_number = 0;
+        while index < lines.len() && level_count.is_none_or(|level_count| level_number < level_count) {
+            level_number += 1;
 
-                    break 'read_save_game;
-                }
+            let (line_number, header) = lines[index];
+            index += 1;
 
-                let line = lines.first().unwrap().trim();
+            let (width, height, par_moves) = match Level::parse_header(line_number, header) {
+                Ok(header) => header,
+                Err(err) => {
+                    errors.push(err.with_level(level_number).with_pack_path(path));
 
-                min_level_not_completed = if let Ok(min_level_not_completed) = usize::from_str(line) {
-                    min_level_not_completed
-                }else {
-                    //TODO add warning message (could not load save file '&id + ".lvl.sav"')
+                    //Skip to the next level header
+                    while index < lines.len() && !lines[index].1.starts_with("w: ") {
+                        index += 1;
+                    }
 
-                    break 'read_save_game;
-                };
+                    continue;
+                },
+            };
 
-                for (i, mut line) in lines.iter().
-                        skip(1).
-                        take(Self::MAX_LEVEL_COUNT_PER_PACK).
-                        map(|line| line.trim()).
-                        enumerate() {
-                    let is_new_format = line.starts_with("ms");
-                    if is_new_format {
-                        line = &line[2..];
-                    }
+            //Rows end early if the next level header or EOF is reached
+            let rows_start = index;
+            while index < lines.len() && index - rows_start < height && !lines[index].1.starts_with("w: ") {
+                index += 1;
+            }
+            let rows = &lines[rows_start..index];
 
-                    let tokens = line.split(",").collect::<Vec<_>>();
-                    if tokens.len() != 2 {
-                        continue;
-                    }
+            //Additional rows with the same width belong to this level
+            let extra_rows_start = index;
+            while index < lines.len() && !lines[index].1.starts_with("w: ") && lines[index].1.chars().count() == width {
+                index += 1;
+            }
+            let actual_height = rows.len() + index - extra_rows_start;
 
-                    let best_time = u64::from_str(tokens[0]).ok().map(|best_time| {
-                        if is_new_format {
-                            best_time
-                        }else {
-                            best_time * 1000 + 999
-                        }
-                    });
-                    let best_moves = u32::from_str(tokens[1]).ok();
+            if actual_height != height {
+                errors.push(LevelLoadingError::new(LevelLoadingErrorCause::HeightMismatch {
+                    expected: height,
+                    actual: actual_height,
+                }).with_position(line_number, 1).with_level(level_number).with_pack_path(path));
 
-                    level_stats[i] = (best_time, best_moves);
-                }
+                continue;
+            }
+
+            match Level::parse_rows(width, rows) {
+                Ok(tiles) => levels.push(LevelWithStats::new(Level { width, height, tiles }, None, None).
+                        with_par_moves(par_moves)),
+                Err(level_errors) => errors.extend(level_errors.into_iter().
+                        map(|err| err.with_level(level_number).with_pack_path(path))),
             }
         }
 
-        let levels = levels.into_iter().
-                zip(level_stats).
-                map(|(level, (best_time, best_moves))| {
-                    LevelWithStats::new(level, best_time, best_moves)
-                }).collect::<Vec<_>>();
+        if let Some(level_count) = level_count && level_number < level_count {
+            errors.push(LevelLoadingError::new(LevelLoadingErrorCause::LevelCountMismatch {
+                expected: level_count,
+                actual: level_number,
+            }).with_pack_path(path));
+        }
+
+        if let Some((line_number, _)) = lines.get(index) {
+            errors.push(LevelLoadingError::new(LevelLoadingErrorCause::TrailingData).
+                    with_position(*line_number, 1).with_pack_path(path));
+        }
+
+        if errors.is_empty() {
+            Ok(levels)
+        }else {
+            Err(LevelPackLoadingError::new(errors))
+        }
+    }
+
+    //Music which cannot be played is not an error (The default track is played instead and a warning is shown)
+    fn parse_music_track(lvl_data: &str) -> Option<String> {
+        lvl_data.lines().
+                map(|line| line.trim()).
+                filter(|line| !line.is_empty()).
+                nth(1).
+                and_then(|line| line.strip_prefix(Self::MUSIC_TRACK_PREFIX)).
+                map(|music_track| music_track.trim().to_string())
+    }
 
+    fn save_game_file(id: &str) -> Result<PathBuf, Box<dyn Error>> {
+        Ok(Game::get_or_create_save_game_folder()?.join(format!("{}.lvl.sav", id)))
+    }
+
+    fn history_file(id: &str) -> Result<PathBuf, Box<dyn Error>> {
+        Ok(Game::get_or_create_save_game_folder()?.join(format!("{}.lvl.history", id)))
+    }
+
+    fn save_states_file(id: &str) -> Result<PathBuf, Box<dyn Error>> {
+        Ok(Game::get_or_create_save_game_folder()?.join(format!("{}.lvl.slots", id)))
+    }
+
+    fn from_levels_with_save_game(id: String, path: String, levels: Vec<LevelWithStats>) -> Result<Self, Box<dyn Error>> {
         let mut level_pack = Self {
             id,
             path,
             levels,
 
-            min_level_not_completed,
+            music_track: Default::default(),
+            music: Default::default(),
+            music_error: Default::default(),
+
+            min_level_not_completed: Default::default(),
             level_pack_best_time_sum: Default::default(),
             level_pack_best_moves_sum: Default::default(),
+
+            save_game_error: Default::default(),
+            level_changes: Default::default(),
+
+            skipped_levels_error: Default::default(),
         };
-        level_pack.calculate_stats_sum();
+
+        //Loading the save game can archive stats and rewrite the save game: Invalid level packs must be rejected first
+        level_pack.validate(false)?;
+        level_pack.reload_save_game()?;
 
         Ok(level_pack)
     }
 
+    //Replaces the stats with the stats of the save game of the current profile
+    pub fn reload_save_game(&mut self) -> Result<(), Box<dyn Error>> {
+        let save_game_file = Self::save_game_file(&self.id)?;
+
+        //Corrupt save games are reported to the user after loading (The progress is reset until the backup is restored)
+        let save_game = match SaveGame::read(&save_game_file, self.levels.len()) {
+            Ok(save_game) => {
+                self.save_game_error = None;
+
+                save_game.unwrap_or_default()
+            },
+            Err(err) => {
+                self.save_game_error = Some(err);
+
+                SaveGame::default()
+            },
+        };
+
+        self.level_changes.clear();
+
+        self.apply_save_game(&save_game)
+    }
+
+    fn apply_save_game(&mut self, save_game: &SaveGame) -> Result<(), Box<dyn Error>> {
+        self.min_level_not_completed = save_game.min_level_not_completed();
+
+        let level_hashes = self.levels.iter().
+                map(|level| level.level().content_hash()).
+                collect::<Vec<_>>();
+        let level_stats_match = save_game.match_level_stats(&level_hashes);
+
+        for (level, level_stats) in self.levels.iter_mut().
+                zip(level_stats_match.level_stats()) {
+            level.best_time = level_stats.best_time();
+            level.best_moves = level_stats.best_moves();
+        }
+
+        self.calculate_stats_sum();
+
+        //The save game is updated immediately in order to archive the stats only once
+        if !level_stats_match.level_changes().is_empty() {
+            let save_game_file = Self::save_game_file(&self.id)?;
+            if !level_stats_match.archived_level_stats().is_empty() {
+                save_game::archive_level_stats(&save_game_file, level_stats_match.archived_level_stats())?;
+            }
+
+            self.save_save_game()?;
+
+            self.level_changes.extend_from_slice(level_stats_match.level_changes());
+        }
+
+        Ok(())
+    }
+
     pub fn save_editor_level_pack(&self) -> Result<(), Box<dyn Error>> {
         self.save_editor_level_pack_to_path(&self.path)
     }
@@ -631,36 +955,102 @@ impl LevelPack {
         let mut file = File::create(path.into())?;
 
         writeln!(file, "Levels: {}", self.levels.len())?;
+        if let Some(music_track) = &self.music_track {
+            writeln!(file, "{}{}", Self::MUSIC_TRACK_PREFIX, music_track)?;
+        }
 
-        for level in self.levels.iter().
-                map(|level| level.level()) {
-            write!(file, "\n{}", level.to_str())?;
+        for level in self.levels.iter() {
+            write!(file, "\n{}", level.level().to_str_with_par_moves(level.par_moves()))?;
         }
         file.flush()?;
 
         Ok(())
     }
 
+    pub fn save_editor_level_pack_to_xsb_path(&self, path: impl Into<String>) -> Result<(), Box<dyn Error>> {
+        //Convert before creating the file in order to not leave an incomplete file behind
+        let xsb_data = xsb::level_pack_to_xsb(&self.id, self.levels.iter().map(|level| level.level()))?;
+
+        let mut file = File::create(path.into())?;
+        file.write_all(xsb_data.as_bytes())?;
+        file.flush()?;
+
+        Ok(())
+    }
+
     pub fn save_save_game(&self) -> Result<(), Box<dyn Error>> {
-        let mut save_game_file = Game::get_or_create_save_game_folder()?;
-        save_game_file.push(&self.id);
-        save_game_file.push(".lvl.sav");
+        let save_game = SaveGame::from_level_stats(
+            self.min_level_not_completed,
+            self.levels.iter().
+                    map(|level| LevelStats::new(level.best_time, level.best_moves, Some(level.level().content_hash()))).
+                    collect(),
+        );
+
+        save_game.write(&Self::save_game_file(&self.id)?, self.levels.len())
+    }
 
-        let mut file = File::create(save_game_file)?;
+    pub fn has_save_game_backup(&self) -> bool {
+        Self::save_game_file(&self.id).is_ok_and(|save_game_file| {
+            SaveGame::read(&save_game::backup_path(&save_game_file), self.levels.len()).
+                    is_ok_and(|save_game| save_game.is_some())
+        })
+    }
 
-        writeln!(file, "{}", self.min_level_not_completed)?;
+    pub fn restore_save_game_backup(&mut self) -> Result<(), Box<dyn Error>> {
+        let save_game_file = Self::save_game_file(&self.id)?;
+        let save_game = SaveGame::read(&save_game::backup_path(&save_game_file), self.levels.len())?.
+                ok_or(GameError::new("The backup does not exist"))?;
 
-        for level in self.levels.iter().
-                take(self.min_level_not_completed) {
-            writeln!(
-                file, "ms{},{}",
-                level.best_time.map_or(-1, |best_time| best_time as i64),
-                level.best_moves.map_or(-1, |best_moves| best_moves as i32)
-            )?;
-        }
-        file.flush()?;
+        self.save_game_error = None;
+        self.apply_save_game(&save_game)?;
 
-        Ok(())
+        self.save_save_game()
+    }
+
+    pub fn append_attempt(&self, index: usize, attempt: &Attempt) -> Result<(), Box<dyn Error>> {
+        let level = self.levels.get(index).
+                ok_or_else(|| GameError::new(format!("Level {} does not exist", index + 1)))?;
+
+        history::append_attempt(&Self::history_file(&self.id)?, index + 1, level.level().content_hash(), attempt)
+    }
+
+    pub fn read_level_history(&self, index: usize) -> Result<LevelHistory, Box<dyn Error>> {
+        let level = self.levels.get(index).
+                ok_or_else(|| GameError::new(format!("Level {} does not exist", index + 1)))?;
+
+        let attempts = history::read_attempts(&Self::history_file(&self.id)?, level.level().content_hash())?;
+
+        Ok(LevelHistory::new(&attempts))
+    }
+
+    pub fn read_attempts(&self) -> Result<Vec<Attempt>, Box<dyn Error>> {
+        history::read_all_attempts(&Self::history_file(&self.id)?)
+    }
+
+    pub fn read_save_states(&self, index: usize) -> Result<SaveStateSlots, Box<dyn Error>> {
+        let level = self.levels.get(index).
+                ok_or_else(|| GameError::new(format!("Level {} does not exist", index + 1)))?;
+
+        suspend::read_save_states(&Self::save_states_file(&self.id)?, level.level().content_hash())
+    }
+
+    pub fn write_save_state(&self, index: usize, slot: usize, save_state: &SaveState) -> Result<(), Box<dyn Error>> {
+        let level = self.levels.get(index).
+                ok_or_else(|| GameError::new(format!("Level {} does not exist", index + 1)))?;
+
+        suspend::write_save_state(&Self::save_states_file(&self.id)?, index + 1, level.level().content_hash(), slot, save_state)
+    }
+
+    pub fn take_save_game_error(&mut self) -> Option<SaveGameError> {
+        self.save_game_error.take()
+    }
+
+    pub fn take_level_changes(&mut self) -> Vec<LevelChange> {
+        std::mem::take(&mut self.level_changes)
+    }
+
+    pub fn skipped_levels_error(&self) -> Option<&LevelPackLoadingError> {
+        self.skipped_levels_error.as_ref()
     }
 
     pub fn id(&self) -> &str {
@@ -671,6 +1061,18 @@ impl LevelPack {
         &self.path
     }
 
+    pub fn music_track(&self) -> Option<&str> {
+        self.music_track.as_deref()
+    }
+
+    pub fn music(&self) -> Option<&Music> {
+        self.music.as_ref()
+    }
+
+    pub fn music_error(&self) -> Option<&dyn Error> {
+        self.music_error.as_deref()
+    }
+
     pub fn levels(&self) -> &[LevelWithStats] {
         &self.levels
     }
@@ -719,6 +1121,52 @@ impl LevelPack {
         Some(())
     }
 
+    //Level packs for the editor might be empty and might contain levels without a player tile
+    pub fn validate(&self, is_editor_level_pack: bool) -> Result<(), LevelPackLoadingError> {
+        let mut errors = Vec::new();
+
+        if !is_editor_level_pack && self.levels.is_empty() {
+            errors.push(LevelLoadingError::new(LevelLoadingErrorCause::NoLevels).with_pack_path(&self.path));
+        }
+
+        if self.levels.len() > Self::MAX_LEVEL_COUNT_PER_PACK {
+            errors.push(LevelLoadingError::new(LevelLoadingErrorCause::TooManyLevels {
+                count: self.levels.len(),
+                max: Self::MAX_LEVEL_COUNT_PER_PACK,
+            }).with_pack_path(&self.path));
+        }
+
+        for (i, level) in self.levels.iter().
+                map(|level| level.level()).
+                enumerate() {
+            if level.width() > Game::LEVEL_MAX_WIDTH || level.height() > Game::LEVEL_MAX_HEIGHT {
+                errors.push(LevelLoadingError::new(LevelLoadingErrorCause::LevelTooLarge {
+                    max_width: Game::LEVEL_MAX_WIDTH,
+                    max_height: Game::LEVEL_MAX_HEIGHT,
+                }).with_level(i + 1).with_pack_path(&self.path));
+            }
+
+            if is_editor_level_pack {
+                continue;
+            }
+
+            let player_tile_count = level.tiles().iter().filter(|tile| **tile == Tile::Player).count();
+            if player_tile_count == 0 {
+                errors.push(LevelLoadingError::new(LevelLoadingErrorCause::MissingPlayer).
+                        with_level(i + 1).with_pack_path(&self.path));
+            }else if player_tile_count > 1 {
+                errors.push(LevelLoadingError::new(LevelLoadingErrorCause::TooManyPlayers).
+                        with_level(i + 1).with_pack_path(&self.path));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        }else {
+            Err(LevelPackLoadingError::new(errors))
+        }
+    }
+
     pub fn add_level(&mut self, level: Level) {
         self.levels.push(LevelWithStats::new(level, None, None));
 
@@ -755,21 +1203,185 @@ impl LevelPack {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LevelLoadingErrorCause {
+    EmptyFile,
+    MissingLevelCount,
+    InvalidLevelCount(String),
+    TooManyLevels {
+        count: usize,
+        max: usize,
+    },
+    LevelCountMismatch {
+        expected: usize,
+        actual: usize,
+    },
+    TrailingData,
+
+    MissingHeader,
+    InvalidHeader,
+    InvalidTile(char),
+    RowLengthMismatch {
+        expected: usize,
+        actual: usize,
+    },
+    HeightMismatch {
+        expected: usize,
+        actual: usize,
+    },
+
+    UnsupportedXsbTile(char),
+    XsbPlayerOnGoal,
+
+    NoLevels,
+    LevelTooLarge {
+        max_width: usize,
+        max_height: usize,
+    },
+    MissingPlayer,
+    TooManyPlayers,
+}
+
+impl Display for LevelLoadingErrorCause {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::EmptyFile => f.write_str("The level pack file is empty"),
+            Self::MissingLevelCount => f.write_str("The level count (\"Levels: <count>\") is missing"),
+            Self::InvalidLevelCount(level_count) => write!(f, "The level count \"{level_count}\" is invalid"),
+            Self::TooManyLevels { count, max } => write!(f, "There are too many levels (Count: {count}, Max: {max})"),
+            Self::LevelCountMismatch { expected, actual } => write!(f, "EOF was reached early (Read: {actual} levels, Expected: {expected} levels)"),
+            Self::TrailingData => f.write_str("Additional data was found after the last level"),
+
+            Self::MissingHeader => f.write_str("The level header (\"w: <width>, h: <height>\") is missing"),
+            Self::InvalidHeader => f.write_str("The level header contains an invalid width, height or par"),
+            Self::InvalidTile(tile) => write!(f, "Invalid tile {tile:?}"),
+            Self::RowLengthMismatch { expected, actual } => write!(f, "The row length ({actual}) does not match the level width ({expected})"),
+            Self::HeightMismatch { expected, actual } => write!(f, "The row count ({actual}) does not match the level height ({expected})"),
+
+            Self::UnsupportedXsbTile(tile) => write!(f, "Unsupported tile {tile:?}"),
+            Self::XsbPlayerOnGoal => f.write_str("Player on goal ('+') is not supported"),
+
+            Self::NoLevels => f.write_str("Level pack contains no levels"),
+            Self::LevelTooLarge { max_width, max_height } => write!(f, "Level is too large (Max: {max_width}x{max_height})"),
+            Self::MissingPlayer => f.write_str("Level does not contain a player tile"),
+            Self::TooManyPlayers => f.write_str("Level contains too many player tiles"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct LevelLoadingError {
-    message: String
+    pack_path: Option<String>,
+    level: Option<usize>,
+    line: Option<usize>,
+    column: Option<usize>,
+    cause: LevelLoadingErrorCause,
 }
 
 impl LevelLoadingError {
-    fn new(message: impl Into<String>) -> Self {
-        Self { message: message.into() }
+    pub fn new(cause: LevelLoadingErrorCause) -> Self {
+        Self {
+            pack_path: None,
+            level: None,
+            line: None,
+            column: None,
+            cause,
+        }
+    }
+
+    pub fn with_pack_path(mut self, pack_path: impl Into<String>) -> Self {
+        self.pack_path = Some(pack_path.into());
+        self
+    }
+
+    pub fn with_level(mut self, level: usize) -> Self {
+        self.level = Some(level);
+        self
+    }
+
+    pub fn with_position(mut self, line: usize, column: usize) -> Self {
+        self.line = Some(line);
+        self.column = Some(column);
+        self
+    }
+
+    pub fn line(&self) -> Option<usize> {
+        self.line
+    }
+
+    pub fn column(&self) -> Option<usize> {
+        self.column
+    }
+
+    pub fn cause(&self) -> &LevelLoadingErrorCause {
+        &self.cause
     }
 }
 
 impl Display for LevelLoadingError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.write_str(&self.message)
+        let mut location = Vec::with_capacity(4);
+        if let Some(pack_path) = &self.pack_path {
+            location.push(format!("Level pack \"{pack_path}\""));
+        }
+        if let Some(level) = self.level {
+            location.push(format!("Level {level}"));
+        }
+        if let Some(line) = self.line {
+            location.push(format!("Line {line}"));
+        }
+        if let Some(column) = self.column {
+            location.push(format!("Column {column}"));
+        }
+
+        if location.is_empty() {
+            write!(f, "{}", self.cause)
+        }else {
+            write!(f, "{}: {}", location.join(", "), self.cause)
+        }
     }
 }
 
 impl Error for LevelLoadingError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LevelPackLoadingError {
+    errors: Vec<LevelLoadingError>,
+}
+
+impl LevelPackLoadingError {
+    pub fn new(errors: Vec<LevelLoadingError>) -> Self {
+        if errors.is_empty() {
+            panic!("Errors must not be empty");
+        }
+
+        Self { errors }
+    }
+
+    pub fn errors(&self) -> &[LevelLoadingError] {
+        &self.errors
+    }
+}
+
+impl From<LevelLoadingError> for LevelPackLoadingError {
+    fn from(error: LevelLoadingError) -> Self {
+        Self::new(vec![error])
+    }
+}
+
+impl Display for LevelPackLoadingError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (i, error) in self.errors.iter().
+                enumerate() {
+            if i > 0 {
+                f.write_str("\n")?;
+            }
+
+            write!(f, "{error}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Error for LevelPackLoadingError {}
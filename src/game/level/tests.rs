@@ -0,0 +1,232 @@
+use super::*;
+
+fn parse_errors(lvl_data: &str) -> Vec<LevelLoadingError> {
+    LevelPack::parse_levels("test.lvl", lvl_data).unwrap_err().errors
+}
+
+#[test]
+fn parse_level_pack() {
+    let levels = LevelPack::parse_levels("test.lvl", "Levels: 2\n\nw: 3, h: 3\n###\n#P#\n###\n\nw: 4, h: 3\n####\n#P@x\n####\n").unwrap();
+
+    assert_eq!(levels.len(), 2);
+    assert_eq!(levels[0].level().to_str(), "w: 3, h: 3\n###\n#P#\n###\n");
+    assert_eq!(levels[1].level().to_str(), "w: 4, h: 3\n####\n#P@x\n####\n");
+}
+
+#[test]
+fn parse_par_moves() {
+    let levels = LevelPack::parse_levels("test.lvl", "Levels: 2\n\nw: 3, h: 3, par: 12\n###\n#P#\n###\n\nw: 4, h: 3\n####\n#P@x\n####\n").unwrap();
+
+    assert_eq!(levels[0].par_moves(), Some(12));
+    assert_eq!(levels[1].par_moves(), None);
+    assert_eq!(levels[0].level().to_str_with_par_moves(levels[0].par_moves()), "w: 3, h: 3, par: 12\n###\n#P#\n###\n");
+
+    //The par moves do not change the level data
+    assert_eq!(levels[0].level().to_str(), "w: 3, h: 3\n###\n#P#\n###\n");
+
+    let errors = parse_errors("Levels: 1\n\nw: 3, h: 3, par: x\n###\n#P#\n###\n");
+    assert_eq!(errors, vec![
+        LevelLoadingError::new(LevelLoadingErrorCause::InvalidHeader).
+                with_position(3, 18).with_level(1).with_pack_path("test.lvl"),
+    ]);
+}
+
+#[test]
+fn parse_music_track() {
+    let lvl_data = "Levels: 1\nMusic: tense\n\nw: 3, h: 3\n###\n#P#\n###\n";
+
+    let level_pack = LevelPack::read_editor_level_pack("test", "test.lvl", lvl_data).unwrap();
+    assert_eq!(level_pack.music_track(), Some("tense"));
+    assert_eq!(level_pack.level_count(), 1);
+
+    let level_pack = LevelPack::read_editor_level_pack("test", "test.lvl", "Levels: 1\n\nw: 3, h: 3\n###\n#P#\n###\n").unwrap();
+    assert_eq!(level_pack.music_track(), None);
+
+    //The music track must follow the level count
+    let errors = parse_errors("Levels: 1\n\nw: 3, h: 3\n###\n#P#\n###\nMusic: tense\n");
+    assert_eq!(errors[0].cause(), &LevelLoadingErrorCause::TrailingData);
+}
+
+#[test]
+fn parse_empty_file() {
+    let errors = parse_errors("");
+
+    assert_eq!(errors, vec![LevelLoadingError::new(LevelLoadingErrorCause::EmptyFile).with_pack_path("test.lvl")]);
+}
+
+#[test]
+fn parse_missing_level_count() {
+    let errors = parse_errors("w: 3, h: 3\n###\n#P#\n###\n");
+
+    assert_eq!(errors[0], LevelLoadingError::new(LevelLoadingErrorCause::MissingLevelCount).
+            with_position(1, 1).with_pack_path("test.lvl"));
+}
+
+#[test]
+fn parse_invalid_level_count() {
+    let errors = parse_errors("Levels: abc\n\nw: 3, h: 3\n###\n#P#\n###\n");
+
+    assert_eq!(errors, vec![
+        LevelLoadingError::new(LevelLoadingErrorCause::InvalidLevelCount("abc".to_string())).
+                with_position(1, 9).with_pack_path("test.lvl"),
+    ]);
+    assert_eq!(errors[0].to_string(), "Level pack \"test.lvl\", Line 1, Column 9: The level count \"abc\" is invalid");
+}
+
+#[test]
+fn parse_too_many_levels() {
+    let errors = parse_errors("Levels: 192
+
+w: 3, h: 3
+###
+#P#
+###
+");
+
+    assert_eq!(errors[0], LevelLoadingError::new(LevelLoadingErrorCause::TooManyLevels { count: 192, max: 191 }).
+            with_position(1, 9).with_pack_path("test.lvl"));
+}
+
+#[test]
+fn parse_invalid_tile() {
+    let errors = parse_errors("Levels: 1\n\nw: 3, h: 3\n###\n#PQ\n###\n");
+
+    assert_eq!(errors, vec![
+        LevelLoadingError::new(LevelLoadingErrorCause::InvalidTile('Q')).
+                with_position(5, 3).with_level(1).with_pack_path("test.lvl"),
+    ]);
+    assert_eq!(errors[0].to_string(), "Level pack \"test.lvl\", Level 1, Line 5, Column 3: Invalid tile 'Q'");
+}
+
+#[test]
+fn parse_row_length_mismatch() {
+    let errors = parse_errors("Levels: 1\n\nw: 3, h: 3\n###\n#P\n###\n");
+
+    assert_eq!(errors, vec![
+        LevelLoadingError::new(LevelLoadingErrorCause::RowLengthMismatch { expected: 3, actual: 2 }).
+                with_position(5, 3).with_level(1).with_pack_path("test.lvl"),
+    ]);
+}
+
+#[test]
+fn parse_height_mismatch() {
+    let errors = parse_errors("Levels: 2\n\nw: 3, h: 4\n###\n#P#\n###\n\nw: 3, h: 2\n###\n#P#\n###\n");
+
+    assert_eq!(errors, vec![
+        LevelLoadingError::new(LevelLoadingErrorCause::HeightMismatch { expected: 4, actual: 3 }).
+                with_position(3, 1).with_level(1).with_pack_path("test.lvl"),
+        LevelLoadingError::new(LevelLoadingErrorCause::HeightMismatch { expected: 2, actual: 3 }).
+                with_position(8, 1).with_level(2).with_pack_path("test.lvl"),
+    ]);
+}
+
+#[test]
+fn parse_missing_header() {
+    let errors = parse_errors("Levels: 2\n\n###\n#P#\n###\n\nw: 3, h: 3\n###\n#P#\n###\n");
+
+    assert_eq!(errors, vec![
+        LevelLoadingError::new(LevelLoadingErrorCause::MissingHeader).
+                with_position(3, 1).with_level(1).with_pack_path("test.lvl"),
+    ]);
+}
+
+#[test]
+fn parse_trailing_data() {
+    let errors = parse_errors("Levels: 1\n\nw: 3, h: 3\n###\n#P#\n###\n\nw: 3, h: 3\n###\n#P#\n###\n");
+
+    assert_eq!(errors, vec![
+        LevelLoadingError::new(LevelLoadingErrorCause::TrailingData).
+                with_position(8, 1).with_pack_path("test.lvl"),
+    ]);
+}
+
+#[test]
+fn parse_level_count_mismatch() {
+    let errors = parse_errors("Levels: 3\n\nw: 3, h: 3\n###\n#P#\n###\n");
+
+    assert_eq!(errors, vec![
+        LevelLoadingError::new(LevelLoadingErrorCause::LevelCountMismatch { expected: 3, actual: 1 }).
+                with_pack_path("test.lvl"),
+    ]);
+}
+
+#[test]
+fn parse_collects_multiple_errors() {
+    let errors = parse_errors("Levels: 3\n\nw: 3, h: 3\n#?#\n#P!\n###\n\nw: 3, h: 3\n###\n#P#\n###\n\nw: 0, h: 3\n###\n#P#\n###\n");
+
+    assert_eq!(errors, vec![
+        LevelLoadingError::new(LevelLoadingErrorCause::InvalidTile('?')).
+                with_position(4, 2).with_level(1).with_pack_path("test.lvl"),
+        LevelLoadingError::new(LevelLoadingErrorCause::InvalidTile('!')).
+                with_position(5, 3).with_level(1).with_pack_path("test.lvl"),
+        LevelLoadingError::new(LevelLoadingErrorCause::InvalidHeader).
+                with_position(13, 1).with_level(3).with_pack_path("test.lvl"),
+    ]);
+}
+
+#[test]
+fn level_from_str_reports_first_error() {
+    let err = Level::from_str("w: 3, h: 2\n#X?\n#!#").unwrap_err();
+
+    assert_eq!(err, LevelLoadingError::new(LevelLoadingErrorCause::InvalidTile('?')).with_position(2, 3));
+}
+
+#[test]
+fn check_level_pack_data() {
+    let lvl_data = "Levels: 1\n\nw: 3, h: 3\n###\n#-#\n###\n";
+
+    assert!(LevelPack::check_level_pack_data("test.lvl", lvl_data, true).is_ok());
+
+    let err = LevelPack::check_level_pack_data("test.lvl", lvl_data, false).unwrap_err();
+    assert_eq!(err.errors(), [
+        LevelLoadingError::new(LevelLoadingErrorCause::MissingPlayer).with_level(1).with_pack_path("test.lvl"),
+    ]);
+
+    let err = LevelPack::check_level_pack_data("test.lvl", "Levels: 1\n\nw: 3, h: 3\n###\n#-?\n###\n", true).unwrap_err();
+    assert_eq!(err.errors()[0].line(), Some(5));
+    assert_eq!(err.errors()[0].column(), Some(3));
+}
+
+#[test]
+fn level_delta() {
+    let old = Level::from_str("w: 4, h: 3\n####\n#P@x\n####").unwrap();
+    let new = Level::from_str("w: 4, h: 3\n####\n#-P*\n####").unwrap();
+
+    let delta = old.diff(&new);
+    let LevelDelta::Tiles(changed_tiles) = &delta else {
+        panic!("Only the changed tiles should be stored");
+    };
+    assert_eq!(changed_tiles.len(), 3);
+
+    let mut level = old.clone();
+    level.apply(&delta);
+    assert_eq!(level.to_str(), new.to_str());
+    level.revert(&delta);
+    assert_eq!(level.to_str(), old.to_str());
+
+    let resized = Level::from_str("w: 3, h: 3\n###\n#P#\n###").unwrap();
+    let delta = old.diff(&resized);
+    assert!(matches!(delta, LevelDelta::Resized(_, _)));
+
+    level.apply(&delta);
+    assert_eq!(level.to_str(), resized.to_str());
+    level.revert(&delta);
+    assert_eq!(level.to_str(), old.to_str());
+}
+
+#[test]
+fn level_deadlock() {
+    let level = Level::from_str("w: 5, h: 4\n#####\n#P-x#\n#--@#\n#####").unwrap();
+    assert!(level.has_deadlock());
+
+    let level = Level::from_str("w: 5, h: 4\n#####\n#P@x#\n#---#\n#####").unwrap();
+    assert!(!level.has_deadlock());
+
+    //Boxes in a corner of a goal are not stuck
+    let level = Level::from_str("w: 5, h: 4\n#####\n#P--#\n#--+#\n#####").unwrap();
+    assert!(!level.has_deadlock());
+
+    //The level can still be completed with the other box
+    let level = Level::from_str("w: 6, h: 4\n######\n#P-@x#\n#---@#\n######").unwrap();
+    assert!(!level.has_deadlock());
+}
@@ -0,0 +1,339 @@
+#[cfg(test)]
+mod tests;
+
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use crate::game::level::{Level, LevelLoadingError, LevelLoadingErrorCause, LevelPackLoadingError, Tile};
+
+//Floor tiles: " " is the standard floor, "-" and "_" are used by some collections instead of spaces
+const FLOOR_TILES: &[u8] = b" -_";
+
+fn is_xsb_tile(tile: u8) -> bool {
+    matches!(tile, b'#' | b'@' | b'+' | b'$' | b'*' | b'.') || FLOOR_TILES.contains(&tile)
+}
+
+fn is_board_row(line: &str) -> bool {
+    !line.is_empty() && line.contains('#') && line.bytes().all(is_xsb_tile)
+}
+
+//Levels which cannot be represented in this game are skipped: Their errors are returned with the other levels
+pub fn parse_level_pack(path: &str, xsb_data: &str) -> Result<(Vec<Level>, Vec<LevelLoadingError>), LevelPackLoadingError> {
+    let mut levels = Vec::new();
+    let mut errors = Vec::new();
+    let mut skipped_level_errors = Vec::new();
+
+    let mut level_number = 0;
+    let mut rows = Vec::new();
+    let mut row_errors = Vec::new();
+
+    //Boards are separated by non-board lines ("Title:" lines, other metadata lines and ";" comments are ignored)
+    for (i, line) in xsb_data.lines().
+            map(|line| line.trim_end()).
+            enumerate() {
+        let line_number = i + 1;
+
+        if is_board_row(line) {
+            rows.push((line_number, line));
+
+            continue;
+        }
+
+        //Rows which start like a board but contain other characters (e.g. RLE counts or custom tiles)
+        if line.trim_start().starts_with('#') {
+            row_errors.extend(line.chars().
+                    enumerate().
+                    filter(|(_, tile)| !tile.is_ascii() || !is_xsb_tile(*tile as u8)).
+                    map(|(column, tile)| LevelLoadingError::new(LevelLoadingErrorCause::UnsupportedXsbTile(tile)).
+                            with_position(line_number, column + 1)));
+            rows.push((line_number, line));
+
+            continue;
+        }
+
+        add_level(path, &mut level_number, &mut rows, &mut row_errors, &mut levels, &mut errors, &mut skipped_level_errors);
+    }
+
+    add_level(path, &mut level_number, &mut rows, &mut row_errors, &mut levels, &mut errors, &mut skipped_level_errors);
+
+    //Collections without any importable level are rejected
+    if levels.is_empty() {
+        errors.append(&mut skipped_level_errors);
+    }
+
+    if errors.is_empty() {
+        Ok((levels, skipped_level_errors))
+    }else {
+        Err(LevelPackLoadingError::new(errors))
+    }
+}
+
+fn add_level(
+    path: &str,
+    level_number: &mut usize,
+    rows: &mut Vec<(usize, &str)>,
+    row_errors: &mut Vec<LevelLoadingError>,
+    levels: &mut Vec<Level>,
+    errors: &mut Vec<LevelLoadingError>,
+    skipped_level_errors: &mut Vec<LevelLoadingError>,
+) {
+    if rows.is_empty() {
+        return;
+    }
+
+    //Level numbers also count levels which could not be parsed
+    *level_number += 1;
+
+    let level_errors = if row_errors.is_empty() {
+        match parse_level(rows) {
+            Ok(level) => {
+                levels.push(level);
+
+                Vec::new()
+            },
+            Err(level_errors) => level_errors,
+        }
+    }else {
+        std::mem::take(row_errors)
+    };
+
+    let level_errors = level_errors.into_iter().
+            map(|err| err.with_level(*level_number).with_pack_path(path)).
+            collect::<Vec<_>>();
+
+    //The player cannot start on a goal in this game: Only this level is skipped
+    if !level_errors.is_empty() && level_errors.iter().
+            all(|err| *err.cause() == LevelLoadingErrorCause::XsbPlayerOnGoal) {
+        skipped_level_errors.extend(level_errors);
+    }else {
+        errors.extend(level_errors);
+    }
+
+    rows.clear();
+}
+
+fn parse_level(rows: &[(usize, &str)]) -> Result<Level, Vec<LevelLoadingError>> {
+    //Irregular rows are padded with floor tiles
+    let width = rows.iter().map(|(_, row)| row.len()).max().unwrap();
+    let height = rows.len();
+
+    let mut errors = Vec::new();
+    let mut board = vec![b' '; width * height];
+    let mut player_pos = None;
+    for (y, (line_number, row)) in rows.iter().
+            enumerate() {
+        for (x, tile) in row.bytes().
+                enumerate() {
+            if tile == b'+' {
+                errors.push(LevelLoadingError::new(LevelLoadingErrorCause::XsbPlayerOnGoal).
+                        with_position(*line_number, x + 1));
+            }
+
+            if matches!(tile, b'@' | b'+') && player_pos.is_none() {
+                player_pos = Some((x, y));
+            }
+
+            board[x + y * width] = tile;
+        }
+    }
+
+    let Some(player_pos) = player_pos else {
+        errors.push(LevelLoadingError::new(LevelLoadingErrorCause::MissingPlayer).
+                with_position(rows[0].0, 1));
+
+        return Err(errors);
+    };
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    //Floor tiles which cannot be reached from the player are outside of the level (This game wraps around
+    //at the level border, therefore they are converted to decoration tiles which act like walls)
+    let mut is_inside = vec![false; width * height];
+    let mut stack = vec![player_pos];
+    while let Some((x, y)) = stack.pop() {
+        let index = x + y * width;
+        if is_inside[index] || board[index] == b'#' {
+            continue;
+        }
+
+        is_inside[index] = true;
+
+        if x > 0 {
+            stack.push((x - 1, y));
+        }
+        if x + 1 < width {
+            stack.push((x + 1, y));
+        }
+        if y > 0 {
+            stack.push((x, y - 1));
+        }
+        if y + 1 < height {
+            stack.push((x, y + 1));
+        }
+    }
+
+    let mut level = Level::new(width, height);
+    for (index, tile) in level.tiles.iter_mut().
+            enumerate() {
+        *tile = match board[index] {
+            b'#' => Tile::Wall,
+            b'@' => Tile::Player,
+            b'$' => Tile::Box,
+            b'*' => Tile::BoxInGoal,
+            b'.' => Tile::Goal,
+            _ if is_inside[index] => Tile::Empty,
+            _ => Tile::DecorationBlank,
+        };
+    }
+
+    Ok(level)
+}
+
+fn tile_to_xsb(level: &Level, x: usize, y: usize) -> Option<u8> {
+    let is_passable = |x: usize, y: usize| level.get_tile(x, y).
+            is_some_and(|tile| !matches!(tile, Tile::Wall | Tile::DecorationBlank));
+
+    match level.get_tile(x, y)? {
+        Tile::Empty => Some(b' '),
+        Tile::Wall => Some(b'#'),
+        Tile::Player => Some(b'@'),
+        Tile::Box => Some(b'$'),
+        Tile::BoxInGoal => Some(b'*'),
+        Tile::Goal => Some(b'.'),
+
+        //Decoration tiles act like walls: They can only be exported as floor if they are not next to a passable tile
+        Tile::DecorationBlank => {
+            let has_passable_neighbor = (x > 0 && is_passable(x - 1, y)) || is_passable(x + 1, y) ||
+                    (y > 0 && is_passable(x, y - 1)) || is_passable(x, y + 1);
+
+            Some(if has_passable_neighbor {
+                b'#'
+            }else {
+                b' '
+            })
+        },
+
+        _ => None,
+    }
+}
+
+fn tile_names(tile: &Tile) -> &'static str {
+    match tile {
+        Tile::OneWayLeft | Tile::OneWayUp | Tile::OneWayRight | Tile::OneWayDown => "one-way doors",
+        Tile::Key | Tile::KeyInGoal => "keys",
+        Tile::LockedDoor => "locked doors",
+        Tile::Hole | Tile::BoxInHole => "holes",
+        Tile::Secret => "secrets",
+        _ => "unknown tiles",
+    }
+}
+
+fn level_to_xsb_rows(level: &Level, level_number: usize) -> Result<Vec<String>, LevelExportError> {
+    let is_passable = |tile: &Tile| !matches!(tile, Tile::Wall | Tile::DecorationBlank);
+
+    //XSB levels cannot wrap around at the level border
+    for x in 0..level.width() {
+        if is_passable(level.get_tile(x, 0).unwrap()) && is_passable(level.get_tile(x, level.height() - 1).unwrap()) {
+            return Err(LevelExportError::new(format!(
+                "XSB does not support wrap-around (Level {level_number}, Column {})",
+                x + 1
+            )));
+        }
+    }
+    for y in 0..level.height() {
+        if is_passable(level.get_tile(0, y).unwrap()) && is_passable(level.get_tile(level.width() - 1, y).unwrap()) {
+            return Err(LevelExportError::new(format!(
+                "XSB does not support wrap-around (Level {level_number}, Row {})",
+                y + 1
+            )));
+        }
+    }
+
+    let mut rows = Vec::with_capacity(level.height());
+    for y in 0..level.height() {
+        let mut row = String::with_capacity(level.width());
+        for x in 0..level.width() {
+            let Some(tile) = tile_to_xsb(level, x, y) else {
+                return Err(LevelExportError::new(format!(
+                    "XSB does not support {} (Level {level_number}, {}:{})",
+                    tile_names(level.get_tile(x, y).unwrap()), x + 1, y + 1
+                )));
+            };
+
+            row.push(tile as char);
+        }
+
+        rows.push(row.trim_end().to_string());
+    }
+
+    Ok(rows)
+}
+
+pub fn level_pack_to_xsb<'a>(id: &str, levels: impl Iterator<Item = &'a Level>) -> Result<String, LevelExportError> {
+    let mut out = format!("; {id}\n");
+
+    for (i, level) in levels.enumerate() {
+        out += "\n";
+        for row in level_to_xsb_rows(level, i + 1)? {
+            out += &row;
+            out += "\n";
+        }
+        out += &format!("Title: {id} {}\n", i + 1);
+    }
+
+    Ok(out)
+}
+
+pub fn level_to_rle(level: &Level) -> Result<String, LevelExportError> {
+    let rows = level_to_xsb_rows(level, 1)?;
+
+    let mut out = String::new();
+    for (i, row) in rows.iter().
+            enumerate() {
+        if i > 0 {
+            out.push('|');
+        }
+
+        //"-" is used for floor tiles, because spaces are not allowed in one-line RLE strings
+        let mut tiles = row.bytes().
+                map(|tile| if tile == b' ' {
+                    b'-'
+                }else {
+                    tile
+                }).
+                peekable();
+        while let Some(tile) = tiles.next() {
+            let mut count = 1;
+            while tiles.next_if_eq(&tile).is_some() {
+                count += 1;
+            }
+
+            if count > 1 {
+                out += &count.to_string();
+            }
+            out.push(tile as char);
+        }
+    }
+
+    Ok(out)
+}
+
+#[derive(Debug)]
+pub struct LevelExportError {
+    message: String
+}
+
+impl LevelExportError {
+    fn new(message: impl Into<String>) -> Self {
+        Self { message: message.into() }
+    }
+}
+
+impl Display for LevelExportError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl Error for LevelExportError {}
@@ -0,0 +1,144 @@
+use std::str::FromStr;
+use super::*;
+
+#[test]
+fn parse_single_level() {
+    let (levels, _) = parse_level_pack("test.xsb", "#####\n#@$.#\n#####\n").unwrap();
+
+    assert_eq!(levels.len(), 1);
+    assert_eq!(levels[0].to_str(), "w: 5, h: 3\n#####\n#P@x#\n#####\n");
+}
+
+#[test]
+fn parse_box_in_goal_and_floor_variants() {
+    let (levels, _) = parse_level_pack("test.xsb", "######\n#@-_*#\n######").unwrap();
+
+    assert_eq!(levels[0].to_str(), "w: 6, h: 3\n######\n#P--+#\n######\n");
+}
+
+#[test]
+fn parse_irregular_rows_are_padded() {
+    let data = "  ####\n###  #\n#@$. #\n######";
+    let (levels, _) = parse_level_pack("test.xsb", data).unwrap();
+
+    assert_eq!(levels[0].width(), 6);
+    assert_eq!(levels[0].height(), 4);
+    assert_eq!(levels[0].to_str(), "w: 6, h: 4\nbb####\n###--#\n#P@x-#\n######\n");
+}
+
+#[test]
+fn parse_multiple_levels_with_metadata() {
+    let data = "\
+; Example collection
+Title: Example
+
+; Level 1
+####
+#@.#
+#$ #
+####
+Title: First
+
+; Level 2
+#####
+#@$.#
+#####
+Title: Second
+Author: Someone
+";
+    let (levels, _) = parse_level_pack("test.xsb", data).unwrap();
+
+    assert_eq!(levels.len(), 2);
+    assert_eq!(levels[0].to_str(), "w: 4, h: 4\n####\n#Px#\n#@-#\n####\n");
+    assert_eq!(levels[1].to_str(), "w: 5, h: 3\n#####\n#P@x#\n#####\n");
+}
+
+#[test]
+fn parse_player_on_goal_is_unsupported() {
+    let err = parse_level_pack("test.xsb", "#####\n#+$.#\n#####\n").unwrap_err();
+
+    assert_eq!(err.errors, vec![
+        LevelLoadingError::new(LevelLoadingErrorCause::XsbPlayerOnGoal).
+                with_position(2, 2).with_level(1).with_pack_path("test.xsb"),
+    ]);
+}
+
+#[test]
+fn parse_player_on_goal_skips_level() {
+    let (levels, skipped_level_errors) = parse_level_pack("test.xsb", "#####\n#@$.#\n#####\n\n#####\n#+$.#\n#####\n\n####\n#@.#\n#$ #\n####").unwrap();
+
+    assert_eq!(levels.len(), 2);
+    assert_eq!(levels[0].to_str(), "w: 5, h: 3\n#####\n#P@x#\n#####\n");
+    assert_eq!(levels[1].to_str(), "w: 4, h: 4\n####\n#Px#\n#@-#\n####\n");
+
+    assert_eq!(skipped_level_errors, vec![
+        LevelLoadingError::new(LevelLoadingErrorCause::XsbPlayerOnGoal).
+                with_position(6, 2).with_level(2).with_pack_path("test.xsb"),
+    ]);
+}
+
+#[test]
+fn parse_unsupported_tile() {
+    let err = parse_level_pack("test.xsb", "#####\n#@$.#\n#3#\n\n####\n#@.#\n#$?#\n####").unwrap_err();
+
+    assert_eq!(err.errors, vec![
+        LevelLoadingError::new(LevelLoadingErrorCause::UnsupportedXsbTile('3')).
+                with_position(3, 2).with_level(1).with_pack_path("test.xsb"),
+        LevelLoadingError::new(LevelLoadingErrorCause::UnsupportedXsbTile('?')).
+                with_position(7, 3).with_level(2).with_pack_path("test.xsb"),
+    ]);
+}
+
+#[test]
+fn parse_missing_player() {
+    let err = parse_level_pack("test.xsb", "#####\n# $.#\n#####\n").unwrap_err();
+
+    assert_eq!(err.to_string(), "Level pack \"test.xsb\", Level 1, Line 1, Column 1: Level does not contain a player tile");
+}
+
+#[test]
+fn export_level_pack() {
+    let (levels, _) = parse_level_pack("test.xsb", "  ####\n###  #\n#@$. #\n######\n\n#####\n#@*.#\n#####").unwrap();
+    let xsb_data = level_pack_to_xsb("test", levels.iter()).unwrap();
+
+    assert_eq!(xsb_data, "; test\n\n  ####\n###  #\n#@$. #\n######\nTitle: test 1\n\n#####\n#@*.#\n#####\nTitle: test 2\n");
+
+    let (levels_reimported, _) = parse_level_pack("test.xsb", &xsb_data).unwrap();
+    assert_eq!(levels_reimported.len(), 2);
+    assert_eq!(levels_reimported[0].to_str(), levels[0].to_str());
+    assert_eq!(levels_reimported[1].to_str(), levels[1].to_str());
+}
+
+#[test]
+fn export_unsupported_tiles() {
+    for (tile, tile_names) in [
+        (Tile::OneWayLeft, "one-way doors"),
+        (Tile::Key, "keys"),
+        (Tile::KeyInGoal, "keys"),
+        (Tile::LockedDoor, "locked doors"),
+        (Tile::Hole, "holes"),
+        (Tile::BoxInHole, "holes"),
+        (Tile::Secret, "secrets"),
+    ] {
+        let mut level = parse_level_pack("test.xsb", "######\n#@$. #\n######").unwrap().0.remove(0);
+        level.set_tile(4, 1, tile);
+
+        let err = level_pack_to_xsb("test", [level].iter()).unwrap_err();
+        assert_eq!(err.to_string(), format!("XSB does not support {tile_names} (Level 1, 5:2)"));
+    }
+}
+
+#[test]
+fn export_wrap_around_is_unsupported() {
+    let level = Level::from_str("w: 5, h: 3\n#####\n-P@x-\n#####").unwrap();
+
+    let err = level_to_rle(&level).unwrap_err();
+    assert_eq!(err.to_string(), "XSB does not support wrap-around (Level 1, Row 2)");
+}
+
+#[test]
+fn export_rle() {
+    let (levels, _) = parse_level_pack("test.xsb", "  ####\n###  #\n#@$. #\n######").unwrap();
+
+    assert_eq!(level_to_rle(&levels[0]).unwrap(), "2-4#|3#2-#|#@$.-#|6#");
+}
@@ -0,0 +1,225 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+use std::sync::RwLock;
+use crate::game::data_dir;
+
+#[cfg(test)]
+mod tests;
+
+pub const DEFAULT_PROFILE: &str = "default";
+
+pub const MIN_PROFILE_NAME_LEN: usize = 3;
+pub const MAX_PROFILE_NAME_LEN: usize = 16;
+
+pub const MAX_PROFILE_COUNT: usize = 12;
+
+const PROFILES_FOLDER: &str = "profiles";
+
+//Contains the name of the last selected profile (Stored in the config folder)
+const CURRENT_PROFILE_FILE: &str = "profile";
+
+//Save games which were stored in the data folder before profiles existed are moved to the default profile
+const SAVE_GAME_FILE_SUFFIXES: [&str; 3] = [".lvl.sav", ".lvl.sav.bak", ".lvl.sav.archive"];
+
+static CURRENT_PROFILE: RwLock<String> = RwLock::new(String::new());
+
+pub fn current_profile() -> String {
+    let current_profile = CURRENT_PROFILE.read().unwrap_or_else(|err| err.into_inner());
+    if current_profile.is_empty() {
+        DEFAULT_PROFILE.to_string()
+    }else {
+        current_profile.clone()
+    }
+}
+
+fn set_current_profile(name: &str) {
+    *CURRENT_PROFILE.write().unwrap_or_else(|err| err.into_inner()) = name.to_string();
+}
+
+//Profile names are used as folder names: Only the characters of level pack IDs are allowed
+pub fn validate_profile_name(name: &str) -> Result<(), ProfileError> {
+    if name.len() < MIN_PROFILE_NAME_LEN {
+        return Err(ProfileError::new(format!("Profile name must have at least {} characters!", MIN_PROFILE_NAME_LEN)));
+    }
+
+    if name.len() > MAX_PROFILE_NAME_LEN {
+        return Err(ProfileError::new(format!("Profile name must have at most {} characters!", MAX_PROFILE_NAME_LEN)));
+    }
+
+    if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+        return Err(ProfileError::new("Profile name must only contain letters, digits, \"_\" and \"-\"!"));
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Profiles {
+    data_dir: PathBuf,
+    config_dir: PathBuf,
+}
+
+impl Profiles {
+    pub fn new(data_dir: impl Into<PathBuf>, config_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            data_dir: data_dir.into(),
+            config_dir: config_dir.into(),
+        }
+    }
+
+    pub fn from_data_dirs() -> Result<Self, Box<dyn Error>> {
+        Ok(Self::new(data_dir::data_dir()?, data_dir::config_dir()?))
+    }
+
+    //Contains the save games of the profile
+    pub fn profile_data_dir(&self, name: &str) -> PathBuf {
+        self.data_dir.join(PROFILES_FOLDER).join(name)
+    }
+
+    //Contains the settings of the profile
+    pub fn profile_config_dir(&self, name: &str) -> PathBuf {
+        self.config_dir.join(PROFILES_FOLDER).join(name)
+    }
+
+    //Returns the sorted names of all profiles
+    pub fn profile_names(&self) -> Result<Vec<String>, Box<dyn Error>> {
+        let profiles_dir = self.data_dir.join(PROFILES_FOLDER);
+        if !std::fs::exists(&profiles_dir)? {
+            return Ok(Vec::new());
+        }
+
+        let mut profile_names = std::fs::read_dir(profiles_dir)?.
+                filter_map(|entry| entry.ok()).
+                filter(|entry| entry.path().is_dir()).
+                filter_map(|entry| entry.file_name().to_str().map(|name| name.to_string())).
+                filter(|name| validate_profile_name(name).is_ok()).
+                collect::<Vec<_>>();
+        profile_names.sort();
+
+        Ok(profile_names)
+    }
+
+    //Creates the default profile if no profile exists and selects the last selected profile
+    pub fn init(&self) -> Result<(), Box<dyn Error>> {
+        let mut profile_names = self.profile_names()?;
+        if profile_names.is_empty() {
+            self.create_profile(DEFAULT_PROFILE)?;
+            self.migrate_save_games(DEFAULT_PROFILE)?;
+
+            profile_names.push(DEFAULT_PROFILE.to_string());
+        }
+
+        let current_profile_file = self.config_dir.join(CURRENT_PROFILE_FILE);
+        let last_profile = if std::fs::exists(&current_profile_file)? {
+            std::fs::read_to_string(&current_profile_file)?.trim().to_string()
+        }else {
+            String::new()
+        };
+
+        if profile_names.contains(&last_profile) {
+            set_current_profile(&last_profile);
+        }else {
+            self.select_profile(&profile_names[0])?;
+        }
+
+        Ok(())
+    }
+
+    fn migrate_save_games(&self, name: &str) -> Result<(), Box<dyn Error>> {
+        let profile_data_dir = self.profile_data_dir(name);
+
+        for entry in std::fs::read_dir(&self.data_dir)?.
+                filter_map(|entry| entry.ok()).
+                filter(|entry| entry.path().is_file()) {
+            let is_save_game_file = entry.file_name().to_str().is_some_and(|file_name| SAVE_GAME_FILE_SUFFIXES.iter().
+                    any(|suffix| file_name.ends_with(suffix)));
+            if is_save_game_file {
+                std::fs::rename(entry.path(), profile_data_dir.join(entry.file_name()))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn select_profile(&self, name: &str) -> Result<(), Box<dyn Error>> {
+        if !self.profile_data_dir(name).is_dir() {
+            return Err(Box::new(ProfileError::new(format!("The profile \"{}\" does not exist!", name))));
+        }
+
+        std::fs::create_dir_all(&self.config_dir)?;
+        std::fs::write(self.config_dir.join(CURRENT_PROFILE_FILE), format!("{}\n", name))?;
+
+        set_current_profile(name);
+
+        Ok(())
+    }
+
+    pub fn create_profile(&self, name: &str) -> Result<(), Box<dyn Error>> {
+        validate_profile_name(name)?;
+
+        let profile_names = self.profile_names()?;
+        if profile_names.iter().any(|profile_name| profile_name == name) {
+            return Err(Box::new(ProfileError::new(format!("The profile \"{}\" already exists!", name))));
+        }
+
+        if profile_names.len() >= MAX_PROFILE_COUNT {
+            return Err(Box::new(ProfileError::new(format!("Max profile count ({}) reached!", MAX_PROFILE_COUNT))));
+        }
+
+        std::fs::create_dir_all(self.profile_data_dir(name))?;
+
+        Ok(())
+    }
+
+    pub fn rename_profile(&self, name: &str, new_name: &str) -> Result<(), Box<dyn Error>> {
+        validate_profile_name(new_name)?;
+
+        if std::fs::exists(self.profile_data_dir(new_name))? {
+            return Err(Box::new(ProfileError::new(format!("The profile \"{}\" already exists!", new_name))));
+        }
+
+        std::fs::rename(self.profile_data_dir(name), self.profile_data_dir(new_name))?;
+
+        if std::fs::exists(self.profile_config_dir(name))? {
+            std::fs::rename(self.profile_config_dir(name), self.profile_config_dir(new_name))?;
+        }
+
+        Ok(())
+    }
+
+    //The last profile can not be deleted
+    pub fn delete_profile(&self, name: &str) -> Result<(), Box<dyn Error>> {
+        let profile_names = self.profile_names()?;
+        if profile_names.len() <= 1 {
+            return Err(Box::new(ProfileError::new("The last profile can not be deleted!")));
+        }
+
+        std::fs::remove_dir_all(self.profile_data_dir(name))?;
+
+        if std::fs::exists(self.profile_config_dir(name))? {
+            std::fs::remove_dir_all(self.profile_config_dir(name))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct ProfileError {
+    message: String
+}
+
+impl ProfileError {
+    fn new(message: impl Into<String>) -> Self {
+        Self { message: message.into() }
+    }
+}
+
+impl Display for ProfileError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl Error for ProfileError {}
@@ -0,0 +1,73 @@
+use super::*;
+use crate::game::test_utils::TempDir;
+
+fn tmp_profiles(name: &str) -> (TempDir, Profiles) {
+    let root = TempDir::new(name);
+    std::fs::create_dir_all(root.join("data")).unwrap();
+
+    let profiles = Profiles::new(root.join("data"), root.join("config"));
+
+    (root, profiles)
+}
+
+#[test]
+fn validate_profile_names() {
+    assert!(validate_profile_name("default").is_ok());
+    assert!(validate_profile_name("Player_2-a").is_ok());
+
+    assert!(validate_profile_name("ab").is_err());
+    assert!(validate_profile_name("abcdefghijklmnopq").is_err());
+    assert!(validate_profile_name("../secret").is_err());
+    assert!(validate_profile_name("a b c").is_err());
+}
+
+#[test]
+fn init_migrates_save_games_to_default_profile() {
+    let (root, profiles) = tmp_profiles("profile-init");
+    std::fs::write(root.join("data").join("main.lvl.sav"), "1\n").unwrap();
+    std::fs::write(root.join("data").join("main.lvl.sav.bak"), "1\n").unwrap();
+    std::fs::write(root.join("data").join("pack.lvl.edit"), "Levels: 0\n").unwrap();
+
+    profiles.init().unwrap();
+
+    assert_eq!(profiles.profile_names().unwrap(), [DEFAULT_PROFILE]);
+    assert_eq!(current_profile(), DEFAULT_PROFILE);
+
+    //Editor level packs are shared between all profiles
+    let default_profile_dir = profiles.profile_data_dir(DEFAULT_PROFILE);
+    assert!(std::fs::exists(default_profile_dir.join("main.lvl.sav")).unwrap());
+    assert!(std::fs::exists(default_profile_dir.join("main.lvl.sav.bak")).unwrap());
+    assert!(!std::fs::exists(root.join("data").join("main.lvl.sav")).unwrap());
+    assert!(std::fs::exists(root.join("data").join("pack.lvl.edit")).unwrap());
+
+    //The last selected profile is selected again
+    profiles.create_profile("player2").unwrap();
+    profiles.select_profile("player2").unwrap();
+    profiles.init().unwrap();
+    assert_eq!(current_profile(), "player2");
+}
+
+#[test]
+fn create_rename_and_delete_profiles() {
+    let (_root, profiles) = tmp_profiles("profile-actions");
+
+    profiles.create_profile("alice").unwrap();
+    profiles.create_profile("bob").unwrap();
+    assert_eq!(profiles.create_profile("alice").unwrap_err().to_string(), "The profile \"alice\" already exists!");
+    assert_eq!(profiles.profile_names().unwrap(), ["alice", "bob"]);
+
+    std::fs::create_dir_all(profiles.profile_config_dir("bob")).unwrap();
+    std::fs::write(profiles.profile_data_dir("bob").join("main.lvl.sav"), "1\n").unwrap();
+
+    profiles.rename_profile("bob", "carol").unwrap();
+    assert_eq!(profiles.profile_names().unwrap(), ["alice", "carol"]);
+    assert!(std::fs::exists(profiles.profile_data_dir("carol").join("main.lvl.sav")).unwrap());
+    assert!(std::fs::exists(profiles.profile_config_dir("carol")).unwrap());
+    assert!(profiles.rename_profile("carol", "alice").is_err());
+
+    profiles.delete_profile("carol").unwrap();
+    assert_eq!(profiles.profile_names().unwrap(), ["alice"]);
+    assert!(!std::fs::exists(profiles.profile_config_dir("carol")).unwrap());
+
+    assert_eq!(profiles.delete_profile("alice").unwrap_err().to_string(), "The last profile can not be deleted!");
+}
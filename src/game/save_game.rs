@@ -0,0 +1,387 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter, Write as _};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+#[cfg(test)]
+mod tests;
+
+//Version 2 save games contain a hash for each level
+const VERSION_2: &str = "v2";
+
+//The end marker is written as the last line in order to detect partially written save games
+const END_MARKER: &str = "end";
+
+pub fn backup_path(path: &Path) -> PathBuf {
+    let mut backup_path = path.as_os_str().to_os_string();
+    backup_path.push(".bak");
+
+    PathBuf::from(backup_path)
+}
+
+fn tmp_path(path: &Path) -> PathBuf {
+    let mut tmp_path = path.as_os_str().to_os_string();
+    tmp_path.push(".tmp");
+
+    PathBuf::from(tmp_path)
+}
+
+//The data is written to a temporary file first which replaces the old file afterwards (The old file is
+//never truncated, therefore a crash during saving can not destroy the old file)
+pub fn write_atomically(path: &Path, data: &str) -> Result<(), Box<dyn Error>> {
+    let tmp_path = tmp_path(path);
+
+    let mut file = File::create(&tmp_path)?;
+    file.write_all(data.as_bytes())?;
+    file.flush()?;
+    file.sync_all()?;
+    drop(file);
+
+    std::fs::rename(&tmp_path, path)?;
+
+    Ok(())
+}
+
+pub fn archive_path(path: &Path) -> PathBuf {
+    let mut archive_path = path.as_os_str().to_os_string();
+    archive_path.push(".archive");
+
+    PathBuf::from(archive_path)
+}
+
+//Stats of levels which were changed are appended to the archive instead of being dropped
+pub fn archive_level_stats(path: &Path, level_stats: &[(usize, LevelStats)]) -> Result<(), Box<dyn Error>> {
+    let mut file = OpenOptions::new().create(true).append(true).open(archive_path(path))?;
+
+    for (level_number, level_stats) in level_stats {
+        writeln!(file, "{}:{}", level_number, level_stats)?;
+    }
+    file.flush()?;
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LevelStats {
+    best_time: Option<u64>,
+    best_moves: Option<u32>,
+    level_hash: Option<u64>,
+}
+
+impl LevelStats {
+    pub fn new(best_time: Option<u64>, best_moves: Option<u32>, level_hash: Option<u64>) -> Self {
+        Self {
+            best_time,
+            best_moves,
+            level_hash,
+        }
+    }
+
+    pub fn best_time(&self) -> Option<u64> {
+        self.best_time
+    }
+
+    pub fn best_moves(&self) -> Option<u32> {
+        self.best_moves
+    }
+
+    //Save games of older versions do not contain level hashes
+    pub fn level_hash(&self) -> Option<u64> {
+        self.level_hash
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.best_time.is_none() && self.best_moves.is_none()
+    }
+}
+
+impl Display for LevelStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f, "ms{},{}",
+            self.best_time.map_or(-1, |best_time| best_time as i64),
+            self.best_moves.map_or(-1, |best_moves| best_moves as i32)
+        )?;
+
+        if let Some(level_hash) = self.level_hash {
+            write!(f, ",{:016x}", level_hash)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LevelChange {
+    Moved {
+        from: usize,
+        to: usize,
+    },
+    Changed {
+        level: usize,
+    },
+}
+
+impl Display for LevelChange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Moved { from, to } => write!(f, "Level {from} was moved to level {to}"),
+            Self::Changed { level } => write!(f, "Level {level} was changed or removed (Best scores were archived)"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LevelStatsMatch {
+    level_stats: Vec<LevelStats>,
+    level_changes: Vec<LevelChange>,
+    archived_level_stats: Vec<(usize, LevelStats)>,
+}
+
+impl LevelStatsMatch {
+    //Contains the stats for each level of the level pack
+    pub fn level_stats(&self) -> &[LevelStats] {
+        &self.level_stats
+    }
+
+    pub fn level_changes(&self) -> &[LevelChange] {
+        &self.level_changes
+    }
+
+    //Contains the level number of the save game and the stats of levels which were changed or removed
+    pub fn archived_level_stats(&self) -> &[(usize, LevelStats)] {
+        &self.archived_level_stats
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SaveGame {
+    min_level_not_completed: usize,
+    level_stats: Vec<LevelStats>,
+}
+
+impl SaveGame {
+    pub fn new(min_level_not_completed: usize, level_stats: Vec<LevelStats>) -> Self {
+        Self {
+            min_level_not_completed,
+            level_stats,
+        }
+    }
+
+    //Contains the stats of all levels up to the level progress and of all levels after it which have stats
+    //(Stats of levels which were moved after the level progress must not be dropped)
+    pub fn from_level_stats(min_level_not_completed: usize, mut level_stats: Vec<LevelStats>) -> Self {
+        let level_count = level_stats.iter().
+                rposition(|level_stats| !level_stats.is_empty()).
+                map_or(0, |i| i + 1).
+                max(min_level_not_completed).
+                min(level_stats.len());
+        level_stats.truncate(level_count);
+
+        Self::new(min_level_not_completed, level_stats)
+    }
+
+    //Returns None if the save game does not exist
+    pub fn read(path: &Path, level_count: usize) -> Result<Option<Self>, SaveGameError> {
+        if !std::fs::exists(path).map_err(|err| SaveGameError::new(format!("Cannot check save game: {err}")))? {
+            return Ok(None);
+        }
+
+        let save_game_data = std::fs::read_to_string(path).
+                map_err(|err| SaveGameError::new(format!("Cannot read save game: {err}")))?;
+
+        Self::parse(&save_game_data, level_count).map(Some)
+    }
+
+    pub fn parse(save_game_data: &str, level_count: usize) -> Result<Self, SaveGameError> {
+        let lines = save_game_data.lines().
+                map(|line| line.trim()).
+                collect::<Vec<_>>();
+        if lines.is_empty() {
+            return Err(SaveGameError::new("The save game is empty"));
+        }
+
+        let is_version_2 = lines[0] == VERSION_2;
+        let header_line_count = if is_version_2 {
+            2
+        }else {
+            1
+        };
+
+        let Some(line) = lines.get(header_line_count - 1) else {
+            return Err(SaveGameError::new("The level progress is missing (Line 2)"));
+        };
+
+        let Ok(min_level_not_completed) = usize::from_str(line) else {
+            return Err(SaveGameError::new(format!("Invalid level progress \"{line}\" (Line {header_line_count})")));
+        };
+
+        let mut has_end_marker = false;
+        let mut level_stats = Vec::new();
+        for (i, mut line) in lines.iter().
+                copied().
+                enumerate().
+                skip(header_line_count) {
+            if has_end_marker {
+                return Err(SaveGameError::new(format!("Additional data was found after the end (Line {})", i + 1)));
+            }
+
+            if line == END_MARKER {
+                has_end_marker = true;
+
+                continue;
+            }
+
+            let is_new_format = line.starts_with("ms");
+            if is_new_format {
+                line = &line[2..];
+            }
+
+            let tokens = line.split(",").collect::<Vec<_>>();
+            let stats = match tokens[..] {
+                [best_time, best_moves] => Self::parse_stat::<u64>(best_time).
+                        zip(Self::parse_stat::<u32>(best_moves)).
+                        map(|(best_time, best_moves)| (best_time, best_moves, None)),
+                [best_time, best_moves, level_hash] => Self::parse_stat::<u64>(best_time).
+                        zip(Self::parse_stat::<u32>(best_moves)).
+                        zip(u64::from_str_radix(level_hash, 16).ok()).
+                        map(|((best_time, best_moves), level_hash)| (best_time, best_moves, Some(level_hash))),
+                _ => None,
+            };
+            let Some((best_time, best_moves, level_hash)) = stats else {
+                return Err(SaveGameError::new(format!("Invalid level stats \"{}\" (Line {})", lines[i], i + 1)));
+            };
+
+            let best_time = best_time.map(|best_time| {
+                if is_new_format {
+                    best_time
+                }else {
+                    best_time * 1000 + 999
+                }
+            });
+
+            level_stats.push(LevelStats::new(best_time, best_moves, level_hash));
+        }
+
+        if is_version_2 && !has_end_marker {
+            return Err(SaveGameError::new("The save game is incomplete (The end is missing)"));
+        }
+
+        //Save games without end marker are from older versions: Only the completed levels are saved
+        if !has_end_marker && level_stats.len() < min_level_not_completed.min(level_count) {
+            return Err(SaveGameError::new(format!(
+                "The save game is incomplete (Read: {} levels, Expected: {} levels)",
+                level_stats.len(),
+                min_level_not_completed.min(level_count),
+            )));
+        }
+
+        Ok(Self {
+            min_level_not_completed,
+            level_stats,
+        })
+    }
+
+    //Stats are matched to levels by the level hash: Stats of moved levels are moved as well and stats of
+    //changed or removed levels are archived (Stats without level hash are matched by the level position)
+    pub fn match_level_stats(&self, level_hashes: &[u64]) -> LevelStatsMatch {
+        let mut matched_level_stats = vec![None; level_hashes.len()];
+
+        let mut unmatched_level_stats = Vec::new();
+        for (i, level_stats) in self.level_stats.iter().
+                enumerate() {
+            match level_stats.level_hash() {
+                None if i < level_hashes.len() => matched_level_stats[i] = Some(*level_stats),
+                Some(level_hash) if level_hashes.get(i) == Some(&level_hash) => matched_level_stats[i] = Some(*level_stats),
+                Some(_) if !level_stats.is_empty() => unmatched_level_stats.push((i, *level_stats)),
+
+                //Stats of levels which were removed in older versions of the level pack or levels without stats
+                _ => {},
+            }
+        }
+
+        let mut level_changes = Vec::new();
+        let mut archived_level_stats = Vec::new();
+        for (i, level_stats) in unmatched_level_stats {
+            let new_index = level_hashes.iter().
+                    enumerate().
+                    position(|(j, level_hash)| matched_level_stats[j].is_none() &&
+                            Some(*level_hash) == level_stats.level_hash());
+
+            if let Some(new_index) = new_index {
+                matched_level_stats[new_index] = Some(level_stats);
+
+                level_changes.push(LevelChange::Moved { from: i + 1, to: new_index + 1 });
+            }else {
+                archived_level_stats.push((i + 1, level_stats));
+
+                level_changes.push(LevelChange::Changed { level: i + 1 });
+            }
+        }
+
+        LevelStatsMatch {
+            level_stats: matched_level_stats.into_iter().
+                    map(|level_stats| level_stats.unwrap_or_default()).
+                    collect(),
+            level_changes,
+            archived_level_stats,
+        }
+    }
+
+    //"-1" is used for levels without stats
+    fn parse_stat<T: FromStr>(stat: &str) -> Option<Option<T>> {
+        if stat == "-1" {
+            Some(None)
+        }else {
+            T::from_str(stat).ok().map(Some)
+        }
+    }
+
+    //The current save game is only kept as backup if it can be loaded (A corrupt save game must not replace a valid backup)
+    pub fn write(&self, path: &Path, level_count: usize) -> Result<(), Box<dyn Error>> {
+        if Self::read(path, level_count).is_ok_and(|save_game| save_game.is_some()) {
+            std::fs::copy(path, backup_path(path))?;
+        }
+
+        write_atomically(path, &self.to_string())
+    }
+
+    pub fn min_level_not_completed(&self) -> usize {
+        self.min_level_not_completed
+    }
+}
+
+impl Display for SaveGame {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}", VERSION_2)?;
+        writeln!(f, "{}", self.min_level_not_completed)?;
+
+        for level_stats in self.level_stats.iter() {
+            writeln!(f, "{}", level_stats)?;
+        }
+
+        f.write_str(END_MARKER)?;
+        f.write_char('\n')
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SaveGameError {
+    message: String
+}
+
+impl SaveGameError {
+    fn new(message: impl Into<String>) -> Self {
+        Self { message: message.into() }
+    }
+}
+
+impl Display for SaveGameError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl Error for SaveGameError {}
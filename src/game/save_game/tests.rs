@@ -0,0 +1,143 @@
+use super::*;
+use crate::game::test_utils::TempDir;
+
+#[test]
+fn parse_save_game() {
+    let save_game = SaveGame::parse("2\nms1500,12\nms-1,-1\nend\n", 3).unwrap();
+
+    assert_eq!(save_game, SaveGame::new(2, vec![LevelStats::new(Some(1500), Some(12), None), LevelStats::new(None, None, None)]));
+}
+
+#[test]
+fn parse_legacy_save_game() {
+    let save_game = SaveGame::parse("1\n3,12\n", 3).unwrap();
+
+    assert_eq!(save_game, SaveGame::new(1, vec![LevelStats::new(Some(3999), Some(12), None)]));
+}
+
+#[test]
+fn parse_corrupt_save_game() {
+    assert_eq!(SaveGame::parse("", 3).unwrap_err().to_string(), "The save game is empty");
+    assert_eq!(SaveGame::parse("x\n", 3).unwrap_err().to_string(), "Invalid level progress \"x\" (Line 1)");
+    assert_eq!(SaveGame::parse("1\nms12\nend\n", 3).unwrap_err().to_string(), "Invalid level stats \"ms12\" (Line 2)");
+    assert_eq!(SaveGame::parse("1\nms1,1\nend\nms1,1\n", 3).unwrap_err().to_string(), "Additional data was found after the end (Line 4)");
+}
+
+#[test]
+fn parse_partial_save_game() {
+    let err = SaveGame::parse("3\nms1500,12\n", 5).unwrap_err();
+
+    assert_eq!(err.to_string(), "The save game is incomplete (Read: 1 levels, Expected: 3 levels)");
+}
+
+#[test]
+fn parse_version_2_save_game() {
+    let save_game = SaveGame::parse("v2\n1\nms1500,12,00000000000000ff\nend\n", 3).unwrap();
+
+    assert_eq!(save_game, SaveGame::new(1, vec![LevelStats::new(Some(1500), Some(12), Some(0xff))]));
+
+    let err = SaveGame::parse("v2\n1\nms1500,12,00000000000000ff\n", 3).unwrap_err();
+    assert_eq!(err.to_string(), "The save game is incomplete (The end is missing)");
+}
+
+#[test]
+fn match_level_stats() {
+    let save_game = SaveGame::new(4, vec![
+        LevelStats::new(Some(1000), Some(10), Some(1)),
+        LevelStats::new(Some(2000), Some(20), Some(2)),
+        LevelStats::new(Some(3000), Some(30), Some(3)),
+        LevelStats::new(Some(4000), Some(40), Some(4)),
+    ]);
+
+    //Level 2 was moved to the end and level 3 was changed
+    let level_stats_match = save_game.match_level_stats(&[1, 5, 4, 2]);
+
+    assert_eq!(level_stats_match.level_stats(), [
+        LevelStats::new(Some(1000), Some(10), Some(1)),
+        LevelStats::default(),
+        LevelStats::new(Some(4000), Some(40), Some(4)),
+        LevelStats::new(Some(2000), Some(20), Some(2)),
+    ]);
+    assert_eq!(level_stats_match.level_changes(), [
+        LevelChange::Moved { from: 2, to: 4 },
+        LevelChange::Changed { level: 3 },
+        LevelChange::Moved { from: 4, to: 3 },
+    ]);
+    assert_eq!(level_stats_match.archived_level_stats(), [(3, LevelStats::new(Some(3000), Some(30), Some(3)))]);
+}
+
+#[test]
+fn save_level_stats_moved_after_level_progress() {
+    let save_game = SaveGame::new(2, vec![
+        LevelStats::new(Some(1000), Some(10), Some(1)),
+        LevelStats::new(Some(2000), Some(20), Some(2)),
+    ]);
+
+    //Level 2 was moved to level 5 which is after the level progress
+    let level_hashes = [1, 3, 4, 5, 2];
+    let level_stats_match = save_game.match_level_stats(&level_hashes);
+    assert_eq!(level_stats_match.level_changes(), [LevelChange::Moved { from: 2, to: 5 }]);
+
+    let level_stats = level_stats_match.level_stats().iter().
+            zip(level_hashes).
+            map(|(level_stats, level_hash)| LevelStats::new(level_stats.best_time(), level_stats.best_moves(), Some(level_hash))).
+            collect::<Vec<_>>();
+    let saved_save_game = SaveGame::from_level_stats(save_game.min_level_not_completed(), level_stats);
+
+    let save_game = SaveGame::parse(&saved_save_game.to_string(), level_hashes.len()).unwrap();
+    assert_eq!(save_game, saved_save_game);
+
+    let level_stats_match = save_game.match_level_stats(&level_hashes);
+    assert_eq!(level_stats_match.level_changes(), []);
+    assert!(level_stats_match.level_stats()[1].is_empty());
+    assert_eq!(level_stats_match.level_stats()[4], LevelStats::new(Some(2000), Some(20), Some(2)));
+
+    //Levels without stats after the level progress are not saved
+    let save_game = SaveGame::from_level_stats(1, vec![
+        LevelStats::new(Some(1000), Some(10), Some(1)),
+        LevelStats::new(None, None, Some(2)),
+    ]);
+    assert_eq!(save_game, SaveGame::new(1, vec![LevelStats::new(Some(1000), Some(10), Some(1))]));
+}
+
+#[test]
+fn match_legacy_level_stats_by_position() {
+    let save_game = SaveGame::new(2, vec![
+        LevelStats::new(Some(1000), Some(10), None),
+        LevelStats::new(Some(2000), Some(20), None),
+    ]);
+
+    let level_stats_match = save_game.match_level_stats(&[7]);
+
+    assert_eq!(level_stats_match.level_stats(), [LevelStats::new(Some(1000), Some(10), None)]);
+    assert!(level_stats_match.level_changes().is_empty());
+}
+
+#[test]
+fn save_game_round_trip() {
+    let save_game = SaveGame::new(2, vec![LevelStats::new(Some(1500), None, Some(0x1234)), LevelStats::new(None, Some(7), None)]);
+
+    assert_eq!(SaveGame::parse(&save_game.to_string(), 2).unwrap(), save_game);
+}
+
+#[test]
+fn write_rotates_backup() {
+    let temp_dir = TempDir::new("save-game-rotate");
+    let path = temp_dir.join("rotate.lvl.sav");
+
+    let first = SaveGame::new(1, vec![LevelStats::new(Some(1000), Some(10), Some(1))]);
+    let second = SaveGame::new(2, vec![LevelStats::new(Some(1000), Some(10), Some(1)), LevelStats::new(Some(2000), Some(20), Some(2))]);
+
+    first.write(&path, 2).unwrap();
+    assert!(!std::fs::exists(backup_path(&path)).unwrap());
+
+    second.write(&path, 2).unwrap();
+    assert_eq!(SaveGame::read(&path, 2).unwrap(), Some(second.clone()));
+    assert_eq!(SaveGame::read(&backup_path(&path), 2).unwrap(), Some(first.clone()));
+    assert!(!std::fs::exists(tmp_path(&path)).unwrap());
+
+    //Corrupt save games must not replace the backup
+    std::fs::write(&path, "v2\n2\nms1000,10,1\n").unwrap();
+    second.write(&path, 2).unwrap();
+    assert_eq!(SaveGame::read(&backup_path(&path), 2).unwrap(), Some(first));
+}
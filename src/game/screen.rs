@@ -1,17 +1,29 @@
 use std::cmp::Ordering;
+use std::error::Error;
 use std::fmt::Write as _;
 use std::str::FromStr;
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use dialog::DialogYesNo;
 use crate::game::{audio, Game, GameState};
-use crate::game::level::{Level, LevelPack, Tile};
+use crate::game::audio::{SoundCategory, SoundEvent};
+use crate::game::achievement::{self, Achievement, AchievementEvent};
+use crate::game::history::{Attempt, AttemptResult, LevelHistory};
+use crate::game::input::{Action, KeyPreset};
+use crate::game::suspend::{self, SaveState, SaveStateSlots, SuspendError, SuspendedLevel, SuspendedState};
+use crate::game::level::{Level, LevelDelta, LevelLoadingError, LevelPack, Tile};
+use crate::game::profile;
+use crate::game::settings::{BlinkRate, ColorTheme, FeedbackMode, Settings};
+use crate::game::statistics::Statistics;
 use crate::game::screen::dialog::{DialogOk, DialogSelection, DialogYesCancelNo};
-use crate::collections::UndoHistory;
+use crate::collections::{Deltas, Diff, UndoHistory, UndoTree};
 use crate::io::{Color, Console, Key};
 
 pub mod dialog;
 pub mod utils;
 
+#[cfg(test)]
+mod tests;
+
 #[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
 pub enum ScreenId {
     StartMenu,
@@ -19,10 +31,15 @@ pub enum ScreenId {
     SelectLevelPack,
     SelectLevel,
 
+    Statistics,
+    Achievements,
+    Settings,
+
     InGame,
 
     SelectLevelPackEditor,
     LevelPackEditor,
+    LevelPackRepair,
     LevelEditor,
 }
 
@@ -41,16 +58,237 @@ pub trait Screen {
     fn on_set_screen(&mut self, game_state: &mut GameState) {}
 }
 
-pub struct ScreenStartMenu {}
+pub struct ScreenStartMenu {
+    restoring_save_game_level_pack_id: Option<String>,
+
+    is_selecting_profile: bool,
+    profile_names: Vec<String>,
+    selected_profile_index: usize,
+
+    is_creating_new_profile: bool,
+    is_renaming_profile: bool,
+    is_deleting_profile: bool,
+    new_profile_name: String,
+}
 
 impl ScreenStartMenu {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            restoring_save_game_level_pack_id: None,
+
+            is_selecting_profile: Default::default(),
+            profile_names: Vec::new(),
+            selected_profile_index: Default::default(),
+
+            is_creating_new_profile: Default::default(),
+            is_renaming_profile: Default::default(),
+            is_deleting_profile: Default::default(),
+            new_profile_name: String::new(),
+        }
+    }
+
+    //Selects the profile with the name or the current profile if the name is None
+    fn reload_profile_names(&mut self, game_state: &mut GameState, selected_profile_name: Option<&str>) {
+        let profile_names = match game_state.profile_names() {
+            Ok(profile_names) => profile_names,
+            Err(err) => {
+                game_state.open_dialog(Box::new(DialogOk::new_error(format!("Cannot load profiles: {}", err))));
+
+                return;
+            },
+        };
+
+        let selected_profile_name = selected_profile_name.map_or_else(|| game_state.current_profile(), |name| name.to_string());
+        self.selected_profile_index = profile_names.iter().
+                position(|profile_name| *profile_name == selected_profile_name).
+                unwrap_or_default();
+        self.profile_names = profile_names;
+    }
+
+    fn draw_profile_selection(&self, game_state: &GameState, console: &Console) {
+        console.reset_color();
+        console.set_underline(true);
+        console.draw_text("Select a profile:");
+        console.set_underline(false);
+
+        let current_profile = game_state.current_profile();
+        for (i, profile_name) in self.profile_names.iter().
+                enumerate() {
+            console.set_cursor_pos(1, 2 + i);
+            if i == self.selected_profile_index {
+                console.set_color(Color::Cyan, Color::Default);
+                console.draw_text("> ");
+            }else {
+                console.draw_text("  ");
+            }
+            console.draw_text(profile_name);
+
+            if *profile_name == current_profile {
+                console.set_color(Color::LightYellow, Color::Default);
+                console.draw_text(" (Current)");
+            }
+            console.reset_color();
+        }
+
+        if self.is_creating_new_profile || self.is_renaming_profile {
+            console.set_cursor_pos(1, 16);
+            console.draw_text(if self.is_creating_new_profile {
+                "Enter a new profile name:"
+            }else {
+                "Enter the new name of the profile:"
+            });
+
+            console.set_cursor_pos(1, 17);
+            console.set_color(Color::Cyan, Color::Default);
+            console.draw_text(format!("> {}", &self.new_profile_name));
+            console.reset_color();
+        }
+
+        console.set_cursor_pos(1, Game::CONSOLE_MIN_HEIGHT - 2);
+        console.draw_text("ENTER: Select, N: New, R: Rename, DELETE: Delete, ESC: Back");
+    }
+
+    fn on_key_pressed_profile_name_input(&mut self, game_state: &mut GameState, key: Key) {
+        match game_state.text_input_menu_key(key) {
+            key if key.is_ascii() && (key.is_alphanumeric() || key == Key::UNDERSCORE || key == Key::MINUS) => {
+                if self.new_profile_name.len() >= profile::MAX_PROFILE_NAME_LEN {
+                    return;
+                }
+
+                let _ = write!(self.new_profile_name, "{}", key.to_ascii().unwrap() as char);
+            },
+            Key::DELETE => {
+                self.new_profile_name.pop();
+            },
+
+            Key::ENTER => {
+                let result = if self.is_creating_new_profile {
+                    game_state.create_profile(&self.new_profile_name)
+                }else {
+                    let profile_name = self.profile_names[self.selected_profile_index].clone();
+
+                    game_state.rename_profile(&profile_name, &self.new_profile_name)
+                };
+
+                if let Err(err) = result {
+                    game_state.open_dialog(Box::new(DialogOk::new_error(err.to_string())));
+
+                    return;
+                }
+
+                game_state.play_sound_effect_ui_select();
+
+                let new_profile_name = std::mem::take(&mut self.new_profile_name);
+                self.is_creating_new_profile = false;
+                self.is_renaming_profile = false;
+
+                self.reload_profile_names(game_state, Some(&new_profile_name));
+            },
+
+            Key::ESC => {
+                game_state.play_sound_event(SoundEvent::UiSelect);
+
+                self.is_creating_new_profile = false;
+                self.is_renaming_profile = false;
+                self.new_profile_name = String::new();
+            },
+
+            _ => {},
+        }
+    }
+
+    fn on_key_pressed_profile_selection(&mut self, game_state: &mut GameState, key: Key) {
+        if self.is_creating_new_profile || self.is_renaming_profile {
+            self.on_key_pressed_profile_name_input(game_state, key);
+
+            return;
+        }
+
+        match game_state.menu_key(key) {
+            Key::ESC => {
+                game_state.play_sound_event(SoundEvent::UiSelect);
+
+                self.is_selecting_profile = false;
+            },
+            Key::F1 => {
+                game_state.open_help_page();
+            },
+
+            Key::UP => {
+                self.selected_profile_index = self.selected_profile_index.saturating_sub(1);
+            },
+            Key::DOWN if self.selected_profile_index + 1 < self.profile_names.len() => {
+                self.selected_profile_index += 1;
+            },
+
+            Key::ENTER => {
+                let Some(profile_name) = self.profile_names.get(self.selected_profile_index) else {
+                    return;
+                };
+
+                if *profile_name != game_state.current_profile() && let Err(err) = game_state.select_profile(profile_name) {
+                    game_state.open_dialog(Box::new(DialogOk::new_error(format!("Cannot select profile: {}", err))));
+
+                    return;
+                }
+
+                game_state.play_sound_effect_ui_select();
+
+                self.is_selecting_profile = false;
+            },
+
+            Key::N => {
+                if self.profile_names.len() >= profile::MAX_PROFILE_COUNT {
+                    game_state.open_dialog(Box::new(DialogOk::new_error(format!(
+                        "Max profile count ({}) reached!",
+                        profile::MAX_PROFILE_COUNT,
+                    ))));
+
+                    return;
+                }
+
+                self.is_creating_new_profile = true;
+            },
+            Key::R => {
+                let Some(profile_name) = self.profile_names.get(self.selected_profile_index) else {
+                    return;
+                };
+
+                self.new_profile_name = profile_name.clone();
+                self.is_renaming_profile = true;
+            },
+            Key::DELETE => {
+                let Some(profile_name) = self.profile_names.get(self.selected_profile_index) else {
+                    return;
+                };
+
+                if self.profile_names.len() <= 1 {
+                    game_state.open_dialog(Box::new(DialogOk::new_error("The last profile can not be deleted!")));
+
+                    return;
+                }
+
+                self.is_deleting_profile = true;
+
+                game_state.open_dialog(Box::new(DialogYesNo::new(format!(
+                    "Delete profile \"{}\" and all of its stats?",
+                    profile_name,
+                ))));
+            },
+
+            _ => {},
+        }
     }
 }
 
 impl Screen for ScreenStartMenu {
-    fn draw(&self, _: &GameState, console: &Console) {
+    fn draw(&self, game_state: &GameState, console: &Console) {
+        if self.is_selecting_profile {
+            self.draw_profile_selection(game_state, console);
+
+            return;
+        }
+
         //Draw border (top)
         console.set_color(Color::White, Color::Blue);
         console.draw_text(
@@ -86,6 +324,51 @@ impl Screen for ScreenStartMenu {
         console.reset_color();
         console.draw_text(" to start the game!");
 
+        if let Some(suspended_level) = game_state.suspended_level() {
+            console.set_cursor_pos(21, 17);
+            console.draw_text("Continue (");
+            console.set_color(Color::LightRed, Color::Default);
+            console.draw_text("c");
+            console.reset_color();
+            console.draw_text(format!(
+                "): Level {} of \"{}\"",
+                suspended_level.level_index() + 1,
+                suspended_level.level_pack_id(),
+            ));
+        }
+
+        console.set_cursor_pos(21, 18);
+        console.draw_text("Profile: ");
+        console.set_color(Color::Cyan, Color::Default);
+        console.draw_text(game_state.current_profile());
+        console.reset_color();
+        console.draw_text(" (");
+        console.set_color(Color::LightRed, Color::Default);
+        console.draw_text("p");
+        console.reset_color();
+        console.draw_text(")");
+
+        console.set_cursor_pos(21, 19);
+        console.draw_text("Statistics (");
+        console.set_color(Color::LightRed, Color::Default);
+        console.draw_text("s");
+        console.reset_color();
+        console.draw_text(")");
+
+        console.set_cursor_pos(21, 20);
+        console.draw_text("Achievements (");
+        console.set_color(Color::LightRed, Color::Default);
+        console.draw_text("a");
+        console.reset_color();
+        console.draw_text(")");
+
+        console.set_cursor_pos(40, 19);
+        console.draw_text("Settings (");
+        console.set_color(Color::LightRed, Color::Default);
+        console.draw_text("o");
+        console.reset_color();
+        console.draw_text(")");
+
         console.set_cursor_pos(1, 21);
         console.draw_text("By ");
         console.set_color(Color::Default, Color::Yellow);
@@ -109,14 +392,69 @@ impl Screen for ScreenStartMenu {
         console.draw_text("\n\\------------------------------------------------------------------------/");
     }
 
+    fn update(&mut self, game_state: &mut GameState) {
+        if game_state.is_dialog_opened() {
+            return;
+        }
+
+        if let Some(startup_error) = game_state.pop_startup_error() {
+            game_state.open_dialog(Box::new(DialogOk::new_error(startup_error)));
+
+            return;
+        }
+
+        let Some(save_game_warning) = game_state.pop_save_game_warning() else {
+            if let Some((level_pack_id, level_changes)) = game_state.take_level_changes() {
+                const MAX_SHOWN_LEVEL_CHANGES: usize = 12;
+
+                let mut message = format!("Levels of \"{}\" were changed:", level_pack_id);
+                for level_change in level_changes.iter().
+                        take(MAX_SHOWN_LEVEL_CHANGES) {
+                    let _ = write!(message, "\n{}", level_change);
+                }
+
+                if level_changes.len() > MAX_SHOWN_LEVEL_CHANGES {
+                    let _ = write!(message, "\n... and {} more", level_changes.len() - MAX_SHOWN_LEVEL_CHANGES);
+                }
+
+                game_state.open_dialog(Box::new(DialogOk::new(message)));
+            }
+
+            return;
+        };
+
+        if save_game_warning.has_backup() {
+            self.restoring_save_game_level_pack_id = Some(save_game_warning.level_pack_id().to_string());
+
+            game_state.open_dialog(Box::new(DialogYesNo::new(format!(
+                "Save game of \"{}\" is corrupt! Restore backup?",
+                save_game_warning.level_pack_id(),
+            ))));
+        }else {
+            game_state.open_dialog(Box::new(DialogOk::new_error(format!(
+                "Save game of \"{}\" is corrupt!\n{}\nThe progress of this level pack was reset.",
+                save_game_warning.level_pack_id(),
+                save_game_warning.error(),
+            ))));
+        }
+    }
+
     fn on_key_pressed(&mut self, game_state: &mut GameState, key: Key) {
-        if key == Key::ESC {
+        if self.is_selecting_profile {
+            self.on_key_pressed_profile_selection(game_state, key);
+
+            return;
+        }
+
+        //Only the navigation keys are mapped: The letter keys of the start menu would conflict with the movement keys
+        let menu_key = game_state.menu_key(key);
+        if menu_key == Key::ESC {
             game_state.open_dialog(Box::new(DialogYesNo::new("Exit game?")));
 
             return;
         }
 
-        if key == Key::F1 {
+        if menu_key == Key::F1 {
             game_state.open_help_page();
 
             return;
@@ -127,19 +465,112 @@ impl Screen for ScreenStartMenu {
 
             game_state.set_screen(ScreenId::SelectLevelPack);
         }
+
+        if key == Key::C && game_state.suspended_level().is_some() {
+            game_state.play_sound_effect_ui_select();
+
+            if let Err(err) = game_state.resume_suspended_level() {
+                game_state.open_dialog(Box::new(DialogOk::new_error(format!("Cannot resume level: {}", err))));
+            }
+        }
+
+        if key == Key::P {
+            game_state.play_sound_effect_ui_select();
+
+            self.reload_profile_names(game_state, None);
+            self.is_selecting_profile = true;
+        }
+
+        if key == Key::S {
+            game_state.play_sound_effect_ui_select();
+
+            game_state.set_screen(ScreenId::Statistics);
+        }
+
+        if key == Key::A {
+            game_state.play_sound_effect_ui_select();
+
+            game_state.set_screen(ScreenId::Achievements);
+        }
+
+        if key == Key::O {
+            game_state.play_sound_effect_ui_select();
+
+            game_state.open_settings();
+        }
     }
 
     fn on_mouse_pressed(&mut self, game_state: &mut GameState, column: usize, row: usize) {
+        if self.is_selecting_profile {
+            if self.is_creating_new_profile || self.is_renaming_profile {
+                return;
+            }
+
+            //Clicking the selected profile selects it
+            if row >= 2 && row - 2 < self.profile_names.len() && column > 0 && column < Game::CONSOLE_MIN_WIDTH - 1 {
+                if row - 2 == self.selected_profile_index {
+                    self.on_key_pressed(game_state, Key::ENTER);
+                }else {
+                    self.selected_profile_index = row - 2;
+                }
+            }
+
+            return;
+        }
+
         if row == 16 && column > 26 && column < 32 {
             self.on_key_pressed(game_state, Key::ENTER);
         }
 
+        if row == 17 && column > 20 && column < 33 {
+            self.on_key_pressed(game_state, Key::C);
+        }
+
+        if row == 18 && column > 20 && column < 30 {
+            self.on_key_pressed(game_state, Key::P);
+        }
+
+        if row == 19 && column > 20 && column < 36 {
+            self.on_key_pressed(game_state, Key::S);
+        }
+
+        if row == 20 && column > 20 && column < 38 {
+            self.on_key_pressed(game_state, Key::A);
+        }
+
+        if row == 19 && column > 39 && column < 52 {
+            self.on_key_pressed(game_state, Key::O);
+        }
+
         if row == 21 && column > 64 && column < 73 {
             game_state.open_help_page();
         }
     }
 
     fn on_dialog_selection(&mut self, game_state: &mut GameState, selection: DialogSelection) {
+        if self.is_deleting_profile {
+            self.is_deleting_profile = false;
+
+            if selection == DialogSelection::Yes {
+                let profile_name = self.profile_names[self.selected_profile_index].clone();
+                if let Err(err) = game_state.delete_profile(&profile_name) {
+                    game_state.open_dialog(Box::new(DialogOk::new_error(format!("Cannot delete: {}", err))));
+                }
+
+                self.reload_profile_names(game_state, None);
+            }
+
+            return;
+        }
+
+        if let Some(level_pack_id) = self.restoring_save_game_level_pack_id.take() {
+            if selection == DialogSelection::Yes && let Err(err) = game_state.restore_save_game_backup(&level_pack_id) {
+                game_state.open_dialog(Box::new(DialogOk::new_error(format!("Cannot restore backup: {}", err))));
+            }
+
+            return;
+        }
+
         if selection == DialogSelection::Yes {
             game_state.exit();
         }
@@ -275,8 +706,10 @@ impl Screen for ScreenSelectLevelPack {
     }
 
     fn on_key_pressed(&mut self, game_state: &mut GameState, key: Key) {
+        let key = game_state.menu_key(key);
+
         if key == Key::ESC {
-            game_state.play_sound_effect(audio::UI_SELECT_EFFECT);
+            game_state.play_sound_event(SoundEvent::UiSelect);
 
             game_state.set_screen(ScreenId::StartMenu);
 
@@ -364,47 +797,588 @@ impl Screen for ScreenSelectLevelPack {
     }
 }
 
-pub struct ScreenSelectLevel {
-    selected_level: usize,
+pub struct ScreenStatistics {
+    statistics: Statistics,
+    has_history_error: bool,
+
+    scroll_pos: usize,
 }
 
-impl ScreenSelectLevel {
+impl ScreenStatistics {
+    const LEVEL_PACK_ROW_COUNT: usize = 9;
+
     pub fn new() -> Self {
         Self {
-            selected_level: Default::default(),
+            statistics: Default::default(),
+            has_history_error: Default::default(),
+
+            scroll_pos: Default::default(),
         }
     }
+
+    fn time_to_string(time: u64) -> String {
+        format!("{:02}:{:02}.{:03}", time/60000, (time%60000)/1000, time%1000)
+    }
 }
 
-impl Screen for ScreenSelectLevel {
+impl Screen for ScreenStatistics {
     fn draw(&self, game_state: &GameState, console: &Console) {
         console.reset_color();
         console.set_underline(true);
-        console.draw_text(format!("Select a level (Level pack \"{}\"):", game_state.get_current_level_pack().unwrap().id()));
+        console.draw_text(format!("Statistics (Profile \"{}\"):", game_state.current_profile()));
         console.set_underline(false);
 
-        let level_count = game_state.get_current_level_pack().as_ref().unwrap().level_count();
+        let play_time = self.statistics.play_time();
 
-        //Draw first line
-        console.set_cursor_pos(0, 1);
-        console.draw_text("-");
-        let mut max = level_count%24;
-        if level_count/24 > 0 {
-            max = 24;
-        }
-        for i in 0..max {
-            let x = 1 + (i%24)*3;
+        console.set_cursor_pos(1, 2);
+        console.draw_text(format!(
+            "Play time    : {:02}:{:02}:{:02}.{:03}",
+            play_time/3600000,
+            (play_time%3600000)/60000,
+            (play_time%60000)/1000,
+            play_time%1000,
+        ));
 
-            console.set_cursor_pos(x, 1);
-            console.draw_text("---");
-        }
+        console.set_cursor_pos(1, 3);
+        console.draw_text(format!("Moves        : {}", self.statistics.moves()));
+        console.set_cursor_pos(1, 4);
+        console.draw_text(format!("Undos        : {}", self.statistics.undo_count()));
+        console.set_cursor_pos(1, 5);
+        console.draw_text(format!("Resets       : {}", self.statistics.reset_count()));
 
-        for i in 0..level_count {
-            let x = 1 + (i%24)*3;
-            let y = 2 + (i/24)*2;
+        console.set_cursor_pos(1, 6);
+        console.draw_text(format!(
+            "Secrets found: {}/{}",
+            self.statistics.found_secret_count(),
+            self.statistics.secret_count(),
+        ));
 
-            //First box
-            if x == 1 {
+        console.set_cursor_pos(1, 7);
+        console.draw_text(format!(
+            "Completion   : {:.1}% ({}/{} levels)",
+            self.statistics.completion_percentage(),
+            self.statistics.cleared_level_count(),
+            self.statistics.level_count(),
+        ));
+
+        console.set_cursor_pos(1, 8);
+        console.draw_text(format!(
+            "Best sums    : {}, {:04} moves (Completed level packs)",
+            Self::time_to_string(self.statistics.best_time_sum()),
+            self.statistics.best_moves_sum(),
+        ));
+
+        console.set_cursor_pos(1, 10);
+        console.set_color(Color::Cyan, Color::Default);
+        console.draw_text("Level pack        Cleared  Completion  Best time sum  Best moves sum");
+        console.reset_color();
+
+        for (i, level_pack) in self.statistics.level_packs().iter().
+                skip(self.scroll_pos).
+                take(Self::LEVEL_PACK_ROW_COUNT).
+                enumerate() {
+            let completion_percentage = if level_pack.level_count() == 0 {
+                0.0
+            }else {
+                level_pack.cleared_level_count() as f64 * 100.0 / level_pack.level_count() as f64
+            };
+
+            console.set_cursor_pos(1, 11 + i);
+            console.draw_text(format!(
+                "{:<16}  {:>3}/{:<3}  {:>9.1}%  {:>13}  {:>14}",
+                level_pack.id(),
+                level_pack.cleared_level_count(),
+                level_pack.level_count(),
+                completion_percentage,
+                level_pack.best_time_sum().map_or("XX:XX.XXX".to_string(), Self::time_to_string),
+                level_pack.best_moves_sum().map_or("XXXX".to_string(), |best_moves_sum| format!("{:04}", best_moves_sum)),
+            ));
+        }
+
+        if self.has_history_error {
+            console.set_cursor_pos(1, Game::CONSOLE_MIN_HEIGHT - 2);
+            console.set_color(Color::LightRed, Color::Default);
+            console.draw_text("The history of some level packs could not be loaded");
+            console.reset_color();
+        }
+    }
+
+    fn on_key_pressed(&mut self, game_state: &mut GameState, key: Key) {
+        let key = game_state.menu_key(key);
+
+        match key {
+            Key::ESC => {
+                game_state.play_sound_event(SoundEvent::UiSelect);
+
+                game_state.set_screen(ScreenId::StartMenu);
+            },
+            Key::F1 => {
+                game_state.open_help_page();
+            },
+
+            Key::UP => {
+                self.scroll_pos = self.scroll_pos.saturating_sub(1);
+            },
+            Key::DOWN if self.scroll_pos + Self::LEVEL_PACK_ROW_COUNT < self.statistics.level_packs().len() => {
+                self.scroll_pos += 1;
+            },
+
+            _ => {},
+        }
+    }
+
+    fn on_set_screen(&mut self, game_state: &mut GameState) {
+        //The main level pack contains the only secret
+        let mut statistics = Statistics::new(game_state.has_found_secret() as usize, 1);
+
+        self.has_history_error = false;
+        for level_pack in game_state.level_packs() {
+            let attempts = level_pack.read_attempts().unwrap_or_else(|_| {
+                self.has_history_error = true;
+
+                Vec::new()
+            });
+
+            statistics.add_level_pack(level_pack, &attempts);
+        }
+
+        self.statistics = statistics;
+        self.scroll_pos = 0;
+    }
+}
+
+pub struct ScreenAchievements {}
+
+impl ScreenAchievements {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Screen for ScreenAchievements {
+    fn draw(&self, game_state: &GameState, console: &Console) {
+        console.reset_color();
+        console.set_underline(true);
+
+        let achievements = match game_state.achievements() {
+            Ok(achievements) => achievements,
+            Err(err) => {
+                console.draw_text("Achievements:");
+                console.set_underline(false);
+
+                console.set_cursor_pos(1, 2);
+                console.set_color(Color::LightRed, Color::Default);
+                console.draw_text(format!("Achievements could not be loaded: {}", err));
+                console.reset_color();
+
+                return;
+            },
+        };
+
+        console.draw_text(format!(
+            "Achievements ({}/{} unlocked):",
+            achievements.unlocked_count(),
+            Achievement::ALL.len(),
+        ));
+        console.set_underline(false);
+
+        for (i, achievement) in Achievement::ALL.iter().
+                enumerate() {
+            let y = 2 + 2 * i;
+
+            console.set_cursor_pos(1, y);
+            if let Some(unlock_timestamp) = achievements.unlock_timestamp(*achievement) {
+                console.set_color(Color::Green, Color::Default);
+                console.draw_text(format!("[X] {}", achievement.name()));
+                console.reset_color();
+                console.draw_text(format!(" (Unlocked: {})", achievement::timestamp_to_date(unlock_timestamp)));
+            }else {
+                console.set_color(Color::LightBlack, Color::Default);
+                console.draw_text(format!("[ ] {}", achievement.name()));
+                console.reset_color();
+            }
+
+            console.set_cursor_pos(5, y + 1);
+            console.draw_text(achievement.description());
+        }
+
+        let play_time = achievements.play_time();
+
+        console.set_cursor_pos(1, 3 + 2 * Achievement::ALL.len());
+        console.draw_text(format!(
+            "Play time: {:02}:{:02}:{:02}",
+            play_time/3600000,
+            (play_time%3600000)/60000,
+            (play_time%60000)/1000,
+        ));
+    }
+
+    fn on_key_pressed(&mut self, game_state: &mut GameState, key: Key) {
+        let key = game_state.menu_key(key);
+
+        if key == Key::ESC {
+            game_state.play_sound_event(SoundEvent::UiSelect);
+
+            game_state.set_screen(ScreenId::StartMenu);
+
+            return;
+        }
+
+        if key == Key::F1 {
+            game_state.open_help_page();
+        }
+    }
+}
+
+pub struct ScreenSettings {
+    selected_entry_index: usize,
+
+    //Names of the installed sound packs (Loaded if the screen is opened)
+    sound_packs: Vec<String>,
+}
+
+impl ScreenSettings {
+    const ENTRY_NAMES: [&'static str; 15] = [
+        "Sound",
+        "Master volume",
+        "UI volume",
+        "Gameplay volume",
+        "Jingle volume",
+        "Music volume",
+        "Sound pack",
+        "Feedback",
+        "Stereo panning",
+        "Color theme",
+        "Player blink",
+        "Player blink rate",
+        "Deadlock warnings",
+        "Confirmation prompts",
+        "Key bindings",
+    ];
+
+    pub fn new() -> Self {
+        Self {
+            selected_entry_index: Default::default(),
+
+            sound_packs: Vec::new(),
+        }
+    }
+
+    fn switch_to_str(is_enabled: bool) -> &'static str {
+        if is_enabled {
+            "On"
+        }else {
+            "Off"
+        }
+    }
+
+    fn entry_value(game_state: &GameState, entry_index: usize) -> String {
+        let settings = game_state.settings();
+        match entry_index {
+            0 => Self::switch_to_str(settings.is_sound_enabled()).to_string(),
+            1 => format!("{}%", settings.master_volume()),
+            2 => format!("{}%", settings.category_volume(SoundCategory::Ui)),
+            3 => format!("{}%", settings.category_volume(SoundCategory::Gameplay)),
+            4 => format!("{}%", settings.category_volume(SoundCategory::Jingles)),
+            5 => format!("{}%", settings.music_volume()),
+            6 => settings.sound_pack().unwrap_or("Default").to_string(),
+            7 => match settings.feedback_mode() {
+                FeedbackMode::Auto => format!("Auto ({})", game_state.feedback_mode().name()),
+
+                feedback_mode => feedback_mode.name().to_string(),
+            },
+            9 => settings.color_theme().name().to_string(),
+            10 => Self::switch_to_str(settings.is_player_blink_enabled()).to_string(),
+            11 => settings.player_blink_rate().name().to_string(),
+            12 => Self::switch_to_str(settings.is_deadlock_warning_enabled()).to_string(),
+            13 => Self::switch_to_str(settings.is_confirmation_enabled()).to_string(),
+            14 => {
+                let key_bindings = settings.key_bindings();
+                if key_bindings.custom_bindings().is_empty() {
+                    key_bindings.preset().name().to_string()
+                }else {
+                    format!("{} (Custom)", key_bindings.preset().name())
+                }
+            },
+
+            _ => String::new(),
+        }
+    }
+
+    //Returns the previous or next value (Values wrap around)
+    fn cycle<T: Copy + PartialEq>(values: &[T], value: T, is_next: bool) -> T {
+        let index = values.iter().position(|v| *v == value).unwrap_or_default();
+        let index = if is_next {
+            (index + 1) % values.len()
+        }else {
+            (index + values.len() - 1) % values.len()
+        };
+
+        values[index]
+    }
+
+    //The volume does not wrap around
+    fn change_volume(volume: u32, is_next: bool) -> u32 {
+        if is_next {
+            volume + Settings::VOLUME_STEP
+        }else {
+            volume.saturating_sub(Settings::VOLUME_STEP)
+        }
+    }
+
+    fn change_selected_entry(&mut self, game_state: &mut GameState, is_next: bool) {
+        let mut settings = game_state.settings().clone();
+        match self.selected_entry_index {
+            0 => settings.set_sound_enabled(!settings.is_sound_enabled()),
+            1 => settings.set_master_volume(Self::change_volume(settings.master_volume(), is_next)),
+            entry_index @ 2..=4 => {
+                let category = SoundCategory::ALL[entry_index - 2];
+
+                settings.set_category_volume(category, Self::change_volume(settings.category_volume(category), is_next));
+            },
+            5 => settings.set_music_volume(Self::change_volume(settings.music_volume(), is_next)),
+            6 => {
+                //None is the default sound pack
+                let sound_packs = [None].into_iter().
+                        chain(self.sound_packs.iter().map(|sound_pack| Some(sound_pack.as_str()))).
+                        collect::<Vec<_>>();
+
+                let sound_pack = Self::cycle(&sound_packs, settings.sound_pack(), is_next).map(str::to_string);
+                settings.set_sound_pack(sound_pack);
+            },
+            7 => settings.set_feedback_mode(Self::cycle(&FeedbackMode::ALL, settings.feedback_mode(), is_next)),
+            8 => settings.set_stereo_panning_enabled(!settings.is_stereo_panning_enabled()),
+            9 => settings.set_color_theme(Self::cycle(&ColorTheme::ALL, settings.color_theme(), is_next)),
+            10 => settings.set_player_blink_enabled(!settings.is_player_blink_enabled()),
+            11 => settings.set_player_blink_rate(Self::cycle(&BlinkRate::ALL, settings.player_blink_rate(), is_next)),
+            12 => settings.set_deadlock_warning_enabled(!settings.is_deadlock_warning_enabled()),
+            13 => settings.set_confirmation_enabled(!settings.is_confirmation_enabled()),
+            14 => {
+                //Custom bindings from the settings file are kept
+                let key_bindings = settings.key_bindings_mut();
+                key_bindings.set_preset(Self::cycle(&KeyPreset::ALL, key_bindings.preset(), is_next));
+            },
+
+            _ => return,
+        }
+
+        if let Err(err) = game_state.set_settings(settings) {
+            game_state.open_dialog(Box::new(DialogOk::new_error(format!("Cannot save settings: {}", err))));
+
+            return;
+        }
+
+        game_state.play_sound_effect_ui_select();
+    }
+}
+
+impl Screen for ScreenSettings {
+    fn draw(&self, game_state: &GameState, console: &Console) {
+        console.reset_color();
+        console.set_underline(true);
+        console.draw_text(format!("Settings (Profile \"{}\"):", game_state.current_profile()));
+        console.set_underline(false);
+
+        for (i, entry_name) in Self::ENTRY_NAMES.iter().
+                enumerate() {
+            console.set_cursor_pos(1, 2 + i);
+            if i == self.selected_entry_index {
+                console.set_color(Color::Cyan, Color::Default);
+                console.draw_text("> ");
+            }else {
+                console.draw_text("  ");
+            }
+            console.draw_text(format!("{:<20}: ", entry_name));
+            console.reset_color();
+
+            console.draw_text(Self::entry_value(game_state, i));
+        }
+
+        console.set_cursor_pos(1, 3 + Self::ENTRY_NAMES.len());
+        console.draw_text("Preview: ");
+        for tile in [Tile::Wall, Tile::Player, Tile::Empty, Tile::Box, Tile::Goal, Tile::BoxInGoal, Tile::Key, Tile::LockedDoor, Tile::Hole] {
+            tile.draw(console, game_state.settings().color_theme(), game_state.is_player_background(), false);
+        }
+        console.reset_color();
+
+        console.set_cursor_pos(1, Game::CONSOLE_MIN_HEIGHT - 2);
+        console.draw_text("UP/DOWN: Select, LEFT/RIGHT/ENTER: Change, ESC: Back");
+    }
+
+    fn on_key_pressed(&mut self, game_state: &mut GameState, key: Key) {
+        let key = game_state.menu_key(key);
+
+        match key {
+            Key::ESC => {
+                game_state.play_sound_event(SoundEvent::UiSelect);
+
+                game_state.close_settings();
+            },
+            Key::F1 => {
+                game_state.open_help_page();
+            },
+
+            Key::UP => {
+                self.selected_entry_index = (self.selected_entry_index + Self::ENTRY_NAMES.len() - 1) % Self::ENTRY_NAMES.len();
+            },
+            Key::DOWN => {
+                self.selected_entry_index = (self.selected_entry_index + 1) % Self::ENTRY_NAMES.len();
+            },
+
+            Key::LEFT => self.change_selected_entry(game_state, false),
+            Key::RIGHT | Key::ENTER => self.change_selected_entry(game_state, true),
+
+            _ => {},
+        }
+    }
+
+    fn on_mouse_pressed(&mut self, game_state: &mut GameState, column: usize, row: usize) {
+        //Clicking the selected entry changes its value
+        if row >= 2 && row - 2 < Self::ENTRY_NAMES.len() && column > 0 && column < Game::CONSOLE_MIN_WIDTH - 1 {
+            if row - 2 == self.selected_entry_index {
+                self.change_selected_entry(game_state, true);
+            }else {
+                self.selected_entry_index = row - 2;
+            }
+        }
+    }
+
+    fn on_set_screen(&mut self, _: &mut GameState) {
+        self.selected_entry_index = 0;
+
+        //Only the default sound pack can be selected if the sound packs could not be listed
+        self.sound_packs = Game::get_or_create_sound_pack_folder().
+                and_then(|sound_pack_folder| audio::installed_sound_packs(&sound_pack_folder)).
+                unwrap_or_default();
+    }
+}
+
+pub struct ScreenSelectLevel {
+    selected_level: usize,
+
+    //None if the history could not be loaded
+    level_history: Option<LevelHistory>,
+}
+
+impl ScreenSelectLevel {
+    pub fn new() -> Self {
+        Self {
+            selected_level: Default::default(),
+
+            level_history: Default::default(),
+        }
+    }
+
+    fn load_level_history(&mut self, game_state: &GameState) {
+        self.level_history = game_state.get_current_level_pack().
+                and_then(|level_pack| level_pack.read_level_history(self.selected_level).ok());
+    }
+
+    fn draw_level_history(&self, console: &Console, y: usize) {
+        const WIDTH: usize = 46;
+
+        console.set_cursor_pos(28, y);
+        console.set_color(Color::Cyan, Color::Default);
+        console.draw_text(format!(".{}.", "-".repeat(WIDTH)));
+        for i in 1..4 {
+            console.set_cursor_pos(28, y + i);
+            console.draw_text(format!("|{}|", " ".repeat(WIDTH)));
+        }
+        console.set_cursor_pos(28, y + 4);
+        console.draw_text(format!("\'{}\'", "-".repeat(WIDTH)));
+        console.reset_color();
+
+        let Some(level_history) = &self.level_history else {
+            console.set_cursor_pos(29, y + 2);
+            console.set_color(Color::LightRed, Color::Default);
+            console.draw_text("History could not be loaded");
+            console.reset_color();
+
+            return;
+        };
+
+        console.set_cursor_pos(29, y + 1);
+        console.draw_text(format!(
+            "Attempts: {:04} (Completed: {:04}, Resets: {:04})",
+            level_history.attempt_count(),
+            level_history.completed_count(),
+            level_history.reset_count(),
+        ));
+
+        console.set_cursor_pos(29, y + 2);
+        console.draw_text("Average : ");
+        match level_history.average_time().zip(level_history.average_moves()) {
+            None => console.draw_text("XX:XX.XXX, XXXX moves"),
+            Some((average_time, average_moves)) => {
+                console.draw_text(format!(
+                    "{:02}:{:02}.{:03}, {:04} moves",
+                    average_time/60000,
+                    (average_time%60000)/1000,
+                    average_time%1000,
+                    average_moves,
+                ));
+            },
+        }
+
+        //Only the newest personal bests are shown if the progression is too long
+        let mut best_moves_progression = String::new();
+        for (i, best_moves) in level_history.best_moves_progression().iter().
+                rev().
+                enumerate() {
+            let best_moves = if i == 0 {
+                best_moves.to_string()
+            }else {
+                format!("{} > ", best_moves)
+            };
+
+            if best_moves_progression.len() + best_moves.len() > WIDTH - 16 {
+                best_moves_progression.insert_str(0, "... > ");
+
+                break;
+            }
+
+            best_moves_progression.insert_str(0, &best_moves);
+        }
+
+        console.set_cursor_pos(29, y + 3);
+        console.draw_text("PB moves: ");
+        if best_moves_progression.is_empty() {
+            console.draw_text("XXXX");
+        }else {
+            console.draw_text(best_moves_progression);
+        }
+    }
+}
+
+impl Screen for ScreenSelectLevel {
+    fn draw(&self, game_state: &GameState, console: &Console) {
+        console.reset_color();
+        console.set_underline(true);
+        console.draw_text(format!("Select a level (Level pack \"{}\"):", game_state.get_current_level_pack().unwrap().id()));
+        console.set_underline(false);
+
+        let level_count = game_state.get_current_level_pack().as_ref().unwrap().level_count();
+
+        //Draw first line
+        console.set_cursor_pos(0, 1);
+        console.draw_text("-");
+        let mut max = level_count%24;
+        if level_count/24 > 0 {
+            max = 24;
+        }
+        for i in 0..max {
+            let x = 1 + (i%24)*3;
+
+            console.set_cursor_pos(x, 1);
+            console.draw_text("---");
+        }
+
+        for i in 0..level_count {
+            let x = 1 + (i%24)*3;
+            let y = 2 + (i/24)*2;
+
+            //First box
+            if x == 1 {
                 console.set_cursor_pos(x - 1, y);
                 console.draw_text("|");
 
@@ -477,130 +1451,566 @@ impl Screen for ScreenSelectLevel {
                     best_time%1000
                 ));
             },
-        }
-        console.set_cursor_pos(1, y + 3);
-        console.draw_text("Best moves    :      ");
-        match game_state.get_current_level_pack().as_ref().unwrap().levels().get(selected_level).unwrap().best_moves() {
-            None => console.draw_text("XXXX"),
-            Some(best_moves) => {
-                console.draw_text(format!("{:04}", best_moves));
+        }
+        console.set_cursor_pos(1, y + 3);
+        console.draw_text("Best moves    :      ");
+        match game_state.get_current_level_pack().as_ref().unwrap().levels().get(selected_level).unwrap().best_moves() {
+            None => console.draw_text("XXXX"),
+            Some(best_moves) => {
+                console.draw_text(format!("{:04}", best_moves));
+            },
+        }
+
+        self.draw_level_history(console, y);
+    }
+
+    fn on_key_pressed(&mut self, game_state: &mut GameState, key: Key) {
+        let key = game_state.menu_key(key);
+
+        if key == Key::ESC {
+            game_state.play_sound_event(SoundEvent::UiSelect);
+
+            game_state.set_screen(ScreenId::SelectLevelPack);
+
+            return;
+        }
+
+        if key == Key::F1 {
+            game_state.open_help_page();
+
+            return;
+        }
+
+        'outer: {
+            match key {
+                Key::LEFT => {
+                    if self.selected_level == 0 {
+                        break 'outer;
+                    }
+
+                    self.selected_level -= 1;
+                },
+                Key::UP => {
+                    if self.selected_level < 24 {
+                        break 'outer;
+                    }
+
+                    self.selected_level -= 24;
+                },
+                Key::RIGHT => {
+                    if self.selected_level + 1 >= game_state.get_current_level_pack().
+                            as_ref().unwrap().level_count() {
+                        break 'outer;
+                    }
+
+                    self.selected_level += 1;
+                },
+                Key::DOWN => {
+                    if self.selected_level + 24 >= game_state.get_current_level_pack().
+                            as_ref().unwrap().level_count() {
+                        break 'outer;
+                    }
+
+                    self.selected_level += 24;
+                },
+
+                Key::ENTER => {
+                    if self.selected_level <= game_state.get_current_level_pack().
+                            as_ref().unwrap().min_level_not_completed() {
+                        game_state.play_sound_effect_ui_select();
+
+                        game_state.set_level_index(self.selected_level);
+                        game_state.set_screen(ScreenId::InGame);
+                    }else {
+                        game_state.play_sound_effect_ui_error();
+                    }
+                },
+
+                _ => {},
+            }
+        }
+
+        if key.is_arrow_key() {
+            self.load_level_history(game_state);
+        }
+    }
+
+    fn on_mouse_pressed(&mut self, game_state: &mut GameState, column: usize, row: usize) {
+        if row == 0 {
+            return;
+        }
+
+        let level_index = column/3 + (row - 1)/2*24;
+        if level_index < game_state.get_current_level_pack().as_ref().unwrap().level_count() {
+            self.selected_level = level_index;
+            self.load_level_history(game_state);
+
+            self.on_key_pressed(game_state, Key::ENTER);
+        }
+    }
+
+    fn on_set_screen(&mut self, game_state: &mut GameState) {
+        self.selected_level = game_state.get_level_index();
+        self.load_level_history(game_state);
+    }
+}
+
+#[derive(Debug, Clone)]
+struct PlayingState {
+    level: Level,
+    player_pos: (usize, usize),
+    moves: u32,
+    pushes: u32,
+}
+
+#[derive(Debug)]
+struct PlayingStateDelta {
+    level: LevelDelta,
+    player_pos: ((usize, usize), (usize, usize)),
+    moves: (u32, u32),
+    pushes: (u32, u32),
+}
+
+impl Diff for PlayingState {
+    type Delta = PlayingStateDelta;
+
+    fn diff(&self, new: &Self) -> Self::Delta {
+        PlayingStateDelta {
+            level: self.level.diff(&new.level),
+            player_pos: (self.player_pos, new.player_pos),
+            moves: (self.moves, new.moves),
+            pushes: (self.pushes, new.pushes),
+        }
+    }
+
+    fn apply(&mut self, delta: &Self::Delta) {
+        self.level.apply(&delta.level);
+        self.player_pos = delta.player_pos.1;
+        self.moves = delta.moves.1;
+        self.pushes = delta.pushes.1;
+    }
+
+    fn revert(&mut self, delta: &Self::Delta) {
+        self.level.revert(&delta.level);
+        self.player_pos = delta.player_pos.0;
+        self.moves = delta.moves.0;
+        self.pushes = delta.pushes.0;
+    }
+}
+
+pub struct ScreenInGame {
+    time_start_in_menu: Option<SystemTime>,
+    time_start: Option<SystemTime>,
+    time_millis: u32,
+    time_sec: u32,
+    time_min: u32,
+
+    level: Option<UndoTree<PlayingState, Deltas<PlayingState>>>,
+
+    undo_count: u32,
+    reset_count: u32,
+
+    is_selecting_save_state_slot: bool,
+    selected_save_state_slot: usize,
+    save_states: SaveStateSlots,
+
+    is_confirming_reset: bool,
+
+    continue_flag: bool,
+    secret_found_flag: bool,
+    game_over_flag: bool,
+}
+
+impl ScreenInGame {
+    pub const UNDO_HISTORY_SIZE_PLAYING: usize = 10000;
+
+    //A confirmation dialog is shown if the level is reset after more moves
+    pub const RESET_CONFIRMATION_MOVES: u32 = 100;
+
+    pub fn new() -> Self {
+        Self {
+            time_start_in_menu: Default::default(),
+            time_start: Default::default(),
+            time_millis: Default::default(),
+            time_sec: Default::default(),
+            time_min: Default::default(),
+
+            level: Default::default(),
+
+            undo_count: Default::default(),
+            reset_count: Default::default(),
+
+            is_selecting_save_state_slot: Default::default(),
+            selected_save_state_slot: Default::default(),
+            save_states: Default::default(),
+
+            is_confirming_reset: Default::default(),
+
+            continue_flag: Default::default(),
+            secret_found_flag: Default::default(),
+            game_over_flag: Default::default(),
+        }
+    }
+
+    //An attempt lasts until the level is completed or abandoned (Resets do not start a new attempt)
+    fn start_attempt(&mut self, level: &Level) {
+        self.undo_count = 0;
+        self.reset_count = 0;
+
+        self.start_level(level);
+    }
+
+    fn is_attempt_started(&self) -> bool {
+        self.time_start.is_some() || self.reset_count > 0
+    }
+
+    fn current_time(&self) -> u64 {
+        self.time_millis as u64 + 1000 * self.time_sec as u64 + 60000 * self.time_min as u64
+    }
+
+    //The timer is started as if the time had already passed
+    fn set_time(&mut self, time: u64) {
+        let time = Duration::from_millis(time);
+
+        self.time_start = SystemTime::now().checked_sub(time);
+        self.time_millis = (time.as_millis() % 1000) as u32;
+        self.time_sec = (time.as_secs() % 60) as u32;
+        self.time_min = (time.as_secs() / 60 % 60) as u32;
+    }
+
+    fn restore_playing_state(level_original: &Level, state: &SuspendedState) -> Result<PlayingState, SuspendError> {
+        Ok(PlayingState {
+            level: state.to_level(level_original)?,
+            player_pos: state.player_pos(),
+            moves: state.moves(),
+            pushes: state.pushes(),
+        })
+    }
+
+    fn load_save_states(&mut self, game_state: &mut GameState) {
+        let current_level_index = game_state.current_level_index;
+        let Some(level_pack) = game_state.get_current_level_pack() else {
+            return;
+        };
+
+        match level_pack.read_save_states(current_level_index) {
+            Ok(save_states) => self.save_states = save_states,
+            Err(err) => {
+                self.save_states = Default::default();
+
+                game_state.open_dialog(Box::new(DialogOk::new_error(format!("Cannot load save states: {}", err))));
+            },
+        }
+    }
+
+    fn save_state(&mut self, game_state: &mut GameState, slot: usize) {
+        let current_level_index = game_state.current_level_index;
+        let Some(level_pack) = game_state.get_current_level_pack() else {
+            return;
+        };
+
+        let state = self.level.as_ref().unwrap().current();
+        let save_state = SaveState::new(self.current_time(), SuspendedState::new(
+            level_pack.levels()[current_level_index].level(),
+            &state.level,
+            state.player_pos,
+            state.moves,
+            state.pushes,
+        ));
+
+        if let Err(err) = level_pack.write_save_state(current_level_index, slot, &save_state) {
+            game_state.open_dialog(Box::new(DialogOk::new_error(format!("Cannot save state: {}", err))));
+
+            return;
+        }
+
+        self.save_states[slot] = Some(save_state);
+
+        game_state.play_sound_effect_ui_select();
+    }
+
+    //Loading a save state is a new undoable step
+    fn load_state(&mut self, game_state: &mut GameState, slot: usize) {
+        self.load_save_states(game_state);
+
+        let current_level_index = game_state.current_level_index;
+        let Some(level_pack) = game_state.get_current_level_pack() else {
+            return;
+        };
+
+        let Some(save_state) = self.save_states[slot].clone() else {
+            game_state.play_sound_effect_ui_error();
+
+            return;
+        };
+
+        let level_original = level_pack.levels()[current_level_index].level();
+        match Self::restore_playing_state(level_original, save_state.state()) {
+            Ok(state) => {
+                self.level.as_mut().unwrap().commit_change(state);
+                self.set_time(save_state.time());
+
+                //Loading a state goes back in the level like an undo
+                self.undo_count += 1;
+
+                game_state.play_sound_event(SoundEvent::Undo);
+            },
+            Err(err) => {
+                game_state.open_dialog(Box::new(DialogOk::new_error(format!("Cannot load state: {}", err))));
             },
         }
     }
 
-    fn on_key_pressed(&mut self, game_state: &mut GameState, key: Key) {
-        if key == Key::ESC {
-            game_state.play_sound_effect(audio::UI_SELECT_EFFECT);
+    fn open_save_state_slots(&mut self, game_state: &mut GameState) {
+        self.time_start_in_menu = Some(SystemTime::now());
+        self.is_selecting_save_state_slot = true;
 
-            game_state.set_screen(ScreenId::SelectLevelPack);
+        self.load_save_states(game_state);
+    }
 
-            return;
+    fn close_save_state_slots(&mut self, game_state: &mut GameState) {
+        self.is_selecting_save_state_slot = false;
+
+        self.on_continue(game_state);
+    }
+
+    fn on_key_pressed_save_state_slots(&mut self, game_state: &mut GameState, key: Key) {
+        //S and ENTER have priority over the movement keys (S is bound to moving down with WASD)
+        let key = if key == Key::S || key == Key::ENTER {
+            key
+        }else {
+            game_state.menu_key(key)
+        };
+
+        match key {
+            Key::ESC | Key::TAB => {
+                self.close_save_state_slots(game_state);
+
+                game_state.play_sound_effect_ui_select();
+            },
+
+            Key::LEFT => {
+                self.selected_save_state_slot = if self.selected_save_state_slot == 0 {
+                    suspend::SAVE_STATE_SLOT_COUNT - 1
+                }else {
+                    self.selected_save_state_slot - 1
+                };
+            },
+            Key::RIGHT => {
+                self.selected_save_state_slot = (self.selected_save_state_slot + 1) % suspend::SAVE_STATE_SLOT_COUNT;
+            },
+            key if key.is_numeric() => {
+                let slot = key.to_ascii().unwrap() - b'0';
+                if slot >= 1 && slot as usize <= suspend::SAVE_STATE_SLOT_COUNT {
+                    self.selected_save_state_slot = slot as usize - 1;
+                }
+            },
+
+            Key::S => {
+                self.save_state(game_state, self.selected_save_state_slot);
+            },
+            Key::ENTER => {
+                self.close_save_state_slots(game_state);
+
+                self.load_state(game_state, self.selected_save_state_slot);
+            },
+
+            _ => {},
         }
+    }
 
-        if key == Key::F1 {
-            game_state.open_help_page();
+    fn draw_save_state_slots(&self, game_state: &GameState, console: &Console) {
+        const SLOT_WIDTH: usize = 18;
+        const MINIATURE_WIDTH: usize = SLOT_WIDTH - 2;
+        const MINIATURE_HEIGHT: usize = 8;
 
+        let Some(level_original) = game_state.get_current_level_pack().
+                and_then(|level_pack| level_pack.levels().get(game_state.current_level_index)).
+                map(|level| level.level()) else {
             return;
+        };
+
+        console.reset_color();
+        for y in 4..19 {
+            console.set_cursor_pos(0, y);
+            console.draw_text(" ".repeat(Game::CONSOLE_MIN_WIDTH));
         }
 
-        'outer: {
-            match key {
-                Key::LEFT => {
-                    if self.selected_level == 0 {
-                        break 'outer;
-                    }
+        console.set_cursor_pos(2, 4);
+        console.set_underline(true);
+        console.draw_text("Save states");
+        console.set_underline(false);
+        console.draw_text(" (1-4: Select, s: Save, ENTER: Load, ESC: Close)");
 
-                    self.selected_level -= 1;
-                },
-                Key::UP => {
-                    if self.selected_level < 24 {
-                        break 'outer;
-                    }
+        for (i, save_state) in self.save_states.iter().
+                enumerate() {
+            let x = 1 + i * SLOT_WIDTH;
 
-                    self.selected_level -= 24;
-                },
-                Key::RIGHT => {
-                    if self.selected_level + 1 >= game_state.get_current_level_pack().
-                            as_ref().unwrap().level_count() {
-                        break 'outer;
-                    }
+            console.set_cursor_pos(x + 1, 6);
+            if i == self.selected_save_state_slot {
+                console.set_color(Color::Black, Color::Yellow);
+            }
+            console.draw_text(format!(" Slot {} ", i + 1));
+            console.reset_color();
 
-                    self.selected_level += 1;
-                },
-                Key::DOWN => {
-                    if self.selected_level + 24 >= game_state.get_current_level_pack().
-                            as_ref().unwrap().level_count() {
-                        break 'outer;
-                    }
+            let Some(save_state) = save_state else {
+                console.set_cursor_pos(x + 1, 7);
+                console.set_color(Color::LightBlack, Color::Default);
+                console.draw_text("Empty");
+                console.reset_color();
 
-                    self.selected_level += 24;
-                },
+                continue;
+            };
 
-                Key::ENTER => {
-                    if self.selected_level <= game_state.get_current_level_pack().
-                            as_ref().unwrap().min_level_not_completed() {
-                        game_state.play_sound_effect_ui_select();
+            console.set_cursor_pos(x + 1, 7);
+            console.draw_text(format!("Moves: {:04}", save_state.state().moves()));
 
-                        game_state.set_level_index(self.selected_level);
-                        game_state.set_screen(ScreenId::InGame);
-                    }else {
-                        game_state.play_sound_effect_ui_error();
-                    }
-                },
+            console.set_cursor_pos(x + 1, 8);
+            console.draw_text(format!(
+                "Time: {:02}:{:02}.{:03}",
+                save_state.time() / 60000 % 60,
+                save_state.time() / 1000 % 60,
+                save_state.time() % 1000,
+            ));
 
-                _ => {},
+            if let Ok(level) = save_state.state().to_level(level_original) {
+                level.draw_miniature(console, x + 1, 10, MINIATURE_WIDTH, MINIATURE_HEIGHT, game_state.settings().color_theme());
             }
         }
     }
 
-    fn on_mouse_pressed(&mut self, game_state: &mut GameState, column: usize, row: usize) {
-        if row == 0 {
+    fn record_attempt(&self, game_state: &mut GameState, result: AttemptResult) {
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).
+                map_or(0, |duration| duration.as_secs());
+
+        let level = self.level.as_ref().unwrap();
+        let attempt = Attempt::new(
+            timestamp,
+            self.current_time(),
+            level.current().moves,
+            level.current().pushes,
+            self.undo_count,
+            self.reset_count,
+            result,
+        );
+
+        let current_level_index = game_state.current_level_index;
+        let Some(level_pack) = game_state.get_current_level_pack() else {
             return;
-        }
+        };
 
-        let level_index = column/3 + (row - 1)/2*24;
-        if level_index < game_state.get_current_level_pack().as_ref().unwrap().level_count() {
-            self.selected_level = level_index;
-            self.on_key_pressed(game_state, Key::ENTER);
+        if let Err(err) = level_pack.append_attempt(current_level_index, &attempt) {
+            game_state.open_dialog(Box::new(DialogOk::new_error(format!("Cannot save history: {}", err))));
         }
+
+        game_state.on_achievement_event(AchievementEvent::Played { time: attempt.time() });
     }
 
-    fn on_set_screen(&mut self, game_state: &mut GameState) {
-        self.selected_level = game_state.get_level_index();
+    fn suspend_level(&self, game_state: &mut GameState) -> Result<(), Box<dyn Error>> {
+        let Some(level_pack) = game_state.get_current_level_pack() else {
+            return Ok(());
+        };
+        let level_original = level_pack.levels()[game_state.current_level_index].level();
+
+        let level = self.level.as_ref().unwrap();
+        let mut states = Vec::new();
+        level.for_each_in_branch(|state| {
+            states.push(SuspendedState::new(level_original, &state.level, state.player_pos, state.moves, state.pushes));
+        });
+
+        let suspended_level = SuspendedLevel::new(
+            level_pack.id(),
+            game_state.current_level_index,
+            level_original.content_hash(),
+            self.current_time(),
+            self.undo_count,
+            self.reset_count,
+            states,
+            level.current_index(),
+        );
+
+        game_state.suspend_level(suspended_level)
     }
-}
 
-pub struct ScreenInGame {
-    time_start_in_menu: Option<SystemTime>,
-    time_start: Option<SystemTime>,
-    time_millis: u32,
-    time_sec: u32,
-    time_min: u32,
+    //Restores the undo history, the stats and the timer of the suspended level
+    fn resume_level(&mut self, level_original: &Level, suspended_level: &SuspendedLevel) -> Result<(), Box<dyn Error>> {
+        let mut states = suspended_level.states().iter().
+                map(|state| Self::restore_playing_state(level_original, state));
 
-    level: Option<UndoHistory<(Level, (usize, usize))>>,
+        let Some(initial_state) = states.next() else {
+            return Err(Box::new(SuspendError::new("The suspended level contains no state")));
+        };
 
-    continue_flag: bool,
-    secret_found_flag: bool,
-    game_over_flag: bool,
-}
+        let mut level = UndoTree::with_storage(Self::UNDO_HISTORY_SIZE_PLAYING, initial_state?);
+        for state in states {
+            level.commit_change(state?);
+        }
 
-impl ScreenInGame {
-    pub const UNDO_HISTORY_SIZE_PLAYING: usize = 10000;
+        while level.current_index() > suspended_level.current_state_index() {
+            level.undo();
+        }
 
-    pub fn new() -> Self {
-        Self {
-            time_start_in_menu: Default::default(),
-            time_start: Default::default(),
-            time_millis: Default::default(),
-            time_sec: Default::default(),
-            time_min: Default::default(),
+        self.start_level(level_original);
 
-            level: Default::default(),
+        self.level = Some(level);
+        self.undo_count = suspended_level.undo_count();
+        self.reset_count = suspended_level.reset_count();
 
-            continue_flag: Default::default(),
-            secret_found_flag: Default::default(),
-            game_over_flag: Default::default(),
+        self.set_time(suspended_level.time());
+
+        Ok(())
+    }
+
+    fn leave_level(&mut self, game_state: &mut GameState) {
+        //The attempt is continued if the suspended level is resumed
+        if !self.continue_flag && !self.game_over_flag && self.is_attempt_started() &&
+                let Err(err) = self.suspend_level(game_state) {
+            self.record_attempt(game_state, AttemptResult::Abandoned);
+
+            game_state.open_dialog(Box::new(DialogOk::new_error(format!("Cannot suspend level: {}", err))));
+        }
+
+        self.continue_flag = false;
+        self.game_over_flag = false;
+
+        game_state.set_screen(ScreenId::SelectLevel);
+    }
+
+    //The initial state is committed as a new undo step, so the reset can be undone (The timer keeps running)
+    fn reset_level(&mut self, game_state: &mut GameState) {
+        let Some(level_pack) = game_state.get_current_level_pack() else {
+            return;
+        };
+
+        let level = self.level.as_mut().unwrap();
+        if level.current().moves == 0 {
+            return;
+        }
+
+        let level_original = level_pack.levels()[game_state.current_level_index].level().clone();
+        let player_pos = Self::find_player_pos(&level_original);
+        level.commit_change(PlayingState {
+            level: level_original,
+            player_pos,
+            moves: 0,
+            pushes: 0,
+        });
+
+        self.reset_count += 1;
+
+        game_state.play_sound_event(SoundEvent::Reset);
+    }
+
+    fn find_player_pos(level: &Level) -> (usize, usize) {
+        for i in 0..level.width() {
+            for j in 0..level.height() {
+                if let Some(tile) = level.get_tile(i, j) && *tile == Tile::Player {
+                    return (i, j);
+                }
+            }
         }
+
+        panic!("Level contains no player");
     }
 
     pub fn start_level(&mut self, level: &Level) {
@@ -614,21 +2024,16 @@ impl ScreenInGame {
 
         self.continue_flag = false;
         self.game_over_flag = false;
+        self.is_selecting_save_state_slot = false;
 
-        let mut player_pos = None;
-
-        'outer:
-        for i in 0..level.width() {
-            for j in 0..level.height() {
-                if let Some(tile) = level.get_tile(i, j) && *tile == Tile::Player {
-                    player_pos = Some((i, j));
-
-                    break 'outer;
-                }
-            }
-        }
+        let player_pos = Self::find_player_pos(&level);
 
-        self.level = Some(UndoHistory::new(Self::UNDO_HISTORY_SIZE_PLAYING, (level, player_pos.unwrap())));
+        self.level = Some(UndoTree::with_storage(Self::UNDO_HISTORY_SIZE_PLAYING, PlayingState {
+            level,
+            player_pos,
+            moves: 0,
+            pushes: 0,
+        }));
     }
 
     fn draw_tutorial_level_text(&self, game_state: &GameState, console: &Console) {
@@ -863,7 +2268,7 @@ impl Screen for ScreenInGame {
         console.draw_text(utils::number_to_string_leading_ascii(2, game_state.current_level_index as u32 + 1, true));
 
         console.set_cursor_pos(((Game::CONSOLE_MIN_WIDTH - 11) as f64 * 0.75) as usize, 0);
-        console.draw_text(format!("Moves: {:04}", self.level.as_ref().unwrap().current_index()));
+        console.draw_text(format!("Moves: {:04}", self.level.as_ref().unwrap().current().moves));
 
         console.set_cursor_pos(Game::CONSOLE_MIN_WIDTH - 15, 0);
         console.draw_text(format!(
@@ -876,6 +2281,15 @@ impl Screen for ScreenInGame {
         if self.continue_flag {
             console.set_cursor_pos(((Game::CONSOLE_MIN_WIDTH - 16) as f64 * 0.5) as usize, 0);
             console.draw_text("Level completed!");
+        }else if !self.game_over_flag && game_state.settings().is_deadlock_warning_enabled() &&
+                self.level.as_ref().unwrap().current().level.has_deadlock() {
+            console.set_cursor_pos(((Game::CONSOLE_MIN_WIDTH - 9) as f64 * 0.5) as usize, 0);
+            console.set_color(Color::LightRed, Color::Default);
+            console.draw_text("Deadlock!");
+            console.reset_color();
+        }else if !self.game_over_flag && let Some((branch_index, branch_count)) = self.level.as_ref().unwrap().branch_position() {
+            console.set_cursor_pos(((Game::CONSOLE_MIN_WIDTH - 13) as f64 * 0.5) as usize, 0);
+            console.draw_text(format!("Branch: {}/{}", branch_index + 1, branch_count));
         }
 
         if self.game_over_flag {
@@ -888,18 +2302,22 @@ impl Screen for ScreenInGame {
             }
         }
 
-        if let Some(level) = self.level.as_ref().map(|level| &level.current().0) {
+        if let Some(level) = self.level.as_ref().map(|level| &level.current().level) {
             let x_offset = ((Game::CONSOLE_MIN_WIDTH - level.width()) as f64 * 0.5) as usize;
             let y_offset = 1;
 
-            level.draw(console, x_offset, y_offset, game_state.is_player_background(), None);
+            level.draw(console, x_offset, y_offset, game_state.settings().color_theme(), game_state.is_player_background(), None);
 
             self.draw_tutorial_level_text(game_state, console);
         }
+
+        if self.is_selecting_save_state_slot {
+            self.draw_save_state_slots(game_state, console);
+        }
     }
 
     fn update(&mut self, game_state: &mut GameState) {
-        if game_state.is_dialog_opened() || self.game_over_flag || self.continue_flag {
+        if game_state.is_dialog_opened() || self.game_over_flag || self.continue_flag || self.is_selecting_save_state_slot {
             return;
         }
 
@@ -923,18 +2341,32 @@ impl Screen for ScreenInGame {
     }
 
     fn on_key_pressed(&mut self, game_state: &mut GameState, key: Key) {
-        if key == Key::ESC {
+        if self.is_selecting_save_state_slot {
+            self.on_key_pressed_save_state_slots(game_state, key);
+
+            return;
+        }
+
+        let action = game_state.key_action(key);
+
+        if action == Some(Action::Back) {
             if self.game_over_flag {
                 self.continue_flag = false;
                 self.game_over_flag = false;
 
-                game_state.play_sound_effect(audio::UI_SELECT_EFFECT);
+                game_state.play_sound_event(SoundEvent::UiSelect);
 
                 game_state.set_screen(ScreenId::SelectLevel);
 
                 return;
             }
 
+            if !game_state.settings().is_confirmation_enabled() {
+                self.leave_level(game_state);
+
+                return;
+            }
+
             self.time_start_in_menu = Some(SystemTime::now());
 
             game_state.open_dialog(Box::new(DialogYesNo::new("Back to level selection?")));
@@ -942,7 +2374,7 @@ impl Screen for ScreenInGame {
             return;
         }
 
-        if key == Key::F1 {
+        if action == Some(Action::Help) {
             self.time_start_in_menu = Some(SystemTime::now());
 
             game_state.open_help_page();
@@ -950,6 +2382,16 @@ impl Screen for ScreenInGame {
             return;
         }
 
+        if action == Some(Action::Settings) {
+            self.time_start_in_menu = Some(SystemTime::now());
+
+            game_state.play_sound_effect_ui_select();
+
+            game_state.open_settings();
+
+            return;
+        }
+
         let current_level_index = game_state.current_level_index;
         let Some(level_pack) = game_state.get_current_level_pack_mut() else {
             return;
@@ -964,50 +2406,91 @@ impl Screen for ScreenInGame {
                 if current_level_index + 1 == level_pack.level_count() {
                     self.game_over_flag = true;
 
-                    game_state.play_sound_effect(audio::LEVEL_PACK_COMPLETE_EFFECT);
+                    game_state.play_sound_event(SoundEvent::PackComplete);
 
                     return;
                 }else {
                     game_state.current_level_index += 1;
                 }
 
-                self.start_level(game_state.get_current_level_pack().unwrap().levels()[game_state.current_level_index].level());
-            }else if key == Key::R {
-                self.start_level(level_pack.levels()[current_level_index].level());
+                self.start_attempt(game_state.get_current_level_pack().unwrap().levels()[game_state.current_level_index].level());
+            }else if action == Some(Action::Reset) {
+                self.start_attempt(level_pack.levels()[current_level_index].level());
 
-                game_state.play_sound_effect(audio::LEVEL_RESET);
+                game_state.play_sound_event(SoundEvent::Reset);
             }
 
             return;
         }
 
-        if key == Key::Z {
+        if action == Some(Action::SaveStateSlots) {
+            self.open_save_state_slots(game_state);
+
+            game_state.play_sound_effect_ui_select();
+
+            return;
+        }else if action == Some(Action::SaveState) {
+            self.save_state(game_state, self.selected_save_state_slot);
+
+            return;
+        }else if action == Some(Action::LoadState) {
+            self.load_state(game_state, self.selected_save_state_slot);
+
+            return;
+        }
+
+        if action == Some(Action::Undo) {
             let level = self.level.as_mut().unwrap().undo();
             if level.is_some() {
-                game_state.play_sound_effect(audio::UNDO_REDO_EFFECT);
+                self.undo_count += 1;
+
+                game_state.play_sound_event(SoundEvent::Undo);
             }
 
             return;
-        }else if key == Key::Y {
+        }else if action == Some(Action::Redo) {
             let level = self.level.as_mut().unwrap().redo();
             if level.is_some() {
-                game_state.play_sound_effect(audio::UNDO_REDO_EFFECT);
+                game_state.play_sound_event(SoundEvent::Undo);
+            }
+
+            return;
+        }else if let Some(action @ (Action::PreviousBranch | Action::NextBranch | Action::LatestBranch)) = action {
+            let level = self.level.as_mut().unwrap();
+            let level = match action {
+                Action::PreviousBranch => level.previous_branch(),
+                Action::NextBranch => level.next_branch(),
+                _ => level.latest_branch_tip(),
+            };
+
+            //Switching branches goes back in the level like an undo
+            if level.is_some() {
+                self.undo_count += 1;
+
+                game_state.play_sound_event(SoundEvent::Undo);
             }
 
             return;
         }
 
         //Reset
-        if key == Key::R {
-            self.start_level(level_pack.levels()[current_level_index].level());
+        if action == Some(Action::Reset) {
+            let moves = self.level.as_ref().unwrap().current().moves;
+            if moves > Self::RESET_CONFIRMATION_MOVES && game_state.settings().is_confirmation_enabled() {
+                self.time_start_in_menu = Some(SystemTime::now());
+                self.is_confirming_reset = true;
 
-            game_state.play_sound_effect(audio::LEVEL_RESET);
+                game_state.open_dialog(Box::new(DialogYesNo::new(format!("Reset level after {} moves?", moves))));
+            }else {
+                self.reset_level(game_state);
+            }
 
             return;
         }
 
-        if key.is_arrow_key() {
-            let (mut level, mut player_pos) = self.level.as_ref().unwrap().current().clone();
+        //Movement keys are mapped to the arrow key of their direction
+        if let Some(key) = action.and_then(|action| action.direction_key()) {
+            let PlayingState { mut level, mut player_pos, moves, mut pushes } = self.level.as_ref().unwrap().current().clone();
 
             let width = level.width();
             let height = level.height();
@@ -1081,18 +2564,30 @@ impl Screen for ScreenInGame {
 
             let has_player_moved = player_pos != (x_from, y_from);
             if has_player_moved {
-                self.level.as_mut().unwrap().commit_change((level, player_pos));
+                if matches!(tile, Tile::Box | Tile::BoxInGoal | Tile::Key | Tile::KeyInGoal) {
+                    pushes += 1;
+                }
+
+                self.level.as_mut().unwrap().commit_change(PlayingState {
+                    level,
+                    player_pos,
+                    moves: moves + 1,
+                    pushes,
+                });
             }
 
             if has_won {
                 self.continue_flag = true;
 
                 //Update best scores
-                let time = self.time_millis as u64 + 1000 * self.time_sec as u64 + 60000 * self.time_min as u64;
-                let moves = self.level.as_ref().unwrap().current_index() as u32;
+                let time = self.current_time();
+                let moves = self.level.as_ref().unwrap().current().moves;
 
+                let par_moves = level_pack.levels()[current_level_index].par_moves();
                 level_pack.update_stats(current_level_index, time, moves);
 
+                let is_level_pack_cleared = level_pack.level_pack_best_moves_sum().is_some();
+
                 if current_level_index >= level_pack.min_level_not_completed() {
                     level_pack.set_min_level_not_completed(current_level_index + 1);
                 }
@@ -1101,16 +2596,30 @@ impl Screen for ScreenInGame {
                     game_state.open_dialog(Box::new(DialogOk::new_error(format!("Cannot save: {}", err))));
                 }
 
-                game_state.play_sound_effect(audio::LEVEL_COMPLETE_EFFECT);
+                self.record_attempt(game_state, AttemptResult::Completed);
+
+                game_state.on_achievement_event(AchievementEvent::LevelCompleted {
+                    moves,
+                    undo_count: self.undo_count,
+                    par_moves,
+                });
+                if is_level_pack_cleared {
+                    game_state.on_achievement_event(AchievementEvent::LevelPackCleared);
+                }
+
+                game_state.play_sound_event(SoundEvent::LevelComplete);
             }
 
-            if has_player_moved {
-                game_state.play_sound_effect(audio::STEP_EFFECT);
-            }else {
-                game_state.play_sound_effect(audio::NO_PATH_EFFECT);
+            //The jingle of the completed level must not be overlapped by the sound of the winning step
+            if has_player_moved && !has_won {
+                game_state.play_sound_event_at(SoundEvent::Step, player_pos.0, width);
+            }else if !has_player_moved {
+                game_state.play_sound_event_at(SoundEvent::Blocked, player_pos.0, width);
             }
 
             if self.secret_found_flag {
+                self.record_attempt(game_state, AttemptResult::Abandoned);
+
                 game_state.open_dialog(Box::new(DialogOk::new_secret_found("You have found a secret!")));
 
                 if let Err(err) = game_state.on_found_secret() {
@@ -1121,6 +2630,17 @@ impl Screen for ScreenInGame {
     }
 
     fn on_dialog_selection(&mut self, game_state: &mut GameState, selection: DialogSelection) {
+        if self.is_confirming_reset {
+            self.is_confirming_reset = false;
+
+            self.on_continue(game_state);
+            if selection == DialogSelection::Yes {
+                self.reset_level(game_state);
+            }
+
+            return;
+        }
+
         if self.secret_found_flag {
             self.continue_flag = false;
             self.game_over_flag = false;
@@ -1132,10 +2652,7 @@ impl Screen for ScreenInGame {
         }
 
         if selection == DialogSelection::Yes {
-            self.continue_flag = false;
-            self.game_over_flag = false;
-
-            game_state.set_screen(ScreenId::SelectLevel);
+            self.leave_level(game_state);
         }else if selection == DialogSelection::No {
             self.on_continue(game_state);
         }
@@ -1153,13 +2670,39 @@ impl Screen for ScreenInGame {
     }
 
     fn on_set_screen(&mut self, game_state: &mut GameState) {
-        self.start_level(game_state.get_current_level_pack().as_ref().unwrap().levels().get(
-            game_state.get_level_index()).unwrap().level());
+        let level = game_state.get_current_level_pack().as_ref().unwrap().levels().get(
+            game_state.get_level_index()).unwrap().level().clone();
+
+        match game_state.take_resuming_suspended_level() {
+            Ok(Some(suspended_level)) => {
+                if let Err(err) = self.resume_level(&level, &suspended_level) {
+                    self.start_attempt(&level);
+
+                    game_state.open_dialog(Box::new(DialogOk::new_error(format!("Cannot resume level: {}", err))));
+                }
+            },
+
+            Ok(None) => {
+                //Starting a new level abandons the suspended level
+                if let Err(err) = game_state.abandon_suspended_level() {
+                    game_state.open_dialog(Box::new(DialogOk::new_error(format!("Cannot save history: {}", err))));
+                }
+
+                self.start_attempt(&level);
+            },
+
+            Err(err) => {
+                self.start_attempt(&level);
+
+                game_state.open_dialog(Box::new(DialogOk::new_error(format!("Cannot resume level: {}", err))));
+            },
+        }
     }
 }
 
 pub struct ScreenSelectLevelPackEditor {
     is_exporting_level_pack: bool,
+    is_exporting_level_pack_to_xsb: bool,
     is_deleting_level_pack: bool,
 
     is_creating_new_level_pack: bool,
@@ -1170,6 +2713,7 @@ impl ScreenSelectLevelPackEditor {
     pub fn new() -> Self {
         Self {
             is_exporting_level_pack: Default::default(),
+            is_exporting_level_pack_to_xsb: Default::default(),
             is_deleting_level_pack: Default::default(),
 
             is_creating_new_level_pack: Default::default(),
@@ -1185,10 +2729,10 @@ impl Screen for ScreenSelectLevelPackEditor {
         console.draw_text("Edit a level pack:");
         console.set_underline(false);
 
-        let has_max_level_pack_count = game_state.editor_state.get_level_pack_count() == LevelPack::MAX_LEVEL_PACK_COUNT;
+        let has_max_level_pack_count = game_state.editor_state.get_level_pack_entry_count() >= LevelPack::MAX_LEVEL_PACK_COUNT;
 
         //Include Create Level Pack entry
-        let entry_count = game_state.editor_state.get_level_pack_count() + 1;
+        let entry_count = game_state.editor_state.get_level_pack_entry_count() + 1;
 
         //Draw first line
         console.set_cursor_pos(0, 1);
@@ -1219,7 +2763,7 @@ impl Screen for ScreenSelectLevelPackEditor {
             }
 
             console.set_cursor_pos(x, y);
-            if i == game_state.editor_state.get_level_pack_count() {
+            if i == game_state.editor_state.get_level_pack_entry_count() {
                 //Level Pack Editor entry
                 if has_max_level_pack_count {
                     console.set_color(Color::White, Color::LightRed);
@@ -1227,6 +2771,10 @@ impl Screen for ScreenSelectLevelPackEditor {
                     console.set_color(Color::White, Color::LightBlue);
                 }
                 console.draw_text(" +");
+            }else if i >= game_state.editor_state.get_level_pack_count() {
+                //Broken level pack entry
+                console.set_color(Color::White, Color::Red);
+                console.draw_text(format!("{:2}", i + 1));
             }else {
                 console.set_color(Color::Black, Color::Green);
                 console.draw_text(format!("{:2}", i + 1));
@@ -1274,7 +2822,7 @@ impl Screen for ScreenSelectLevelPackEditor {
             console.set_cursor_pos(1, y + 2);
             console.set_color(Color::Cyan, Color::Default);
             console.draw_text(format!("> {}", &self.new_level_pack_id));
-        }else if game_state.editor_state.get_level_pack_index() == game_state.editor_state.get_level_pack_count() {
+        }else if game_state.editor_state.get_level_pack_index() == game_state.editor_state.get_level_pack_entry_count() {
             //Level Pack Editor entry
             if has_max_level_pack_count {
                 let error_msg = format!(
@@ -1287,9 +2835,25 @@ impl Screen for ScreenSelectLevelPackEditor {
                 console.set_color(Color::LightRed, Color::Default);
                 console.draw_text(error_msg);
             }else {
-                console.set_cursor_pos(29, y + 2);
-                console.draw_text("Create level pack");
+                console.set_cursor_pos(29, y + 2);
+                console.draw_text("Create level pack");
+            }
+        }else if let Some(broken_level_pack) = game_state.editor_state.get_current_broken_level_pack() {
+            console.set_cursor_pos(1, y + 1);
+            console.set_color(Color::LightRed, Color::Default);
+            console.draw_text(format!("Broken Level Pack ID: {}", broken_level_pack.id()));
+            console.reset_color();
+
+            let error = broken_level_pack.error().lines().next().unwrap_or_default();
+            console.set_cursor_pos(1, y + 2);
+            if error.chars().count() > Game::CONSOLE_MIN_WIDTH - 2 {
+                console.draw_text(error.chars().take(Game::CONSOLE_MIN_WIDTH - 5).collect::<String>() + "...");
+            }else {
+                console.draw_text(error);
             }
+
+            console.set_cursor_pos(1, y + 3);
+            console.draw_text("ENTER: Repair, DELETE: Delete");
         }else {
             console.set_cursor_pos(1, y + 1);
             console.draw_text(format!("Level Pack ID: {}", game_state.editor_state.get_current_level_pack().unwrap().id()));
@@ -1301,7 +2865,7 @@ impl Screen for ScreenSelectLevelPackEditor {
 
     fn on_key_pressed(&mut self, game_state: &mut GameState, key: Key) {
         if self.is_creating_new_level_pack {
-            match key {
+            match game_state.text_input_menu_key(key) {
                 key if key.is_ascii() && (key.is_alphanumeric() || key == Key::UNDERSCORE || key == Key::MINUS) => {
                     if self.new_level_pack_id.len() >= Game::MAX_LEVEL_PACK_ID_LEN {
                         return;
@@ -1320,22 +2884,21 @@ impl Screen for ScreenSelectLevelPackEditor {
                         return;
                     }
 
-                    for id in game_state.editor_state.level_packs.iter().
-                            map(|level_pack| level_pack.id()) {
-                        if id == self.new_level_pack_id {
-                            game_state.open_dialog(Box::new(DialogOk::new_error(format!("The level pack with the ID \"{}\" already exists!", id))));
+                    if game_state.editor_state.has_level_pack_id(&self.new_level_pack_id) {
+                        game_state.open_dialog(Box::new(DialogOk::new_error(format!(
+                            "The level pack with the ID \"{}\" already exists!",
+                            self.new_level_pack_id,
+                        ))));
 
-                            return;
-                        }
+                        return;
                     }
 
-                    let Ok(mut save_game_file) = Game::get_or_create_save_game_folder() else {
+                    let Ok(editor_level_pack_folder) = Game::get_or_create_editor_level_pack_folder() else {
                         game_state.open_dialog(Box::new(DialogOk::new_error("Cannot save!")));
 
                         return;
                     };
-                    save_game_file.push(&self.new_level_pack_id);
-                    save_game_file.push(".lvl.edit");
+                    let save_game_file = editor_level_pack_folder.join(format!("{}.lvl.edit", self.new_level_pack_id));
 
                     let Some(save_game_file) = save_game_file.to_str() else {
                         game_state.open_dialog(Box::new(DialogOk::new_error("Cannot save!")));
@@ -1357,6 +2920,8 @@ impl Screen for ScreenSelectLevelPackEditor {
 
                     game_state.editor_state.level_packs.insert(index, level_pack);
 
+                    game_state.on_achievement_event(AchievementEvent::EditorLevelPackCreated);
+
                     self.is_creating_new_level_pack = false;
                     self.new_level_pack_id = String::new();
 
@@ -1366,7 +2931,7 @@ impl Screen for ScreenSelectLevelPackEditor {
                 },
 
                 Key::ESC => {
-                    game_state.play_sound_effect(audio::UI_SELECT_EFFECT);
+                    game_state.play_sound_event(SoundEvent::UiSelect);
 
                     self.is_creating_new_level_pack = false;
                     self.new_level_pack_id = String::new();
@@ -1378,8 +2943,10 @@ impl Screen for ScreenSelectLevelPackEditor {
             return;
         }
 
+        let key = game_state.menu_key(key);
+
         if key == Key::ESC {
-            game_state.play_sound_effect(audio::UI_SELECT_EFFECT);
+            game_state.play_sound_event(SoundEvent::UiSelect);
 
             game_state.set_screen(ScreenId::SelectLevelPack);
 
@@ -1392,24 +2959,36 @@ impl Screen for ScreenSelectLevelPackEditor {
             return;
         }
 
-        if key == Key::E && game_state.editor_state.selected_level_pack_index != game_state.editor_state.get_level_pack_count() {
+        if key == Key::E && game_state.editor_state.selected_level_pack_index < game_state.editor_state.get_level_pack_count() {
             self.is_exporting_level_pack = true;
 
             game_state.open_dialog(Box::new(DialogYesNo::new("Do you want to export the level pack to the current directory?")));
         }
 
-        if key == Key::DELETE && game_state.editor_state.selected_level_pack_index != game_state.editor_state.get_level_pack_count() {
+        if key == Key::X && game_state.editor_state.selected_level_pack_index < game_state.editor_state.get_level_pack_count() {
+            self.is_exporting_level_pack_to_xsb = true;
+
+            game_state.open_dialog(Box::new(DialogYesNo::new("Do you want to export the level pack as XSB to the current directory?")));
+        }
+
+        if key == Key::DELETE && game_state.editor_state.selected_level_pack_index != game_state.editor_state.get_level_pack_entry_count() {
             self.is_deleting_level_pack = true;
 
+            let level_pack_id = game_state.editor_state.get_current_level_pack().
+                    map(|level_pack| level_pack.id()).
+                    or_else(|| game_state.editor_state.get_current_broken_level_pack().
+                            map(|level_pack| level_pack.id())).
+                    unwrap();
+
             game_state.open_dialog(Box::new(DialogYesNo::new(format!(
                 "Do you really want to delete level pack \"{}\"?",
-                game_state.editor_state.get_current_level_pack().unwrap().id(),
+                level_pack_id,
             ))));
         }
 
         'outer: {
             //Include Level Pack Editor entry
-            let entry_count = game_state.editor_state.get_level_pack_count() + 1;
+            let entry_count = game_state.editor_state.get_level_pack_entry_count() + 1;
 
             match key {
                 Key::LEFT => {
@@ -1442,9 +3021,9 @@ impl Screen for ScreenSelectLevelPackEditor {
                 },
 
                 Key::ENTER => {
-                    if game_state.editor_state.selected_level_pack_index == game_state.editor_state.get_level_pack_count() {
+                    if game_state.editor_state.selected_level_pack_index == game_state.editor_state.get_level_pack_entry_count() {
                         //Level Pack Editor entry
-                        if game_state.editor_state.get_level_pack_count() == LevelPack::MAX_LEVEL_PACK_COUNT {
+                        if game_state.editor_state.get_level_pack_entry_count() >= LevelPack::MAX_LEVEL_PACK_COUNT {
                             game_state.open_dialog(Box::new(DialogOk::new_error(format!(
                                 "Cannot create new level packs (Max level pack count ({}) reached)",
                                 LevelPack::MAX_LEVEL_PACK_COUNT,
@@ -1454,6 +3033,14 @@ impl Screen for ScreenSelectLevelPackEditor {
 
                             self.is_creating_new_level_pack = true;
                         }
+                    }else if let Some(broken_level_pack) = game_state.editor_state.get_current_broken_level_pack() {
+                        if broken_level_pack.lvl_data().is_none() {
+                            game_state.open_dialog(Box::new(DialogOk::new_error("Cannot repair: The level pack file could not be read")));
+                        }else {
+                            game_state.play_sound_effect_ui_select();
+
+                            game_state.set_screen(ScreenId::LevelPackRepair);
+                        }
                     }else {
                         game_state.play_sound_effect_ui_select();
 
@@ -1473,7 +3060,7 @@ impl Screen for ScreenSelectLevelPackEditor {
             return;
         }
         //Include Level Pack Editor entry
-        let entry_count = game_state.editor_state.get_level_pack_count() + 1;
+        let entry_count = game_state.editor_state.get_level_pack_entry_count() + 1;
 
         let level_pack_index = column/3 + (row - 1)/2*24;
         if level_pack_index < entry_count {
@@ -1505,15 +3092,41 @@ impl Screen for ScreenSelectLevelPackEditor {
                     game_state.open_dialog(Box::new(DialogOk::new("The level pack was exported successfully")));
                 }
             }
+        }else if self.is_exporting_level_pack_to_xsb {
+            self.is_exporting_level_pack_to_xsb = false;
+
+            if selection == DialogSelection::Yes {
+                let level_pack = game_state.editor_state.get_current_level_pack().unwrap();
+                let path = level_pack.id().to_string() + ".xsb";
+
+                if std::fs::exists(&path).ok().is_none_or(|exists| exists) {
+                    game_state.open_dialog(Box::new(DialogOk::new_error(format!(
+                        "File \"{}\" already exists!",
+                        path,
+                    ))));
+
+                    return;
+                }
+
+                if let Err(err) = level_pack.save_editor_level_pack_to_xsb_path(path) {
+                    game_state.open_dialog(Box::new(DialogOk::new_error(format!("Cannot export: {}", err))));
+                }else {
+                    game_state.open_dialog(Box::new(DialogOk::new("The level pack was exported successfully")));
+                }
+            }
         }else if self.is_deleting_level_pack {
             self.is_deleting_level_pack = false;
 
             if selection == DialogSelection::Yes {
-                let path = game_state.editor_state.get_current_level_pack().unwrap().path();
+                let path = game_state.editor_state.get_current_level_pack().
+                        map(|level_pack| level_pack.path()).
+                        or_else(|| game_state.editor_state.get_current_broken_level_pack().
+                                map(|level_pack| level_pack.path())).
+                        unwrap();
 
                 if let Err(err) = std::fs::remove_file(path) {
                     game_state.open_dialog(Box::new(DialogOk::new_error(format!("Cannot delete: {}", err))));
-                }else {
+                }else if game_state.editor_state.remove_current_broken_level_pack().is_none() {
                     let index = game_state.editor_state.selected_level_pack_index;
                     game_state.editor_state.level_packs.remove(index);
                 }
@@ -1526,6 +3139,7 @@ pub struct ScreenLevelPackEditor {
     is_creating_new_level: bool,
     is_editing_height: bool,
     is_deleting_level: bool,
+    is_exporting_level: bool,
     new_level_width_str: String,
     new_level_height_str: String,
 }
@@ -1536,6 +3150,7 @@ impl ScreenLevelPackEditor {
             is_creating_new_level: Default::default(),
             is_editing_height: Default::default(),
             is_deleting_level: Default::default(),
+            is_exporting_level: Default::default(),
             new_level_width_str: String::new(),
             new_level_height_str: String::new(),
         }
@@ -1684,7 +3299,7 @@ impl Screen for ScreenLevelPackEditor {
 
     fn on_key_pressed(&mut self, game_state: &mut GameState, key: Key) {
         if self.is_creating_new_level {
-            match key {
+            match game_state.text_input_menu_key(key) {
                 key if key.is_ascii() && key.is_numeric() => {
                     if self.is_editing_height {
                         if self.new_level_height_str.len() >= 2 {
@@ -1768,7 +3383,7 @@ impl Screen for ScreenLevelPackEditor {
                 },
 
                 Key::ESC => {
-                    game_state.play_sound_effect(audio::UI_SELECT_EFFECT);
+                    game_state.play_sound_event(SoundEvent::UiSelect);
 
                     self.is_creating_new_level = false;
                     self.is_editing_height = false;
@@ -1782,8 +3397,11 @@ impl Screen for ScreenLevelPackEditor {
             return;
         }
 
+        //Letters are hotkeys of this screen
+        let key = game_state.text_input_menu_key(key);
+
         if key == Key::ESC {
-            game_state.play_sound_effect(audio::UI_SELECT_EFFECT);
+            game_state.play_sound_event(SoundEvent::UiSelect);
 
             game_state.set_screen(ScreenId::SelectLevelPackEditor);
 
@@ -1851,12 +3469,19 @@ impl Screen for ScreenLevelPackEditor {
                     }
                 },
 
-                Key::DELETE => {
-                    if game_state.editor_state.selected_level_index != game_state.editor_state.get_current_level_pack().unwrap().level_count() {
-                        self.is_deleting_level = true;
+                Key::DELETE if game_state.editor_state.selected_level_index != game_state.editor_state.get_current_level_pack().unwrap().level_count() => {
+                    self.is_deleting_level = true;
 
-                        game_state.open_dialog(Box::new(DialogYesNo::new(format!("Do you really want to delete level {}?", game_state.editor_state.selected_level_index + 1))));
-                    }
+                    game_state.open_dialog(Box::new(DialogYesNo::new(format!("Do you really want to delete level {}?", game_state.editor_state.selected_level_index + 1))));
+                },
+
+                Key::E if game_state.editor_state.selected_level_index != game_state.editor_state.get_current_level_pack().unwrap().level_count() => {
+                    self.is_exporting_level = true;
+
+                    game_state.open_dialog(Box::new(DialogYesNo::new(format!(
+                        "Do you want to export level {} as RLE string to the current directory?",
+                        game_state.editor_state.selected_level_index + 1,
+                    ))));
                 },
 
                 _ => {},
@@ -1890,15 +3515,379 @@ impl Screen for ScreenLevelPackEditor {
                     game_state.open_dialog(Box::new(DialogOk::new_error(format!("Cannot save: {}", err))));
                 }
             }
+        }else if self.is_exporting_level {
+            self.is_exporting_level = false;
+
+            if selection == DialogSelection::Yes {
+                let path = format!(
+                    "{}-{}.rle",
+                    game_state.editor_state.get_current_level_pack().unwrap().id(),
+                    game_state.editor_state.selected_level_index + 1,
+                );
+
+                if std::fs::exists(&path).ok().is_none_or(|exists| exists) {
+                    game_state.open_dialog(Box::new(DialogOk::new_error(format!(
+                        "File \"{}\" already exists!",
+                        path,
+                    ))));
+
+                    return;
+                }
+
+                let rle = match game_state.editor_state.get_current_level().unwrap().to_rle_str() {
+                    Ok(rle) => rle,
+                    Err(err) => {
+                        game_state.open_dialog(Box::new(DialogOk::new_error(format!("Cannot export: {}", err))));
+
+                        return;
+                    },
+                };
+
+                if let Err(err) = std::fs::write(&path, rle + "\n") {
+                    game_state.open_dialog(Box::new(DialogOk::new_error(format!("Cannot export: {}", err))));
+                }else {
+                    game_state.open_dialog(Box::new(DialogOk::new(format!("The level was exported to \"{}\"", path))));
+                }
+            }
+        }
+    }
+}
+
+pub struct ScreenLevelPackRepair {
+    lines: Vec<Vec<char>>,
+    errors: Vec<LevelLoadingError>,
+    has_changes: bool,
+
+    cursor_pos: (usize, usize),
+    scroll_pos: (usize, usize),
+}
+
+impl ScreenLevelPackRepair {
+    const TEXT_HEIGHT: usize = Game::CONSOLE_MIN_HEIGHT - 4;
+
+    pub fn new() -> Self {
+        Self {
+            lines: Vec::new(),
+            errors: Vec::new(),
+            has_changes: Default::default(),
+
+            cursor_pos: Default::default(),
+            scroll_pos: Default::default(),
+        }
+    }
+
+    fn lvl_data(&self) -> String {
+        let mut lvl_data = String::new();
+        for line in self.lines.iter() {
+            lvl_data.extend(line.iter());
+            lvl_data.push('\n');
+        }
+
+        lvl_data
+    }
+
+    fn check_lvl_data(&mut self, game_state: &GameState) {
+        let path = game_state.editor_state.get_current_broken_level_pack().unwrap().path();
+
+        self.errors = match LevelPack::check_level_pack_data(path, &self.lvl_data(), true) {
+            Ok(_) => Vec::new(),
+            Err(err) => err.errors().to_vec(),
+        };
+    }
+
+    //Errors in the line of the cursor are shown first
+    fn current_error(&self) -> Option<&LevelLoadingError> {
+        self.errors.iter().
+                find(|error| error.line() == Some(self.cursor_pos.1 + 1)).
+                or_else(|| self.errors.first())
+    }
+
+    fn update_scroll_pos(&mut self) {
+        let (x, y) = self.cursor_pos;
+
+        if x < self.scroll_pos.0 {
+            self.scroll_pos.0 = x;
+        }else if x >= self.scroll_pos.0 + Game::CONSOLE_MIN_WIDTH {
+            self.scroll_pos.0 = x + 1 - Game::CONSOLE_MIN_WIDTH;
+        }
+
+        if y < self.scroll_pos.1 {
+            self.scroll_pos.1 = y;
+        }else if y >= self.scroll_pos.1 + Self::TEXT_HEIGHT {
+            self.scroll_pos.1 = y + 1 - Self::TEXT_HEIGHT;
+        }
+    }
+
+    fn save(&mut self, game_state: &mut GameState) {
+        let lvl_data = self.lvl_data();
+
+        let broken_level_pack = game_state.editor_state.get_current_broken_level_pack().unwrap();
+        let id = broken_level_pack.id().to_string();
+        let path = broken_level_pack.path().to_string();
+
+        if let Err(err) = std::fs::write(&path, &lvl_data) {
+            game_state.open_dialog(Box::new(DialogOk::new_error(format!("Cannot save: {}", err))));
+
+            return;
+        }
+
+        match Game::load_editor_level_pack(&id, &path, &lvl_data) {
+            Ok(level_pack) => {
+                let index = game_state.editor_state.replace_current_broken_level_pack(level_pack).unwrap();
+                game_state.editor_state.set_level_pack_index(index);
+
+                game_state.open_dialog(Box::new(DialogOk::new("The level pack was repaired successfully")));
+            },
+
+            Err(err) => {
+                game_state.editor_state.get_current_broken_level_pack_mut().unwrap().set_lvl_data(lvl_data, err.to_string());
+            },
+        }
+    }
+}
+
+impl Screen for ScreenLevelPackRepair {
+    fn draw(&self, game_state: &GameState, console: &Console) {
+        let Some(broken_level_pack) = game_state.editor_state.get_current_broken_level_pack() else {
+            return;
+        };
+
+        console.reset_color();
+        console.set_underline(true);
+        console.draw_text(format!("Repair level pack \"{}\":", broken_level_pack.id()));
+        console.set_underline(false);
+
+        let cursor_text = format!("Cursor ({:03}:{:02})", self.cursor_pos.1 + 1, self.cursor_pos.0 + 1);
+        console.set_cursor_pos(Game::CONSOLE_MIN_WIDTH - cursor_text.len(), 0);
+        console.draw_text(cursor_text);
+
+        let visible_columns = self.scroll_pos.0..self.scroll_pos.0 + Game::CONSOLE_MIN_WIDTH;
+        let visible_lines = self.scroll_pos.1..self.scroll_pos.1 + Self::TEXT_HEIGHT;
+
+        for (i, line) in self.lines.iter().
+                enumerate().
+                skip(self.scroll_pos.1).
+                take(Self::TEXT_HEIGHT) {
+            console.set_cursor_pos(0, 1 + i - self.scroll_pos.1);
+            console.draw_text(line.iter().
+                    skip(self.scroll_pos.0).
+                    take(Game::CONSOLE_MIN_WIDTH).
+                    map(|c| if c.is_control() {
+                        '?'
+                    }else {
+                        *c
+                    }).
+                    collect::<String>());
+        }
+
+        let draw_cell = |x: usize, y: usize| {
+            console.set_cursor_pos(x - self.scroll_pos.0, 1 + y - self.scroll_pos.1);
+            console.draw_text(self.lines.get(y).
+                    and_then(|line| line.get(x)).
+                    filter(|c| !c.is_control()).
+                    map_or(" ".to_string(), |c| c.to_string()));
+        };
+
+        //Mark error positions
+        console.set_color(Color::White, Color::Red);
+        for error in self.errors.iter() {
+            if let (Some(line), Some(column)) = (error.line(), error.column()) &&
+                    visible_columns.contains(&(column - 1)) && visible_lines.contains(&(line - 1)) {
+                draw_cell(column - 1, line - 1);
+            }
+        }
+
+        //Mark cursor
+        console.set_color(Color::Black, Color::LightWhite);
+        draw_cell(self.cursor_pos.0, self.cursor_pos.1);
+
+        console.set_cursor_pos(0, Self::TEXT_HEIGHT + 1);
+        console.set_color(Color::Cyan, Color::Default);
+        console.draw_text("-".repeat(Game::CONSOLE_MIN_WIDTH));
+
+        console.set_cursor_pos(0, Self::TEXT_HEIGHT + 2);
+        if let Some(error) = self.current_error() {
+            console.set_color(Color::LightRed, Color::Default);
+            console.draw_text(format!("Errors: {}", self.errors.len()));
+            console.reset_color();
+
+            if let (Some(line), Some(column)) = (error.line(), error.column()) {
+                console.draw_text(format!(" (Line {}, Column {})", line, column));
+            }
+
+            let cause = error.cause().to_string();
+            console.set_cursor_pos(0, Self::TEXT_HEIGHT + 3);
+            if cause.chars().count() > Game::CONSOLE_MIN_WIDTH {
+                console.draw_text(cause.chars().take(Game::CONSOLE_MIN_WIDTH - 3).collect::<String>() + "...");
+            }else {
+                console.draw_text(cause);
+            }
+        }else {
+            console.set_color(Color::LightGreen, Color::Default);
+            console.draw_text("No errors found");
+            console.reset_color();
+            console.draw_text(" (Exit with ESC in order to save the level pack)");
+        }
+    }
+
+    fn on_key_pressed(&mut self, game_state: &mut GameState, key: Key) {
+        let key = game_state.text_input_menu_key(key);
+
+        if key == Key::ESC {
+            if self.has_changes {
+                game_state.open_dialog(Box::new(DialogYesCancelNo::new("Exiting (Save changes?)")));
+            }else {
+                game_state.play_sound_event(SoundEvent::UiSelect);
+
+                game_state.set_screen(ScreenId::SelectLevelPackEditor);
+            }
+
+            return;
+        }
+
+        if key == Key::F1 {
+            game_state.open_help_page();
+
+            return;
+        }
+
+        let (x, y) = self.cursor_pos;
+        match key {
+            Key::LEFT => {
+                if x > 0 {
+                    self.cursor_pos.0 -= 1;
+                }else if y > 0 {
+                    self.cursor_pos = (self.lines[y - 1].len(), y - 1);
+                }
+            },
+            Key::UP if y > 0 => {
+                self.cursor_pos = (x.min(self.lines[y - 1].len()), y - 1);
+            },
+            Key::RIGHT => {
+                if x < self.lines[y].len() {
+                    self.cursor_pos.0 += 1;
+                }else if y + 1 < self.lines.len() {
+                    self.cursor_pos = (0, y + 1);
+                }
+            },
+            Key::DOWN if y + 1 < self.lines.len() => {
+                self.cursor_pos = (x.min(self.lines[y + 1].len()), y + 1);
+            },
+
+            Key::ENTER => {
+                let new_line = self.lines[y].split_off(x);
+                self.lines.insert(y + 1, new_line);
+                self.cursor_pos = (0, y + 1);
+
+                self.has_changes = true;
+                self.check_lvl_data(game_state);
+            },
+            Key::DELETE => {
+                if x > 0 {
+                    self.lines[y].remove(x - 1);
+                    self.cursor_pos.0 -= 1;
+                }else if y > 0 {
+                    let line = self.lines.remove(y);
+                    self.cursor_pos = (self.lines[y - 1].len(), y - 1);
+                    self.lines[y - 1].extend(line);
+                }else {
+                    return;
+                }
+
+                self.has_changes = true;
+                self.check_lvl_data(game_state);
+            },
+
+            key if key.to_ascii().is_some_and(|c| c.is_ascii_graphic() || c == b' ') => {
+                self.lines[y].insert(x, key.to_ascii().unwrap() as char);
+                self.cursor_pos.0 += 1;
+
+                self.has_changes = true;
+                self.check_lvl_data(game_state);
+            },
+
+            _ => {},
+        }
+
+        self.update_scroll_pos();
+    }
+
+    fn on_mouse_pressed(&mut self, _: &mut GameState, column: usize, row: usize) {
+        if row == 0 || row > Self::TEXT_HEIGHT {
+            return;
+        }
+
+        let y = row - 1 + self.scroll_pos.1;
+        if let Some(line) = self.lines.get(y) {
+            self.cursor_pos = ((column + self.scroll_pos.0).min(line.len()), y);
+            self.update_scroll_pos();
+        }
+    }
+
+    fn on_dialog_selection(&mut self, game_state: &mut GameState, selection: DialogSelection) {
+        if selection == DialogSelection::Yes {
+            self.save(game_state);
+
+            game_state.set_screen(ScreenId::SelectLevelPackEditor);
+        }else if selection == DialogSelection::No {
+            game_state.set_screen(ScreenId::SelectLevelPackEditor);
+        }
+
+        //Cancel: Close dialog without doing anything
+    }
+
+    fn on_set_screen(&mut self, game_state: &mut GameState) {
+        let lvl_data = game_state.editor_state.get_current_broken_level_pack().unwrap().lvl_data().unwrap_or_default();
+
+        self.lines = lvl_data.lines().
+                map(|line| line.chars().collect()).
+                collect();
+        if self.lines.is_empty() {
+            self.lines.push(Vec::new());
         }
+
+        self.has_changes = false;
+        self.scroll_pos = (0, 0);
+        self.check_lvl_data(game_state);
+
+        //Start at the first error
+        self.cursor_pos = self.errors.first().
+                and_then(|error| error.line().zip(error.column())).
+                map_or((0, 0), |(line, column)| {
+                    let y = (line - 1).min(self.lines.len() - 1);
+
+                    ((column - 1).min(self.lines[y].len()), y)
+                });
+        self.update_scroll_pos();
+    }
+}
+
+//Level and player position while playing in the level editor
+type EditorPlayingState = (Level, (usize, usize));
+
+impl Diff for EditorPlayingState {
+    type Delta = (LevelDelta, (usize, usize), (usize, usize));
+
+    fn diff(&self, new: &Self) -> Self::Delta {
+        (self.0.diff(&new.0), self.1, new.1)
+    }
+
+    fn apply(&mut self, delta: &Self::Delta) {
+        self.0.apply(&delta.0);
+        self.1 = delta.2;
+    }
+
+    fn revert(&mut self, delta: &Self::Delta) {
+        self.0.revert(&delta.0);
+        self.1 = delta.1;
     }
 }
 
 pub struct ScreenLevelEditor {
-    level: UndoHistory<Level>,
+    level: UndoTree<Level, Deltas<Level>>,
     is_vertical_input: bool,
     is_reverse_input: bool,
-    playing_level: Option<UndoHistory<(Level, (usize, usize))>>,
+    playing_level: Option<UndoHistory<EditorPlayingState, Deltas<EditorPlayingState>>>,
     cursor_pos: (usize, usize),
 }
 
@@ -1908,7 +3897,7 @@ impl ScreenLevelEditor {
 
     pub fn new() -> Self {
         Self {
-            level: UndoHistory::new(Self::UNDO_HISTORY_SIZE, Level::new(1, 1)),
+            level: UndoTree::with_storage(Self::UNDO_HISTORY_SIZE, Level::new(1, 1)),
             is_vertical_input: Default::default(),
             is_reverse_input: Default::default(),
             playing_level: Default::default(),
@@ -1917,9 +3906,11 @@ impl ScreenLevelEditor {
     }
 
     fn on_key_pressed_playing(&mut self, game_state: &mut GameState, key: Key) {
+        let action = game_state.key_action(key);
+
         if let Some(level_history) = self.playing_level.as_mut() {
-            if matches!(key, Key::Z | Key::Y) {
-                let is_undo = key == Key::Z;
+            if matches!(action, Some(Action::Undo | Action::Redo)) {
+                let is_undo = action == Some(Action::Undo);
 
                 let level = if is_undo {
                     level_history.undo()
@@ -1928,11 +3919,11 @@ impl ScreenLevelEditor {
                 };
 
                 if level.is_some() {
-                    game_state.play_sound_effect(audio::UNDO_REDO_EFFECT);
+                    game_state.play_sound_event(SoundEvent::Undo);
                 }
             }
 
-            if key.is_arrow_key() {
+            if let Some(key) = action.and_then(|action| action.direction_key()) {
                 let (mut level, mut player_pos) = level_history.current().clone();
 
                 let width = level.width();
@@ -2001,15 +3992,39 @@ impl ScreenLevelEditor {
                 if player_pos != (x_from, y_from) {
                     level_history.commit_change((level, player_pos));
 
-                    game_state.play_sound_effect(audio::STEP_EFFECT);
+                    game_state.play_sound_event_at(SoundEvent::Step, player_pos.0, width);
                 }else {
-                    game_state.play_sound_effect(audio::NO_PATH_EFFECT);
+                    game_state.play_sound_event_at(SoundEvent::Blocked, player_pos.0, width);
                 }
             }
         }
     }
 
     fn on_key_pressed_editing(&mut self, game_state: &mut GameState, key: Key) {
+        //The edit history uses the key bindings of the game (Bound keys are not used as tiles)
+        if let Some(action @ (Action::Undo | Action::Redo | Action::PreviousBranch | Action::NextBranch | Action::LatestBranch)) =
+                game_state.key_action(key) {
+            let level = match action {
+                Action::Undo => self.level.undo(),
+                Action::Redo => self.level.redo(),
+                Action::PreviousBranch => self.level.previous_branch(),
+                Action::NextBranch => self.level.next_branch(),
+                _ => self.level.latest_branch_tip(),
+            };
+
+            if let Some(level) = level {
+                if self.cursor_pos.0 >= level.width() {
+                    self.cursor_pos.0 = level.width() - 1;
+                }
+
+                if self.cursor_pos.1 >= level.height() {
+                    self.cursor_pos.1 = level.height() - 1;
+                }
+            }
+
+            return;
+        }
+
         match key {
             Key::LEFT => {
                 if self.cursor_pos.0 > 0 {
@@ -2205,26 +4220,6 @@ impl ScreenLevelEditor {
                 }
             },
 
-            Key::Z | Key::Y => {
-                let is_undo = key == Key::Z;
-
-                let level = if is_undo {
-                    self.level.undo()
-                }else {
-                    self.level.redo()
-                };
-
-                if let Some(level) = level {
-                    if self.cursor_pos.0 >= level.width() {
-                        self.cursor_pos.0 = level.width() - 1;
-                    }
-
-                    if self.cursor_pos.1 >= level.height() {
-                        self.cursor_pos.1 = level.height() - 1;
-                    }
-                }
-            },
-
             key if key.is_ascii() => {
                 if let Ok(tile_input) = Tile::from_ascii(key.to_ascii().unwrap()) && tile_input != Tile::Secret {
                     let mut level = self.level.current().clone();
@@ -2278,32 +4273,45 @@ impl Screen for ScreenLevelEditor {
 
             console.set_cursor_pos(((Game::CONSOLE_MIN_WIDTH - 14) as f64 * 0.5) as usize, 0);
             console.draw_text(format!("Cursor ({:02}:{:02})", self.cursor_pos.0 + 1, self.cursor_pos.1 + 1));
+
+            if let Some((branch_index, branch_count)) = self.level.branch_position() {
+                console.set_cursor_pos(((Game::CONSOLE_MIN_WIDTH - 13) as f64 * 0.75) as usize, 0);
+                console.draw_text(format!("Branch: {}/{}", branch_index + 1, branch_count));
+            }
         }
 
         let x_offset = ((Game::CONSOLE_MIN_WIDTH - self.level.current().width()) as f64 * 0.5) as usize;
         let y_offset = 1;
 
         self.playing_level.as_ref().map_or(self.level.current(), |level| &level.current().0).
-                draw(console, x_offset, y_offset, game_state.is_player_background(),
+                draw(console, x_offset, y_offset, game_state.settings().color_theme(), game_state.is_player_background(),
                      self.playing_level.as_ref().map_or(Some(self.cursor_pos), |_| None));
     }
 
     fn on_key_pressed(&mut self, game_state: &mut GameState, key: Key) {
-        if key == Key::ESC {
+        //Letters are tiles in the editing mode
+        let menu_key = if self.playing_level.is_some() {
+            game_state.menu_key(key)
+        }else {
+            game_state.text_input_menu_key(key)
+        };
+
+        if menu_key == Key::ESC {
             game_state.open_dialog(Box::new(DialogYesCancelNo::new("Exiting (Save changes?)")));
 
             return;
         }
 
-        if key == Key::F1 {
+        if menu_key == Key::F1 {
             game_state.open_help_page();
 
             return;
         }
 
-        if key == Key::R {
+        //The reset action switches between the playing and the editing mode
+        if game_state.key_action(key) == Some(Action::Reset) {
             self.playing_level = if self.playing_level.is_some() {
-                game_state.play_sound_effect(audio::LEVEL_RESET);
+                game_state.play_sound_event(SoundEvent::Reset);
 
                 None
             }else {
@@ -2331,14 +4339,14 @@ impl Screen for ScreenLevelEditor {
                     }
                 }
 
-                Some(UndoHistory::new(Self::UNDO_HISTORY_SIZE_PLAYING, (self.level.current().clone(), player_pos.unwrap())))
+                Some(UndoHistory::with_storage(Self::UNDO_HISTORY_SIZE_PLAYING, (self.level.current().clone(), player_pos.unwrap())))
             };
 
             return;
         }
 
         if self.playing_level.is_none() {
-            self.on_key_pressed_editing(game_state, key);
+            self.on_key_pressed_editing(game_state, menu_key);
         }else {
             self.on_key_pressed_playing(game_state, key);
         }
@@ -162,19 +162,44 @@ impl DialogOk {
     }
 }
 
+impl DialogOk {
+    //Additional lines of multi-line messages are drawn left-aligned below the first line
+    fn layout(&self, console_width: usize, console_height: usize) -> (usize, usize, usize, usize) {
+        let line_count = self.message.lines().count().max(1);
+
+        let width = self.message.lines().
+                map(|line| line.chars().count()).
+                max().unwrap_or_default().
+                clamp(16, console_width - 2);
+        let width_with_border = width + 2;
+
+        let x_start = ((console_width - width_with_border) as f64 * 0.5) as usize;
+        let y_start = (console_height.saturating_sub(5 + line_count) as f64 * 0.5) as usize;
+
+        (width, x_start, y_start, line_count - 1)
+    }
+
+    fn truncate_line(line: &str, width: usize) -> String {
+        if line.chars().count() <= width {
+            line.to_string()
+        }else {
+            line.chars().take(width - 3).collect::<String>() + "..."
+        }
+    }
+}
+
 impl Dialog for DialogOk {
     fn dialog_type(&self) -> DialogType {
         self.dialog_type
     }
 
     fn draw(&self, console: &Console, console_width: usize, console_height: usize) {
-        let char_count = self.message.chars().count();
-
-        let width = char_count.max(16);
+        let (width, x_start, y_start, additional_line_count) = self.layout(console_width, console_height);
         let width_with_border = width + 2;
 
-        let x_start = ((console_width - width_with_border) as f64 * 0.5) as usize;
-        let y_start = ((console_height - 6) as f64 * 0.5) as usize;
+        let mut lines = self.message.lines();
+        let message = Self::truncate_line(lines.next().unwrap_or_default(), width);
+        let char_count = message.chars().count();
 
         let whitespace_count_half = ((width - char_count) as f64 * 0.5) as usize;
 
@@ -183,7 +208,7 @@ impl Dialog for DialogOk {
         console.draw_text(format!(
             "{}{}{}",
             " ".repeat(whitespace_count_half),
-            self.message,
+            message,
             " ".repeat(width - char_count - whitespace_count_half),
         ));
 
@@ -196,6 +221,15 @@ impl Dialog for DialogOk {
             " ".repeat(width - char_count - whitespace_count_half),
         ));
 
+        for (i, line) in lines.enumerate() {
+            let line = Self::truncate_line(line, width);
+
+            console.set_cursor_pos(x_start + 1, y_start + 3 + i);
+            console.draw_text(format!("{}{}", line, " ".repeat(width - line.chars().count())));
+        }
+
+        let y_start = y_start + additional_line_count;
+
         console.set_cursor_pos(x_start + 1, y_start + 3);
         console.draw_text(" ".repeat(width));
 
@@ -209,7 +243,7 @@ impl Dialog for DialogOk {
         ));
 
         console.set_color(Color::LightBlack, Color::Red);
-        self.draw_border(console, x_start, y_start, width_with_border, 5);
+        self.draw_border(console, x_start, y_start - additional_line_count, width_with_border, 5 + additional_line_count);
     }
 
     fn on_key_pressed(&self, _: usize, _: usize, key: Key) -> Option<DialogSelection> {
@@ -221,13 +255,8 @@ impl Dialog for DialogOk {
     }
 
     fn on_mouse_pressed(&self, console_width: usize, console_height: usize, column: usize, row: usize) -> Option<DialogSelection> {
-        let char_count = self.message.chars().count();
-
-        let width = char_count.max(16);
-        let width_with_border = width + 2;
-
-        let x_start = ((console_width - width_with_border) as f64 * 0.5) as usize;
-        let y_start = ((console_height - 6) as f64 * 0.5) as usize;
+        let (width, x_start, y_start, additional_line_count) = self.layout(console_width, console_height);
+        let y_start = y_start + additional_line_count;
 
         let whitespace_count_half = ((width - 4) as f64 * 0.5) as usize;
 
@@ -0,0 +1,176 @@
+use super::*;
+use crate::game::audio::{AudioBackend, RecordingAudioBackend};
+
+const LVL_DATA: &str = "Levels: 1\n\nw: 5, h: 3\n#####\n#P--#\n#####\n";
+
+fn start_level(settings: Settings) -> (GameState, ScreenInGame, RecordingAudioBackend) {
+    let level_pack = LevelPack::read_editor_level_pack("test", "test.lvl", LVL_DATA).unwrap();
+    let level = level_pack.levels()[0].level().clone();
+
+    let audio_backend = RecordingAudioBackend::default();
+    let mut game_state = GameState::new(vec![level_pack], Vec::new(), Vec::new(), Some(Box::new(audio_backend.clone()) as Box<dyn AudioBackend>));
+    game_state.settings = settings;
+
+    let mut screen = ScreenInGame::new();
+    screen.start_attempt(&level);
+
+    (game_state, screen, audio_backend)
+}
+
+#[test]
+fn sound_events_of_moves() {
+    let (mut game_state, mut screen, audio_backend) = start_level(Settings::default());
+
+    screen.on_key_pressed(&mut game_state, Key::LEFT);
+    screen.on_key_pressed(&mut game_state, Key::RIGHT);
+    screen.on_key_pressed(&mut game_state, Key::RIGHT);
+    screen.on_key_pressed(&mut game_state, Key::RIGHT);
+    assert_eq!(audio_backend.sound_events(), [SoundEvent::Blocked, SoundEvent::Step, SoundEvent::Step, SoundEvent::Blocked]);
+
+    audio_backend.clear();
+
+    let undo_key = game_state.settings().key_bindings().keys(Action::Undo)[0];
+    screen.on_key_pressed(&mut game_state, undo_key);
+    screen.on_key_pressed(&mut game_state, undo_key);
+    screen.on_key_pressed(&mut game_state, undo_key);
+    assert_eq!(audio_backend.sound_events(), [SoundEvent::Undo, SoundEvent::Undo]);
+}
+
+#[test]
+fn sound_events_mapped_to_none() {
+    let settings = Settings::parse("sound-mapping,step,none\nsound-mapping,blocked,step|undo_redo").unwrap();
+    let (mut game_state, mut screen, audio_backend) = start_level(settings);
+
+    screen.on_key_pressed(&mut game_state, Key::RIGHT);
+    screen.on_key_pressed(&mut game_state, Key::UP);
+    assert_eq!(audio_backend.sound_events(), [SoundEvent::Blocked]);
+}
+
+#[test]
+fn sound_events_without_sound() {
+    let mut settings = Settings::default();
+    settings.set_sound_enabled(false);
+    let (mut game_state, mut screen, audio_backend) = start_level(settings);
+
+    screen.on_key_pressed(&mut game_state, Key::RIGHT);
+    assert_eq!(audio_backend.sound_events(), []);
+}
+
+#[test]
+fn sound_events_panned_by_player_position() {
+    let (mut game_state, mut screen, audio_backend) = start_level(Settings::default());
+
+    screen.on_key_pressed(&mut game_state, Key::LEFT);
+    screen.on_key_pressed(&mut game_state, Key::RIGHT);
+    screen.on_key_pressed(&mut game_state, Key::RIGHT);
+    assert_eq!(audio_backend.pans(), [Some(-0.5), Some(0.0), Some(0.5)]);
+
+    let mut settings = game_state.settings().clone();
+    settings.set_stereo_panning_enabled(false);
+    game_state.settings = settings;
+    audio_backend.clear();
+
+    screen.on_key_pressed(&mut game_state, Key::LEFT);
+    assert_eq!(audio_backend.pans(), [None]);
+
+    let undo_key = game_state.settings().key_bindings().keys(Action::Undo)[0];
+    game_state.settings = Settings::default();
+    screen.on_key_pressed(&mut game_state, undo_key);
+    assert_eq!(audio_backend.pans(), [None, None]);
+}
+
+#[test]
+fn menu_keys_of_key_bindings() {
+    let settings = Settings::parse("key-binding,back,F4\nkey-binding,move-up,i").unwrap();
+    let (game_state, _, _) = start_level(settings);
+
+    assert_eq!(game_state.menu_key(Key::F4), Key::ESC);
+    assert_eq!(game_state.menu_key(Key::I), Key::UP);
+    assert_eq!(game_state.menu_key(Key::Q), Key::Q);
+
+    assert_eq!(game_state.text_input_menu_key(Key::F4), Key::ESC);
+    assert_eq!(game_state.text_input_menu_key(Key::I), Key::I);
+}
+
+#[test]
+fn startup_errors_are_shown_one_after_another() {
+    let (mut game_state, _, audio_backend) = start_level(Settings::default());
+    game_state.startup_errors = vec!["Cannot load settings".to_string(), "Cannot resume level".to_string()];
+
+    let mut screen = ScreenStartMenu::new();
+    for _ in 0..2 {
+        screen.update(&mut game_state);
+        assert!(game_state.is_dialog_opened());
+
+        game_state.dialog = None;
+    }
+
+    screen.update(&mut game_state);
+    assert!(!game_state.is_dialog_opened());
+    assert_eq!(audio_backend.sound_events(), [SoundEvent::UiError, SoundEvent::UiError]);
+}
+
+#[test]
+fn bell_is_pending_until_drawn() {
+    let settings = Settings::parse("feedback,bell").unwrap();
+    let (mut game_state, mut screen, audio_backend) = start_level(settings);
+
+    screen.on_key_pressed(&mut game_state, Key::RIGHT);
+    assert!(!game_state.is_bell_pending.get());
+
+    screen.on_key_pressed(&mut game_state, Key::UP);
+    assert!(game_state.is_bell_pending.get());
+    assert_eq!(audio_backend.sound_events(), []);
+}
+
+#[test]
+fn branch_switches_are_counted_as_undos() {
+    let (mut game_state, mut screen, _) = start_level(Settings::default());
+
+    let key_bindings = game_state.settings().key_bindings().clone();
+    screen.on_key_pressed(&mut game_state, Key::RIGHT);
+    screen.on_key_pressed(&mut game_state, key_bindings.keys(Action::Undo)[0]);
+    screen.on_key_pressed(&mut game_state, key_bindings.keys(Action::LatestBranch)[0]);
+    screen.on_key_pressed(&mut game_state, key_bindings.keys(Action::LatestBranch)[0]);
+
+    assert_eq!(screen.undo_count, 2);
+}
+
+#[test]
+fn level_editor_uses_key_bindings() {
+    let settings = Settings::parse("key-binding,undo,y\nkey-binding,redo,z\nkey-binding,reset,F6").unwrap();
+    let (mut game_state, _, _) = start_level(settings);
+
+    let mut screen = ScreenLevelEditor::new();
+    screen.level = UndoTree::with_storage(ScreenLevelEditor::UNDO_HISTORY_SIZE, Level::from_str("w: 5, h: 3\n#####\n#P--#\n#####").unwrap());
+    screen.cursor_pos = (2, 1);
+
+    screen.on_key_pressed(&mut game_state, Key::X);
+    assert_eq!(screen.level.current().get_tile(2, 1), Some(&Tile::Goal));
+
+    screen.on_key_pressed(&mut game_state, Key::Y);
+    assert_eq!(screen.level.current().get_tile(2, 1), Some(&Tile::Empty));
+
+    screen.on_key_pressed(&mut game_state, Key::Z);
+    assert_eq!(screen.level.current().get_tile(2, 1), Some(&Tile::Goal));
+
+    //R does not switch to the playing mode if the reset action is bound to another key
+    screen.on_key_pressed(&mut game_state, Key::R);
+    assert!(screen.playing_level.is_none());
+
+    screen.on_key_pressed(&mut game_state, Key::F6);
+    assert!(screen.playing_level.is_some());
+}
+
+#[test]
+fn suspended_level_of_unloaded_level_pack_is_kept() {
+    let (mut game_state, _, _) = start_level(Settings::default());
+    game_state.suspended_level = Some(SuspendedLevel::new(
+        "external", 0, 0, 1000, 0, 0, vec![SuspendedState::from_str("state,1,1,0,0").unwrap()], 0,
+    ));
+
+    let err = game_state.resume_suspended_level().unwrap_err();
+    assert!(err.to_string().starts_with("The level pack \"external\" is not loaded"));
+    assert!(game_state.suspended_level().is_some());
+    assert!(!game_state.is_resuming_suspended_level);
+}
@@ -0,0 +1,501 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use std::str::FromStr;
+use crate::game::audio::{SoundCategory, SoundMapping};
+use crate::game::input::{KeyBindings, KeyPreset};
+use crate::game::save_game;
+
+#[cfg(test)]
+mod tests;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorTheme {
+    #[default]
+    Default,
+    HighContrast,
+    Monochrome,
+}
+
+impl ColorTheme {
+    pub const ALL: [ColorTheme; 3] = [
+        Self::Default,
+        Self::HighContrast,
+        Self::Monochrome,
+    ];
+
+    //Used in the settings file
+    fn id(&self) -> &'static str {
+        match self {
+            Self::Default => "default",
+            Self::HighContrast => "high-contrast",
+            Self::Monochrome => "monochrome",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Default => "Default",
+            Self::HighContrast => "High contrast",
+            Self::Monochrome => "Monochrome",
+        }
+    }
+}
+
+impl FromStr for ColorTheme {
+    type Err = SettingsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL.into_iter().
+                find(|color_theme| color_theme.id() == s).
+                ok_or_else(|| SettingsError::new(format!("Unknown color theme \"{s}\"")))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BlinkRate {
+    Slow,
+    #[default]
+    Normal,
+    Fast,
+}
+
+impl BlinkRate {
+    pub const ALL: [BlinkRate; 3] = [
+        Self::Slow,
+        Self::Normal,
+        Self::Fast,
+    ];
+
+    //Used in the settings file
+    fn id(&self) -> &'static str {
+        match self {
+            Self::Slow => "slow",
+            Self::Normal => "normal",
+            Self::Fast => "fast",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Slow => "Slow",
+            Self::Normal => "Normal",
+            Self::Fast => "Fast",
+        }
+    }
+
+    //Updates between switching the player background (25 updates per second)
+    pub fn delay(&self) -> i32 {
+        match self {
+            Self::Slow => 25,
+            Self::Normal => 12,
+            Self::Fast => 6,
+        }
+    }
+}
+
+impl FromStr for BlinkRate {
+    type Err = SettingsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL.into_iter().
+                find(|blink_rate| blink_rate.id() == s).
+                ok_or_else(|| SettingsError::new(format!("Unknown blink rate \"{s}\"")))
+    }
+}
+
+//Backend for the feedback of important sound effects (Auto uses the sound if an audio device exists and the ticker otherwise)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FeedbackMode {
+    #[default]
+    Auto,
+    Sound,
+    Bell,
+    Flash,
+    Ticker,
+}
+
+impl FeedbackMode {
+    pub const ALL: [FeedbackMode; 5] = [
+        Self::Auto,
+        Self::Sound,
+        Self::Bell,
+        Self::Flash,
+        Self::Ticker,
+    ];
+
+    //Used in the settings file
+    fn id(&self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::Sound => "sound",
+            Self::Bell => "bell",
+            Self::Flash => "flash",
+            Self::Ticker => "ticker",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Auto => "Auto",
+            Self::Sound => "Sound",
+            Self::Bell => "Terminal bell",
+            Self::Flash => "Screen flash",
+            Self::Ticker => "Event ticker",
+        }
+    }
+}
+
+impl FromStr for FeedbackMode {
+    type Err = SettingsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL.into_iter().
+                find(|feedback_mode| feedback_mode.id() == s).
+                ok_or_else(|| SettingsError::new(format!("Unknown feedback mode \"{s}\"")))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Settings {
+    is_sound_enabled: bool,
+
+    //Percent
+    master_volume: u32,
+    ui_volume: u32,
+    gameplay_volume: u32,
+    jingle_volume: u32,
+    music_volume: u32,
+
+    //None: The embedded sound effects are used
+    sound_pack: Option<String>,
+    sound_mapping: SoundMapping,
+
+    feedback_mode: FeedbackMode,
+
+    //Gameplay sounds are panned by the horizontal position of the player
+    is_stereo_panning_enabled: bool,
+
+    color_theme: ColorTheme,
+
+    is_player_blink_enabled: bool,
+    player_blink_rate: BlinkRate,
+
+    is_deadlock_warning_enabled: bool,
+    is_confirmation_enabled: bool,
+
+    key_bindings: KeyBindings,
+}
+
+impl Settings {
+    pub const MAX_VOLUME: u32 = 100;
+    pub const VOLUME_STEP: u32 = 10;
+
+    //Returns the default settings if the file does not exist
+    pub fn read(path: &Path) -> Result<Self, SettingsError> {
+        if !std::fs::exists(path).map_err(|err| SettingsError::new(format!("Cannot check settings: {err}")))? {
+            return Ok(Self::default());
+        }
+
+        let settings_data = std::fs::read_to_string(path).
+                map_err(|err| SettingsError::new(format!("Cannot read settings: {err}")))?;
+
+        Self::parse(&settings_data)
+    }
+
+    //Settings which are missing in the settings data keep their default value
+    pub fn parse(settings_data: &str) -> Result<Self, SettingsError> {
+        let mut settings = Self::default();
+
+        for (i, line) in settings_data.lines().
+                map(|line| line.trim()).
+                enumerate().
+                filter(|(_, line)| !line.is_empty()) {
+            let Some((key, value)) = line.split_once(",") else {
+                return Err(SettingsError::new(format!("Invalid setting \"{}\" (Line {})", line, i + 1)));
+            };
+
+            settings.parse_setting(key, value).
+                    map_err(|err| SettingsError::new(format!("{} (Line {})", err, i + 1)))?;
+        }
+
+        Ok(settings)
+    }
+
+    fn parse_setting(&mut self, key: &str, value: &str) -> Result<(), SettingsError> {
+        match key {
+            "sound" => self.is_sound_enabled = Self::parse_switch(value)?,
+            //"effect-volume" was used for the master volume in older settings files
+            "master-volume" | "effect-volume" => self.master_volume = Self::parse_volume(value)?,
+            "ui-volume" => self.ui_volume = Self::parse_volume(value)?,
+            "gameplay-volume" => self.gameplay_volume = Self::parse_volume(value)?,
+            "jingle-volume" => self.jingle_volume = Self::parse_volume(value)?,
+            "music-volume" => self.music_volume = Self::parse_volume(value)?,
+            "sound-pack" if !value.is_empty() => self.sound_pack = Some(value.to_string()),
+            "sound-pack" => return Err(SettingsError::new("Sound pack name must not be empty")),
+            "sound-mapping" => self.sound_mapping.parse_custom_mapping(value)?,
+            "feedback" => self.feedback_mode = FeedbackMode::from_str(value)?,
+            "stereo-panning" => self.is_stereo_panning_enabled = Self::parse_switch(value)?,
+            "color-theme" => self.color_theme = ColorTheme::from_str(value)?,
+            "player-blink" => self.is_player_blink_enabled = Self::parse_switch(value)?,
+            "player-blink-rate" => self.player_blink_rate = BlinkRate::from_str(value)?,
+            "deadlock-warnings" => self.is_deadlock_warning_enabled = Self::parse_switch(value)?,
+            "confirmations" => self.is_confirmation_enabled = Self::parse_switch(value)?,
+            "key-preset" => self.key_bindings.set_preset(KeyPreset::from_str(value)?),
+            "key-binding" => self.key_bindings.parse_custom_binding(value)?,
+
+            _ => return Err(SettingsError::new(format!("Unknown setting \"{key}\""))),
+        }
+
+        Ok(())
+    }
+
+    fn parse_volume(value: &str) -> Result<u32, SettingsError> {
+        u32::from_str(value).ok().
+                filter(|volume| *volume <= Self::MAX_VOLUME).
+                ok_or_else(|| SettingsError::new(format!("Invalid volume \"{value}\"")))
+    }
+
+    fn parse_switch(value: &str) -> Result<bool, SettingsError> {
+        match value {
+            "on" => Ok(true),
+            "off" => Ok(false),
+
+            _ => Err(SettingsError::new(format!("Invalid switch \"{value}\" (Must be \"on\" or \"off\")"))),
+        }
+    }
+
+    fn switch_to_str(is_enabled: bool) -> &'static str {
+        if is_enabled {
+            "on"
+        }else {
+            "off"
+        }
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        save_game::write_atomically(path, &self.to_string())
+    }
+
+    pub fn is_sound_enabled(&self) -> bool {
+        self.is_sound_enabled
+    }
+
+    pub fn set_sound_enabled(&mut self, is_sound_enabled: bool) {
+        self.is_sound_enabled = is_sound_enabled;
+    }
+
+    pub fn master_volume(&self) -> u32 {
+        self.master_volume
+    }
+
+    //The volume is clamped to MAX_VOLUME
+    pub fn set_master_volume(&mut self, master_volume: u32) {
+        self.master_volume = master_volume.min(Self::MAX_VOLUME);
+    }
+
+    pub fn category_volume(&self, category: SoundCategory) -> u32 {
+        match category {
+            SoundCategory::Ui => self.ui_volume,
+            SoundCategory::Gameplay => self.gameplay_volume,
+            SoundCategory::Jingles => self.jingle_volume,
+        }
+    }
+
+    //The volume is clamped to MAX_VOLUME
+    pub fn set_category_volume(&mut self, category: SoundCategory, volume: u32) {
+        let volume = volume.min(Self::MAX_VOLUME);
+        match category {
+            SoundCategory::Ui => self.ui_volume = volume,
+            SoundCategory::Gameplay => self.gameplay_volume = volume,
+            SoundCategory::Jingles => self.jingle_volume = volume,
+        }
+    }
+
+    pub fn music_volume(&self) -> u32 {
+        self.music_volume
+    }
+
+    //The volume is clamped to MAX_VOLUME
+    pub fn set_music_volume(&mut self, music_volume: u32) {
+        self.music_volume = music_volume.min(Self::MAX_VOLUME);
+    }
+
+    pub fn sound_pack(&self) -> Option<&str> {
+        self.sound_pack.as_deref()
+    }
+
+    pub fn set_sound_pack(&mut self, sound_pack: Option<String>) {
+        self.sound_pack = sound_pack;
+    }
+
+    pub fn sound_mapping(&self) -> &SoundMapping {
+        &self.sound_mapping
+    }
+
+    pub fn feedback_mode(&self) -> FeedbackMode {
+        self.feedback_mode
+    }
+
+    pub fn set_feedback_mode(&mut self, feedback_mode: FeedbackMode) {
+        self.feedback_mode = feedback_mode;
+    }
+
+    pub fn is_stereo_panning_enabled(&self) -> bool {
+        self.is_stereo_panning_enabled
+    }
+
+    pub fn set_stereo_panning_enabled(&mut self, is_stereo_panning_enabled: bool) {
+        self.is_stereo_panning_enabled = is_stereo_panning_enabled;
+    }
+
+    //Returns the volume factor of the music (Includes the master volume, 0.0 if the sound is disabled)
+    pub fn effective_music_volume(&self) -> f32 {
+        if !self.is_sound_enabled {
+            return 0.0;
+        }
+
+        (self.master_volume * self.music_volume) as f32 / (Self::MAX_VOLUME * Self::MAX_VOLUME) as f32
+    }
+
+    //Returns the volume factor of a sound effect of the category (Includes the master volume, 0.0 if the sound is disabled)
+    pub fn effective_volume(&self, category: SoundCategory) -> f32 {
+        if !self.is_sound_enabled {
+            return 0.0;
+        }
+
+        (self.master_volume * self.category_volume(category)) as f32 / (Self::MAX_VOLUME * Self::MAX_VOLUME) as f32
+    }
+
+    pub fn color_theme(&self) -> ColorTheme {
+        self.color_theme
+    }
+
+    pub fn set_color_theme(&mut self, color_theme: ColorTheme) {
+        self.color_theme = color_theme;
+    }
+
+    pub fn is_player_blink_enabled(&self) -> bool {
+        self.is_player_blink_enabled
+    }
+
+    pub fn set_player_blink_enabled(&mut self, is_player_blink_enabled: bool) {
+        self.is_player_blink_enabled = is_player_blink_enabled;
+    }
+
+    pub fn player_blink_rate(&self) -> BlinkRate {
+        self.player_blink_rate
+    }
+
+    pub fn set_player_blink_rate(&mut self, player_blink_rate: BlinkRate) {
+        self.player_blink_rate = player_blink_rate;
+    }
+
+    pub fn is_deadlock_warning_enabled(&self) -> bool {
+        self.is_deadlock_warning_enabled
+    }
+
+    pub fn set_deadlock_warning_enabled(&mut self, is_deadlock_warning_enabled: bool) {
+        self.is_deadlock_warning_enabled = is_deadlock_warning_enabled;
+    }
+
+    //Confirmation prompts for resetting a level after many moves and for leaving a level
+    pub fn is_confirmation_enabled(&self) -> bool {
+        self.is_confirmation_enabled
+    }
+
+    pub fn set_confirmation_enabled(&mut self, is_confirmation_enabled: bool) {
+        self.is_confirmation_enabled = is_confirmation_enabled;
+    }
+
+    pub fn key_bindings(&self) -> &KeyBindings {
+        &self.key_bindings
+    }
+
+    pub fn key_bindings_mut(&mut self) -> &mut KeyBindings {
+        &mut self.key_bindings
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            is_sound_enabled: true,
+
+            master_volume: Self::MAX_VOLUME,
+            ui_volume: Self::MAX_VOLUME,
+            gameplay_volume: Self::MAX_VOLUME,
+            jingle_volume: Self::MAX_VOLUME,
+            music_volume: Self::MAX_VOLUME,
+
+            sound_pack: Default::default(),
+            sound_mapping: Default::default(),
+
+            feedback_mode: Default::default(),
+
+            is_stereo_panning_enabled: true,
+
+            color_theme: Default::default(),
+
+            is_player_blink_enabled: true,
+            player_blink_rate: Default::default(),
+
+            is_deadlock_warning_enabled: true,
+            is_confirmation_enabled: true,
+
+            key_bindings: Default::default(),
+        }
+    }
+}
+
+impl Display for Settings {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "sound,{}", Self::switch_to_str(self.is_sound_enabled))?;
+        writeln!(f, "master-volume,{}", self.master_volume)?;
+        writeln!(f, "ui-volume,{}", self.ui_volume)?;
+        writeln!(f, "gameplay-volume,{}", self.gameplay_volume)?;
+        writeln!(f, "jingle-volume,{}", self.jingle_volume)?;
+        writeln!(f, "music-volume,{}", self.music_volume)?;
+        if let Some(sound_pack) = &self.sound_pack {
+            writeln!(f, "sound-pack,{}", sound_pack)?;
+        }
+
+        for custom_mapping in self.sound_mapping.custom_mapping_strs() {
+            writeln!(f, "sound-mapping,{}", custom_mapping)?;
+        }
+
+        writeln!(f, "feedback,{}", self.feedback_mode.id())?;
+        writeln!(f, "stereo-panning,{}", Self::switch_to_str(self.is_stereo_panning_enabled))?;
+        writeln!(f, "color-theme,{}", self.color_theme.id())?;
+        writeln!(f, "player-blink,{}", Self::switch_to_str(self.is_player_blink_enabled))?;
+        writeln!(f, "player-blink-rate,{}", self.player_blink_rate.id())?;
+        writeln!(f, "deadlock-warnings,{}", Self::switch_to_str(self.is_deadlock_warning_enabled))?;
+        writeln!(f, "confirmations,{}", Self::switch_to_str(self.is_confirmation_enabled))?;
+        writeln!(f, "key-preset,{}", self.key_bindings.preset_id())?;
+
+        for custom_binding in self.key_bindings.custom_binding_strs() {
+            writeln!(f, "key-binding,{}", custom_binding)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct SettingsError {
+    message: String
+}
+
+impl SettingsError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self { message: message.into() }
+    }
+}
+
+impl Display for SettingsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl Error for SettingsError {}
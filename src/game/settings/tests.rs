@@ -0,0 +1,131 @@
+use super::*;
+use crate::game::audio::{SoundCategory, SoundEvent};
+use crate::game::input::KeyPreset;
+use crate::game::test_utils::TempDir;
+
+#[test]
+fn settings_round_trip() {
+    let mut settings = Settings::default();
+    settings.set_sound_enabled(false);
+    settings.set_master_volume(40);
+    settings.set_category_volume(SoundCategory::Gameplay, 70);
+    settings.set_color_theme(ColorTheme::HighContrast);
+    settings.set_player_blink_rate(BlinkRate::Fast);
+    settings.set_confirmation_enabled(false);
+    settings.set_feedback_mode(FeedbackMode::Ticker);
+    settings.set_stereo_panning_enabled(false);
+
+    assert_eq!(
+        settings.to_string(),
+        "sound,off\nmaster-volume,40\nui-volume,100\ngameplay-volume,70\njingle-volume,100\nmusic-volume,100\nfeedback,ticker\nstereo-panning,off\ncolor-theme,high-contrast\nplayer-blink,on\nplayer-blink-rate,fast\n\
+        deadlock-warnings,on\nconfirmations,off\nkey-preset,arrows\n",
+    );
+    assert_eq!(Settings::parse(&settings.to_string()).unwrap(), settings);
+}
+
+#[test]
+fn parse_settings() {
+    //Missing settings keep their default value
+    let settings = Settings::parse("\ncolor-theme,monochrome\n\nplayer-blink,off\n").unwrap();
+    assert_eq!(settings.color_theme(), ColorTheme::Monochrome);
+    assert!(!settings.is_player_blink_enabled());
+    assert!(settings.is_sound_enabled());
+    assert_eq!(settings.master_volume(), Settings::MAX_VOLUME);
+
+    //Old settings files contain the master volume as "effect-volume"
+    assert_eq!(Settings::parse("effect-volume,30").unwrap().master_volume(), 30);
+
+    assert_eq!(Settings::parse("sound").unwrap_err().to_string(), "Invalid setting \"sound\" (Line 1)");
+    assert_eq!(Settings::parse("sound,on\nvolume,5").unwrap_err().to_string(), "Unknown setting \"volume\" (Line 2)");
+    assert!(Settings::parse("sound,yes").is_err());
+    assert!(Settings::parse("effect-volume,101").is_err());
+    assert!(Settings::parse("jingle-volume,-1").is_err());
+    assert!(Settings::parse("color-theme,dark").is_err());
+    assert!(Settings::parse("player-blink-rate,0").is_err());
+    assert!(Settings::parse("feedback,beep").is_err());
+}
+
+#[test]
+fn read_and_write_settings() {
+    let temp_dir = TempDir::new("settings");
+    let path = temp_dir.join("settings.cfg");
+
+    assert_eq!(Settings::read(&path).unwrap(), Settings::default());
+
+    let mut settings = Settings::default();
+    settings.set_master_volume(250);
+    assert_eq!(settings.master_volume(), Settings::MAX_VOLUME);
+    settings.set_deadlock_warning_enabled(false);
+    settings.write(&path).unwrap();
+
+    assert_eq!(Settings::read(&path).unwrap(), settings);
+}
+
+#[test]
+fn parse_key_bindings() {
+    let settings = Settings::parse("key-preset,wasd\nkey-binding,undo,y\nkey-binding,redo,z\nkey-binding,back,q\nkey-binding,back,ESC").unwrap();
+    assert_eq!(settings.key_bindings().preset(), KeyPreset::Wasd);
+    assert_eq!(settings.key_bindings().custom_bindings().len(), 4);
+    assert_eq!(Settings::parse(&settings.to_string()).unwrap(), settings);
+
+    assert!(Settings::parse("key-preset,qwerty").is_err());
+    assert!(Settings::parse("key-binding,undo").is_err());
+    assert!(Settings::parse("key-binding,jump,SPACE").is_err());
+    assert!(Settings::parse("key-binding,undo,CTRL").is_err());
+}
+
+#[test]
+fn effective_volume() {
+    let mut settings = Settings::default();
+    assert_eq!(settings.effective_volume(SoundCategory::Ui), 1.0);
+
+    settings.set_master_volume(50);
+    settings.set_category_volume(SoundCategory::Jingles, 40);
+    assert_eq!(settings.effective_volume(SoundCategory::Jingles), 0.2);
+    assert_eq!(settings.effective_volume(SoundCategory::Gameplay), 0.5);
+
+    settings.set_music_volume(20);
+    assert_eq!(settings.effective_music_volume(), 0.1);
+
+    settings.set_sound_enabled(false);
+    assert_eq!(settings.effective_volume(SoundCategory::Gameplay), 0.0);
+    assert_eq!(settings.effective_music_volume(), 0.0);
+}
+
+#[test]
+fn parse_sound_pack() {
+    assert_eq!(Settings::default().sound_pack(), None);
+
+    let settings = Settings::parse("sound-pack,retro").unwrap();
+    assert_eq!(settings.sound_pack(), Some("retro"));
+    assert!(settings.to_string().contains("\nsound-pack,retro\n"));
+    assert_eq!(Settings::parse(&settings.to_string()).unwrap(), settings);
+
+    assert!(Settings::parse("sound-pack,").is_err());
+}
+
+#[test]
+fn parse_sound_mapping() {
+    fn sound_effect_names(settings: &Settings, sound_event: SoundEvent) -> Vec<&'static str> {
+        settings.sound_mapping().sound_effects(sound_event).iter().
+                map(|sound_effect| sound_effect.name()).
+                collect()
+    }
+
+    let settings = Settings::parse("sound-mapping,step,step|undo_redo\nsound-mapping,ui-select,none\nsound-mapping,blocked,ui_error").unwrap();
+    assert_eq!(sound_effect_names(&settings, SoundEvent::Step), ["step", "undo_redo"]);
+    assert_eq!(sound_effect_names(&settings, SoundEvent::UiSelect), Vec::<&str>::new());
+    assert_eq!(sound_effect_names(&settings, SoundEvent::Blocked), ["ui_error"]);
+    assert_eq!(sound_effect_names(&settings, SoundEvent::Reset), ["level_reset"]);
+    assert!(settings.to_string().contains("\nsound-mapping,step,step|undo_redo\nsound-mapping,ui-select,none\n"));
+    assert_eq!(Settings::parse(&settings.to_string()).unwrap(), settings);
+
+    //A later mapping of the same event replaces the earlier mapping
+    let settings = Settings::parse("sound-mapping,undo,none\nsound-mapping,undo,step").unwrap();
+    assert_eq!(sound_effect_names(&settings, SoundEvent::Undo), ["step"]);
+
+    assert!(Settings::parse("sound-mapping,step").is_err());
+    assert!(Settings::parse("sound-mapping,jump,step").is_err());
+    assert!(Settings::parse("sound-mapping,step,boing").is_err());
+    assert!(Settings::parse("sound-mapping,step,step|").is_err());
+}
@@ -0,0 +1,143 @@
+use crate::game::history::Attempt;
+use crate::game::level::LevelPack;
+
+#[cfg(test)]
+mod tests;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LevelPackStatistics {
+    id: String,
+    level_count: usize,
+    cleared_level_count: usize,
+    best_time_sum: Option<u64>,
+    best_moves_sum: Option<u32>,
+}
+
+impl LevelPackStatistics {
+    pub fn new(level_pack: &LevelPack) -> Self {
+        Self {
+            id: level_pack.id().to_string(),
+            level_count: level_pack.level_count(),
+            cleared_level_count: level_pack.levels().iter().
+                    filter(|level| level.best_time().is_some() || level.best_moves().is_some()).
+                    count(),
+            best_time_sum: level_pack.level_pack_best_time_sum(),
+            best_moves_sum: level_pack.level_pack_best_moves_sum(),
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn level_count(&self) -> usize {
+        self.level_count
+    }
+
+    pub fn cleared_level_count(&self) -> usize {
+        self.cleared_level_count
+    }
+
+    //Only available if all levels of the level pack were cleared
+    pub fn best_time_sum(&self) -> Option<u64> {
+        self.best_time_sum
+    }
+
+    //Only available if all levels of the level pack were cleared
+    pub fn best_moves_sum(&self) -> Option<u32> {
+        self.best_moves_sum
+    }
+}
+
+//Progress of the save games and totals of the attempt history of all level packs
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Statistics {
+    level_packs: Vec<LevelPackStatistics>,
+
+    play_time: u64,
+    moves: u64,
+    undo_count: u64,
+    reset_count: u64,
+
+    found_secret_count: usize,
+    secret_count: usize,
+}
+
+impl Statistics {
+    pub fn new(found_secret_count: usize, secret_count: usize) -> Self {
+        Self {
+            found_secret_count,
+            secret_count,
+
+            ..Default::default()
+        }
+    }
+
+    pub fn add_level_pack(&mut self, level_pack: &LevelPack, attempts: &[Attempt]) {
+        self.level_packs.push(LevelPackStatistics::new(level_pack));
+
+        for attempt in attempts {
+            self.play_time += attempt.time();
+            self.moves += attempt.moves() as u64;
+            self.undo_count += attempt.undo_count() as u64;
+            self.reset_count += attempt.reset_count() as u64;
+        }
+    }
+
+    pub fn level_packs(&self) -> &[LevelPackStatistics] {
+        &self.level_packs
+    }
+
+    pub fn level_count(&self) -> usize {
+        self.level_packs.iter().map(|level_pack| level_pack.level_count).sum()
+    }
+
+    pub fn cleared_level_count(&self) -> usize {
+        self.level_packs.iter().map(|level_pack| level_pack.cleared_level_count).sum()
+    }
+
+    //Percentage of cleared levels of all level packs
+    pub fn completion_percentage(&self) -> f64 {
+        let level_count = self.level_count();
+        if level_count == 0 {
+            return 0.0;
+        }
+
+        self.cleared_level_count() as f64 * 100.0 / level_count as f64
+    }
+
+    //Sum of the best time sums of all completed level packs
+    pub fn best_time_sum(&self) -> u64 {
+        self.level_packs.iter().filter_map(|level_pack| level_pack.best_time_sum).sum()
+    }
+
+    //Sum of the best moves sums of all completed level packs
+    pub fn best_moves_sum(&self) -> u64 {
+        self.level_packs.iter().filter_map(|level_pack| level_pack.best_moves_sum.map(|sum| sum as u64)).sum()
+    }
+
+    //Time of all recorded attempts
+    pub fn play_time(&self) -> u64 {
+        self.play_time
+    }
+
+    pub fn moves(&self) -> u64 {
+        self.moves
+    }
+
+    pub fn undo_count(&self) -> u64 {
+        self.undo_count
+    }
+
+    pub fn reset_count(&self) -> u64 {
+        self.reset_count
+    }
+
+    pub fn found_secret_count(&self) -> usize {
+        self.found_secret_count
+    }
+
+    pub fn secret_count(&self) -> usize {
+        self.secret_count
+    }
+}
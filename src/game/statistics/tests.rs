@@ -0,0 +1,40 @@
+use super::*;
+use crate::game::history::AttemptResult;
+
+const LVL_DATA: &str = "Levels: 2\n\nw: 3, h: 3\n###\n#P#\n###\n\nw: 3, h: 3\n###\n#P#\n###\n";
+
+#[test]
+fn aggregate_statistics() {
+    let mut completed_level_pack = LevelPack::read_editor_level_pack("completed", "completed.lvl", LVL_DATA).unwrap();
+    completed_level_pack.update_stats(0, 1000, 10).unwrap();
+    completed_level_pack.update_stats(1, 2000, 20).unwrap();
+
+    let mut started_level_pack = LevelPack::read_editor_level_pack("started", "started.lvl", LVL_DATA).unwrap();
+    started_level_pack.update_stats(1, 3000, 30).unwrap();
+
+    let mut statistics = Statistics::new(1, 1);
+    statistics.add_level_pack(&completed_level_pack, &[
+        Attempt::new(0, 1500, 12, 2, 3, 1, AttemptResult::Completed),
+        Attempt::new(0, 500, 4, 0, 1, 0, AttemptResult::Abandoned),
+    ]);
+    statistics.add_level_pack(&started_level_pack, &[]);
+
+    assert_eq!(statistics.level_count(), 4);
+    assert_eq!(statistics.cleared_level_count(), 3);
+    assert_eq!(statistics.completion_percentage(), 75.0);
+
+    assert_eq!(statistics.level_packs()[0].best_time_sum(), Some(3000));
+    assert_eq!(statistics.level_packs()[1].best_time_sum(), None);
+    assert_eq!(statistics.best_time_sum(), 3000);
+    assert_eq!(statistics.best_moves_sum(), 30);
+
+    assert_eq!(statistics.play_time(), 2000);
+    assert_eq!(statistics.moves(), 16);
+    assert_eq!(statistics.undo_count(), 4);
+    assert_eq!(statistics.reset_count(), 1);
+}
+
+#[test]
+fn completion_percentage_without_levels() {
+    assert_eq!(Statistics::new(0, 1).completion_percentage(), 0.0);
+}
@@ -0,0 +1,434 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use std::str::FromStr;
+use crate::game::level::{Level, Tile};
+use crate::game::save_game;
+
+#[cfg(test)]
+mod tests;
+
+const VERSION_1: &str = "v1";
+
+//The end marker is written as the last line in order to detect partially written files
+const END_MARKER: &str = "end";
+
+pub const SAVE_STATE_SLOT_COUNT: usize = 4;
+
+pub type SaveStateSlots = [Option<SaveState>; SAVE_STATE_SLOT_COUNT];
+
+//State of the level after a move: Only the tiles which differ from the original level are stored
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SuspendedState {
+    player_pos: (usize, usize),
+    moves: u32,
+    pushes: u32,
+    changed_tiles: Vec<(usize, usize, Tile)>,
+}
+
+impl SuspendedState {
+    pub fn new(level_original: &Level, level: &Level, player_pos: (usize, usize), moves: u32, pushes: u32) -> Self {
+        let changed_tiles = level.tiles().iter().
+                zip(level_original.tiles()).
+                enumerate().
+                filter(|(_, (tile, tile_original))| tile != tile_original).
+                map(|(i, (tile, _))| (i % level.width(), i / level.width(), tile.clone())).
+                collect();
+
+        Self {
+            player_pos,
+            moves,
+            pushes,
+            changed_tiles,
+        }
+    }
+
+    pub fn player_pos(&self) -> (usize, usize) {
+        self.player_pos
+    }
+
+    pub fn moves(&self) -> u32 {
+        self.moves
+    }
+
+    pub fn pushes(&self) -> u32 {
+        self.pushes
+    }
+
+    pub fn to_level(&self, level_original: &Level) -> Result<Level, SuspendError> {
+        let (player_x, player_y) = self.player_pos;
+        if player_x >= level_original.width() || player_y >= level_original.height() {
+            return Err(SuspendError::new(format!("Player position ({}, {}) is outside of the level", player_x, player_y)));
+        }
+
+        let mut level = level_original.clone();
+        for (x, y, tile) in self.changed_tiles.iter() {
+            if *x >= level.width() || *y >= level.height() {
+                return Err(SuspendError::new(format!("Tile position ({}, {}) is outside of the level", x, y)));
+            }
+
+            level.set_tile(*x, *y, tile.clone());
+        }
+
+        Ok(level)
+    }
+}
+
+impl Display for SuspendedState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "state,{},{},{},{}", self.player_pos.0, self.player_pos.1, self.moves, self.pushes)?;
+
+        for (x, y, tile) in self.changed_tiles.iter() {
+            write!(f, ",{}:{}:{}", x, y, tile.to_ascii() as char)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for SuspendedState {
+    type Err = SuspendError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid_state = || SuspendError::new(format!("Invalid state \"{s}\""));
+
+        let tokens = s.split(",").collect::<Vec<_>>();
+        let ["state", player_x, player_y, moves, pushes, changed_tiles @ ..] = &tokens[..] else {
+            return Err(invalid_state());
+        };
+
+        let player_pos = usize::from_str(player_x).ok().
+                zip(usize::from_str(player_y).ok()).
+                ok_or_else(invalid_state)?;
+        let moves = u32::from_str(moves).map_err(|_| invalid_state())?;
+        let pushes = u32::from_str(pushes).map_err(|_| invalid_state())?;
+
+        let changed_tiles = changed_tiles.iter().
+                map(|changed_tile| {
+                    let [x, y, tile] = changed_tile.split(":").collect::<Vec<_>>()[..] else {
+                        return Err(invalid_state());
+                    };
+
+                    let [tile] = tile.as_bytes() else {
+                        return Err(invalid_state());
+                    };
+
+                    Ok((
+                        usize::from_str(x).map_err(|_| invalid_state())?,
+                        usize::from_str(y).map_err(|_| invalid_state())?,
+                        Tile::from_ascii(*tile).map_err(|_| invalid_state())?,
+                    ))
+                }).
+                collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            player_pos,
+            moves,
+            pushes,
+            changed_tiles,
+        })
+    }
+}
+
+//An in-progress level which is stored if the level is left before it was completed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SuspendedLevel {
+    level_pack_id: String,
+    level_index: usize,
+    level_hash: u64,
+
+    time: u64,
+    undo_count: u32,
+    reset_count: u32,
+
+    //Contains the current branch of the undo history (Including states which can be redone)
+    states: Vec<SuspendedState>,
+    current_state_index: usize,
+}
+
+impl SuspendedLevel {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        level_pack_id: impl Into<String>, level_index: usize, level_hash: u64, time: u64, undo_count: u32,
+        reset_count: u32, states: Vec<SuspendedState>, current_state_index: usize,
+    ) -> Self {
+        Self {
+            level_pack_id: level_pack_id.into(),
+            level_index,
+            level_hash,
+
+            time,
+            undo_count,
+            reset_count,
+
+            states,
+            current_state_index,
+        }
+    }
+
+    //Returns None if no level is suspended
+    pub fn read(path: &Path) -> Result<Option<Self>, SuspendError> {
+        if !std::fs::exists(path).map_err(|err| SuspendError::new(format!("Cannot check suspended level: {err}")))? {
+            return Ok(None);
+        }
+
+        let suspended_level_data = std::fs::read_to_string(path).
+                map_err(|err| SuspendError::new(format!("Cannot read suspended level: {err}")))?;
+
+        Self::parse(&suspended_level_data).map(Some)
+    }
+
+    pub fn parse(suspended_level_data: &str) -> Result<Self, SuspendError> {
+        let lines = suspended_level_data.lines().
+                map(|line| line.trim()).
+                collect::<Vec<_>>();
+
+        let [VERSION_1, level_pack_id, level, time, undo_count, reset_count, current_state_index, states @ .., END_MARKER] = &lines[..] else {
+            return Err(SuspendError::new("The suspended level is incomplete or of an unknown version"));
+        };
+
+        let level_pack_id = Self::parse_value(level_pack_id, "level-pack")?;
+
+        let (level_index, level_hash) = Self::parse_value(level, "level")?.
+                split_once(",").
+                and_then(|(level_index, level_hash)| usize::from_str(level_index).ok().
+                        zip(u64::from_str_radix(level_hash, 16).ok())).
+                ok_or_else(|| SuspendError::new(format!("Invalid level \"{level}\"")))?;
+
+        let time = Self::parse_number(time, "time")?;
+        let undo_count = Self::parse_number(undo_count, "undo-count")?;
+        let reset_count = Self::parse_number(reset_count, "reset-count")?;
+        let current_state_index = Self::parse_number(current_state_index, "current-state")?;
+
+        let states = states.iter().
+                map(|state| SuspendedState::from_str(state)).
+                collect::<Result<Vec<_>, _>>()?;
+        if current_state_index >= states.len() {
+            return Err(SuspendError::new(format!(
+                "Invalid current state {} (State count: {})",
+                current_state_index,
+                states.len(),
+            )));
+        }
+
+        Ok(Self {
+            level_pack_id: level_pack_id.to_string(),
+            level_index,
+            level_hash,
+
+            time,
+            undo_count,
+            reset_count,
+
+            states,
+            current_state_index,
+        })
+    }
+
+    fn parse_value<'a>(line: &'a str, key: &str) -> Result<&'a str, SuspendError> {
+        line.strip_prefix(key).
+                and_then(|value| value.strip_prefix(",")).
+                ok_or_else(|| SuspendError::new(format!("Expected \"{key}\" but got \"{line}\"")))
+    }
+
+    fn parse_number<T: FromStr>(line: &str, key: &str) -> Result<T, SuspendError> {
+        let value = Self::parse_value(line, key)?;
+
+        T::from_str(value).map_err(|_| SuspendError::new(format!("Invalid {key} \"{value}\"")))
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        save_game::write_atomically(path, &self.to_string())
+    }
+
+    pub fn level_pack_id(&self) -> &str {
+        &self.level_pack_id
+    }
+
+    pub fn level_index(&self) -> usize {
+        self.level_index
+    }
+
+    pub fn level_hash(&self) -> u64 {
+        self.level_hash
+    }
+
+    pub fn time(&self) -> u64 {
+        self.time
+    }
+
+    pub fn undo_count(&self) -> u32 {
+        self.undo_count
+    }
+
+    pub fn reset_count(&self) -> u32 {
+        self.reset_count
+    }
+
+    pub fn states(&self) -> &[SuspendedState] {
+        &self.states
+    }
+
+    pub fn current_state_index(&self) -> usize {
+        self.current_state_index
+    }
+}
+
+impl Display for SuspendedLevel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}", VERSION_1)?;
+        writeln!(f, "level-pack,{}", self.level_pack_id)?;
+        writeln!(f, "level,{},{:016x}", self.level_index, self.level_hash)?;
+        writeln!(f, "time,{}", self.time)?;
+        writeln!(f, "undo-count,{}", self.undo_count)?;
+        writeln!(f, "reset-count,{}", self.reset_count)?;
+        writeln!(f, "current-state,{}", self.current_state_index)?;
+
+        for state in self.states.iter() {
+            writeln!(f, "{}", state)?;
+        }
+
+        writeln!(f, "{}", END_MARKER)
+    }
+}
+
+//Snapshot of a level which is stored in a numbered slot
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SaveState {
+    time: u64,
+    state: SuspendedState,
+}
+
+impl SaveState {
+    pub fn new(time: u64, state: SuspendedState) -> Self {
+        Self {
+            time,
+            state,
+        }
+    }
+
+    pub fn time(&self) -> u64 {
+        self.time
+    }
+
+    pub fn state(&self) -> &SuspendedState {
+        &self.state
+    }
+}
+
+//Save state lines: "level number,level hash,slot,time,state"
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SaveStateEntry {
+    level_number: usize,
+    level_hash: u64,
+    slot: usize,
+    save_state: SaveState,
+}
+
+impl Display for SaveStateEntry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f, "{},{:016x},{},{},{}",
+            self.level_number,
+            self.level_hash,
+            self.slot,
+            self.save_state.time,
+            self.save_state.state,
+        )
+    }
+}
+
+impl FromStr for SaveStateEntry {
+    type Err = SuspendError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid_save_state = || SuspendError::new(format!("Invalid save state \"{s}\""));
+
+        let tokens = s.splitn(5, ",").collect::<Vec<_>>();
+        let [level_number, level_hash, slot, time, state] = tokens[..] else {
+            return Err(invalid_save_state());
+        };
+
+        let slot = usize::from_str(slot).map_err(|_| invalid_save_state())?;
+        if slot >= SAVE_STATE_SLOT_COUNT {
+            return Err(invalid_save_state());
+        }
+
+        Ok(Self {
+            level_number: usize::from_str(level_number).map_err(|_| invalid_save_state())?,
+            level_hash: u64::from_str_radix(level_hash, 16).map_err(|_| invalid_save_state())?,
+            slot,
+            save_state: SaveState {
+                time: u64::from_str(time).map_err(|_| invalid_save_state())?,
+                state: SuspendedState::from_str(state)?,
+            },
+        })
+    }
+}
+
+fn read_save_state_entries(path: &Path) -> Result<Vec<SaveStateEntry>, Box<dyn Error>> {
+    if !std::fs::exists(path)? {
+        return Ok(Vec::new());
+    }
+
+    let save_states_data = std::fs::read_to_string(path)?;
+
+    //Invalid lines are skipped
+    Ok(save_states_data.lines().
+            filter_map(|line| SaveStateEntry::from_str(line.trim()).ok()).
+            collect())
+}
+
+//Save states are matched to the level by the level hash (Save states of changed levels are not returned)
+pub fn read_save_states(path: &Path, level_hash: u64) -> Result<SaveStateSlots, Box<dyn Error>> {
+    let mut save_states = SaveStateSlots::default();
+    for entry in read_save_state_entries(path)?.into_iter().
+            filter(|entry| entry.level_hash == level_hash) {
+        save_states[entry.slot] = Some(entry.save_state);
+    }
+
+    Ok(save_states)
+}
+
+//Replaces the save state in the slot of the level
+pub fn write_save_state(path: &Path, level_number: usize, level_hash: u64, slot: usize, save_state: &SaveState) -> Result<(), Box<dyn Error>> {
+    if slot >= SAVE_STATE_SLOT_COUNT {
+        return Err(Box::new(SuspendError::new(format!("Invalid slot {} (Max: {})", slot + 1, SAVE_STATE_SLOT_COUNT))));
+    }
+
+    let mut entries = read_save_state_entries(path)?;
+    entries.retain(|entry| entry.level_hash != level_hash || entry.slot != slot);
+    entries.push(SaveStateEntry {
+        level_number,
+        level_hash,
+        slot,
+        save_state: save_state.clone(),
+    });
+
+    let mut save_states_data = String::new();
+    for entry in entries {
+        save_states_data += &entry.to_string();
+        save_states_data += "\n";
+    }
+
+    save_game::write_atomically(path, &save_states_data)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SuspendError {
+    message: String
+}
+
+impl SuspendError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self { message: message.into() }
+    }
+}
+
+impl Display for SuspendError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl Error for SuspendError {}
@@ -0,0 +1,136 @@
+use super::*;
+use crate::game::test_utils::TempDir;
+
+const LEVEL: &str = "w: 5, h: 3\n#####\n#P@x#\n#####\n";
+
+fn suspended_level() -> SuspendedLevel {
+    let level_original = Level::from_str(LEVEL).unwrap();
+
+    let mut level = level_original.clone();
+    level.set_tile(1, 1, Tile::Empty);
+    level.set_tile(2, 1, Tile::Player);
+    level.set_tile(3, 1, Tile::BoxInGoal);
+
+    SuspendedLevel::new("main", 4, 0xabc, 12345, 2, 1, vec![
+        SuspendedState::new(&level_original, &level_original, (1, 1), 0, 0),
+        SuspendedState::new(&level_original, &level, (2, 1), 1, 1),
+    ], 1)
+}
+
+#[test]
+fn suspended_state_round_trip() {
+    let level_original = Level::from_str(LEVEL).unwrap();
+
+    let mut level = level_original.clone();
+    level.set_tile(1, 1, Tile::Empty);
+    level.set_tile(2, 1, Tile::Player);
+    level.set_tile(3, 1, Tile::BoxInGoal);
+
+    let state = SuspendedState::new(&level_original, &level, (2, 1), 3, 1);
+    assert_eq!(state.to_string(), "state,2,1,3,1,1:1:-,2:1:P,3:1:+");
+    assert_eq!(SuspendedState::from_str(&state.to_string()), Ok(state.clone()));
+
+    assert_eq!(state.to_level(&level_original).unwrap().to_str(), level.to_str());
+
+    let state = SuspendedState::new(&level_original, &level_original, (1, 1), 0, 0);
+    assert_eq!(state.to_string(), "state,1,1,0,0");
+    assert_eq!(state.to_level(&level_original).unwrap().to_str(), level_original.to_str());
+}
+
+#[test]
+fn suspended_state_outside_of_level() {
+    let level_original = Level::from_str(LEVEL).unwrap();
+
+    assert!(SuspendedState::from_str("state,5,1,0,0").unwrap().to_level(&level_original).is_err());
+    assert!(SuspendedState::from_str("state,1,1,0,0,7:1:-").unwrap().to_level(&level_original).is_err());
+}
+
+#[test]
+fn invalid_suspended_state() {
+    assert!(SuspendedState::from_str("state,1,1,0").is_err());
+    assert!(SuspendedState::from_str("state,1,1,0,0,1:1").is_err());
+    assert!(SuspendedState::from_str("state,1,1,0,0,1:1:?").is_err());
+    assert!(SuspendedState::from_str("state,1,1,0,0,1:1:--").is_err());
+    assert!(SuspendedState::from_str("move,1,1,0,0").is_err());
+}
+
+#[test]
+fn suspended_level_round_trip() {
+    let suspended_level = suspended_level();
+
+    assert_eq!(
+        suspended_level.to_string(),
+        "v1\nlevel-pack,main\nlevel,4,0000000000000abc\ntime,12345\nundo-count,2\nreset-count,1\ncurrent-state,1\n\
+        state,1,1,0,0\nstate,2,1,1,1,1:1:-,2:1:P,3:1:+\nend\n",
+    );
+    assert_eq!(SuspendedLevel::parse(&suspended_level.to_string()), Ok(suspended_level));
+}
+
+#[test]
+fn invalid_suspended_level() {
+    let suspended_level_data = suspended_level().to_string();
+
+    //Incomplete
+    assert!(SuspendedLevel::parse(&suspended_level_data[..suspended_level_data.len() - 4]).is_err());
+    assert!(SuspendedLevel::parse("").is_err());
+
+    //Invalid current state
+    assert!(SuspendedLevel::parse(&suspended_level_data.replace("current-state,1", "current-state,2")).is_err());
+
+    //Invalid values
+    assert!(SuspendedLevel::parse(&suspended_level_data.replace("time,12345", "time,-1")).is_err());
+    assert!(SuspendedLevel::parse(&suspended_level_data.replace("level,4,", "level,4;")).is_err());
+    assert!(SuspendedLevel::parse(&suspended_level_data.replace("undo-count", "undos")).is_err());
+}
+
+#[test]
+fn read_and_write_suspended_level() {
+    let temp_dir = TempDir::new("suspended-level");
+    let path = temp_dir.join("suspended.sav");
+
+    assert_eq!(SuspendedLevel::read(&path), Ok(None));
+
+    let suspended_level = suspended_level();
+    suspended_level.write(&path).unwrap();
+    assert_eq!(SuspendedLevel::read(&path), Ok(Some(suspended_level)));
+}
+
+#[test]
+fn save_state_entry_round_trip() {
+    let entry = SaveStateEntry {
+        level_number: 2,
+        level_hash: 0xff,
+        slot: 3,
+        save_state: SaveState::new(4321, SuspendedState::from_str("state,2,1,1,1,1:1:-,2:1:P,3:1:+").unwrap()),
+    };
+
+    assert_eq!(entry.to_string(), "2,00000000000000ff,3,4321,state,2,1,1,1,1:1:-,2:1:P,3:1:+");
+    assert_eq!(SaveStateEntry::from_str(&entry.to_string()), Ok(entry));
+
+    //Invalid slot
+    assert!(SaveStateEntry::from_str("2,00000000000000ff,4,4321,state,2,1,1,1").is_err());
+    assert!(SaveStateEntry::from_str("2,00000000000000ff,3,4321").is_err());
+}
+
+#[test]
+fn read_and_write_save_states() {
+    let temp_dir = TempDir::new("save-states");
+    let path = temp_dir.join("save-states.lvl.slots");
+
+    assert_eq!(read_save_states(&path, 1).unwrap(), SaveStateSlots::default());
+
+    let first = SaveState::new(1000, SuspendedState::from_str("state,1,1,0,0").unwrap());
+    let second = SaveState::new(2000, SuspendedState::from_str("state,2,1,1,1,1:1:-,2:1:P,3:1:+").unwrap());
+    write_save_state(&path, 1, 1, 0, &first).unwrap();
+    write_save_state(&path, 1, 1, 2, &first).unwrap();
+    write_save_state(&path, 2, 2, 0, &second).unwrap();
+
+    //Replace slot
+    write_save_state(&path, 1, 1, 2, &second).unwrap();
+
+    assert_eq!(read_save_states(&path, 1).unwrap(), [Some(first), None, Some(second.clone()), None]);
+    assert_eq!(read_save_states(&path, 2).unwrap(), [Some(second.clone()), None, None, None]);
+    assert_eq!(read_save_states(&path, 3).unwrap(), SaveStateSlots::default());
+
+    assert!(write_save_state(&path, 1, 1, SAVE_STATE_SLOT_COUNT, &second).is_err());
+}
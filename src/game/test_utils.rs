@@ -0,0 +1,27 @@
+use std::path::{Path, PathBuf};
+
+//Directory for the files of a test which is removed with all its files if it is dropped
+pub struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    //The name must be unique between all tests (Tests are run in parallel)
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("ConsoleSokoban-test-{}-{}", std::process::id(), name));
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).unwrap();
+
+        Self { path }
+    }
+
+    pub fn join(&self, path: impl AsRef<Path>) -> PathBuf {
+        self.path.join(path)
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}